@@ -1,11 +1,19 @@
-use std::ffi::OsString;
+use std::{
+	ffi::OsString,
+	path::PathBuf,
+};
 
 use clap::{clap_app, crate_authors, crate_version, crate_description};
 
 use enumset::EnumSet;
 
 use crate::{
-	modules::{metasource::MetaSources, tracksource::TrackSources},
+	modules::{
+		item::progress::LineFormat,
+		metasource::MetaSources,
+		tracksource::TrackSources,
+	},
+	net::url::Url,
 	track::Track,
 };
 
@@ -17,19 +25,67 @@ mod tests;
 pub enum Command {
 	Help(Box<str>),
 	Version(Box<str>),
-	Download(Args)
+	Download(Args),
+	Batch(BatchArgs),
+}
+
+
+/// What to download.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Input {
+	Track(Track),
+	/// A metasource page, from which the track is read.
+	Url(Url),
+	/// A local audio file, from which the duration is read, along with the track id when
+	/// not given.
+	Reference {
+		path: PathBuf,
+		track: Option<Track>,
+	},
+}
+
+
+/// How to display progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ui {
+	Tui,
+	Lines(LineFormat),
 }
 
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Args {
 	pub log_level: log::Level,
-	pub track: Track,
-	pub metasources: EnumSet<MetaSources>,
+	pub ui: Ui,
+	pub input: Input,
+	pub output: Option<PathBuf>,
+	pub events: Option<PathBuf>,
+	pub force: bool,
+	pub no_cache: bool,
+	/// None when no metasource is given, meaning all of them.
+	pub metasources: Option<EnumSet<MetaSources>>,
 	pub tracksources: EnumSet<TrackSources>,
 }
 
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BatchArgs {
+	pub log_level: log::Level,
+	pub tracklist: PathBuf,
+	pub jobs: usize,
+	pub output: Option<PathBuf>,
+	pub events: Option<PathBuf>,
+	pub force: bool,
+	pub no_cache: bool,
+	/// None when no metasource is given, meaning all of them.
+	pub metasources: Option<EnumSet<MetaSources>>,
+	pub tracksources: EnumSet<TrackSources>,
+}
+
+
+const DEFAULT_JOBS: usize = 4;
+
+
 pub fn parse<A, T>(args: A) -> clap::Result<Command>
 where
 	A: IntoIterator<Item = T>,
@@ -40,26 +96,69 @@ where
 			(version: crate_version!())
 			(author: crate_authors!())
 			(about: crate_description!())
-			(@arg verbose: -v ... "level of logging information")
-			(@arg id: +required "the track id to download")
+			(@setting SubcommandsNegateReqs)
+			(@arg verbose: -v ... +global "level of logging information")
+			(@arg id: required_unless[reference] "the track id to download, or the url of a beatport or bandcamp track page")
 			(@arg duration: -d --duration +takes_value "specify the track duration")
+			(@arg reference: -r --reference +takes_value conflicts_with[duration] "mp3 or m4a file to read the track duration, and the id if not given, from")
+			(@arg output: -o --output +takes_value +global "directory to save the downloaded tracks in")
+			(@arg no_tui: --("no-tui") "print progress as plain lines instead of the interactive interface")
+			(@arg json: --json conflicts_with[no_tui] "print progress as JSON lines instead of the interactive interface")
+			(@arg events: --events +takes_value +global "file to write progress events to, as JSON lines")
+			(@arg force: -f --force +global "download tracks even if they are in the download history")
+			(@arg no_cache: --("no-cache") +global "search the web even if the results are cached")
 			// metasources:
-			(@arg beatport: --beatport "Use the beatport module")
-			(@arg bandcamp: --bandcamp "Use the bandcamp module")
+			(@arg beatport: --beatport +global "Use the beatport module")
+			(@arg bandcamp: --bandcamp +global "Use the bandcamp module")
+			(@arg musicbrainz: --musicbrainz +global "Use the musicbrainz module")
+			(@arg discogs: --discogs +global "Use the discogs module")
 			// tracksources:
-			(@arg slider:  --slider  "Use the slider module")
-			(@arg music2k: --music2k "Use the music2k module")
-			(@arg zippy:   --zippy   "Use the zippy module")
+			(@arg slider:  --slider  +global "Use the slider module")
+			(@arg music2k: --music2k +global "Use the music2k module")
+			(@arg zippy:   --zippy   +global "Use the zippy module")
+			(@arg plugin:  --plugin  +global "Use the configured plugin commands")
+			(@arg library: --library +global "Use the local music library")
+			(@subcommand batch =>
+				(about: "Download every track listed in a file")
+				(@arg tracklist: +required "file with one track id per line, optionally followed by the duration, or a CSV or JSON playlist export")
+				(@arg jobs: -j --jobs +takes_value "how many tracks to download concurrently")
+			)
 	);
 
 	match app.get_matches_from_safe(args) {
 		Ok(matches) => {
-			let track = parse_track(&matches)?;
+			if let Some(matches) = matches.subcommand_matches("batch") {
+				return Ok(
+					Command::Batch(
+						BatchArgs {
+							tracklist: matches
+								.value_of_os("tracklist")
+								.expect("tracklist parameter is required")
+								.into(),
+							jobs: parse_jobs(matches)?,
+							output: parse_output(matches),
+							events: parse_events(matches),
+							force: matches.is_present("force"),
+							no_cache: matches.is_present("no_cache"),
+							metasources: parse_metasources(matches),
+							tracksources: parse_tracksources(matches),
+							log_level: parse_log_level(matches),
+						}
+					)
+				);
+			}
+
+			let input = parse_input(&matches)?;
 
 			Ok(
 				Command::Download(
 					Args {
-						track,
+						ui: parse_ui(&matches),
+						input,
+						output: parse_output(&matches),
+						events: parse_events(&matches),
+						force: matches.is_present("force"),
+						no_cache: matches.is_present("no_cache"),
 						metasources: parse_metasources(&matches),
 						tracksources: parse_tracksources(&matches),
 						log_level: parse_log_level(&matches),
@@ -90,13 +189,59 @@ fn parse_log_level(matches: &clap::ArgMatches) -> log::Level {
 }
 
 
-fn parse_track(matches: &clap::ArgMatches) -> clap::Result<Track> {
+fn parse_input(matches: &clap::ArgMatches) -> clap::Result<Input> {
+	let reference = matches.value_of_os("reference");
+
+	let id = match (matches.value_of("id"), reference) {
+		(Some(id), _) => id,
+		(None, Some(path)) => return Ok(
+			Input::Reference {
+				path: path.into(),
+				track: None,
+			}
+		),
+		(None, None) => panic!("id parameter is required without a reference"),
+	};
+
+	let url = id
+		.parse::<Url>()
+		.ok()
+		.filter(
+			|url| url.scheme() == "http" || url.scheme() == "https"
+		);
+
+	match (url, reference) {
+		(Some(_), Some(_)) => Err(
+			clap::Error::with_description(
+				"the track is read from the page, a reference can't be given with a url",
+				clap::ErrorKind::ArgumentConflict
+			)
+		),
+
+		(Some(_), None) if matches.is_present("duration") => Err(
+			clap::Error::with_description(
+				"the duration is read from the page, it can't be given with a url",
+				clap::ErrorKind::ArgumentConflict
+			)
+		),
+
+		(Some(url), _) => Ok(Input::Url(url)),
+
+		(None, Some(path)) => Ok(
+			Input::Reference {
+				path: path.into(),
+				track: Some(parse_track(id, matches)?),
+			}
+		),
+
+		(None, None) => parse_track(id, matches).map(Input::Track),
+	}
+}
+
+
+fn parse_track(id: &str, matches: &clap::ArgMatches) -> clap::Result<Track> {
 	let mut track = Track
-		::new(
-			matches
-				.value_of("id")
-				.expect("id parameter is required")
-		)
+		::new(id)
 		.map_err(
 			|error| clap::Error::with_description(
 				&format!("invalid track id: {}", error),
@@ -121,7 +266,51 @@ fn parse_track(matches: &clap::ArgMatches) -> clap::Result<Track> {
 }
 
 
-fn parse_metasources(matches: &clap::ArgMatches) -> EnumSet<MetaSources> {
+fn parse_jobs(matches: &clap::ArgMatches) -> clap::Result<usize> {
+	match matches.value_of("jobs") {
+		None => Ok(DEFAULT_JOBS),
+		Some(jobs) => jobs
+			.parse()
+			.ok()
+			.filter(|&jobs| jobs > 0)
+			.ok_or_else(
+				|| clap::Error::with_description(
+					&format!("invalid number of jobs: {}", jobs),
+					clap::ErrorKind::ValueValidation
+				)
+			),
+	}
+}
+
+
+fn parse_ui(matches: &clap::ArgMatches) -> Ui {
+	if matches.is_present("json") {
+		Ui::Lines(LineFormat::Json)
+	}
+	else if matches.is_present("no_tui") {
+		Ui::Lines(LineFormat::Plain)
+	}
+	else {
+		Ui::Tui
+	}
+}
+
+
+fn parse_output(matches: &clap::ArgMatches) -> Option<PathBuf> {
+	matches
+		.value_of_os("output")
+		.map(Into::into)
+}
+
+
+fn parse_events(matches: &clap::ArgMatches) -> Option<PathBuf> {
+	matches
+		.value_of_os("events")
+		.map(Into::into)
+}
+
+
+fn parse_metasources(matches: &clap::ArgMatches) -> Option<EnumSet<MetaSources>> {
 	let mut sources = EnumSet::new();
 
 	if matches.is_present("beatport") {
@@ -132,11 +321,19 @@ fn parse_metasources(matches: &clap::ArgMatches) -> EnumSet<MetaSources> {
 		sources.insert(MetaSources::Bandcamp);
 	}
 
+	if matches.is_present("musicbrainz") {
+		sources.insert(MetaSources::MusicBrainz);
+	}
+
+	if matches.is_present("discogs") {
+		sources.insert(MetaSources::Discogs);
+	}
+
 	if sources.is_empty() {
-		EnumSet::all()
+		None
 	}
 	else {
-		sources
+		Some(sources)
 	}
 }
 
@@ -156,6 +353,14 @@ fn parse_tracksources(matches: &clap::ArgMatches) -> EnumSet<TrackSources> {
 		sources.insert(TrackSources::Zippy);
 	}
 
+	if matches.is_present("plugin") {
+		sources.insert(TrackSources::Plugin);
+	}
+
+	if matches.is_present("library") {
+		sources.insert(TrackSources::Library);
+	}
+
 	if sources.is_empty() {
 		EnumSet::all()
 	}
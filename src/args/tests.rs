@@ -2,6 +2,7 @@ use enumset::EnumSet;
 
 use crate::{
 	args,
+	modules::{item::progress::LineFormat, metasource::MetaSources, tracksource::TrackSources},
 	track::{Duration, Track},
 };
 
@@ -41,8 +42,13 @@ fn test_debug() {
 				args::Command::Download(
 					args::Args {
 						log_level: level,
-						track,
-						metasources: EnumSet::all(),
+						ui: args::Ui::Tui,
+						input: args::Input::Track(track),
+						output: None,
+						events: None,
+						force: false,
+						no_cache: false,
+						metasources: None,
 						tracksources: EnumSet::all(),
 					}
 				)
@@ -56,3 +62,159 @@ fn test_debug() {
 	test("-vvv", log::Level::Trace);
 	test("-vvvv", log::Level::Trace);
 }
+
+
+#[test]
+fn test_ui() {
+	let test = |command, ui| {
+		match command_line(command) {
+			args::Command::Download(args) => assert_eq!(args.ui, ui),
+			other => panic!("unexpected command: {:?}", other),
+		}
+	};
+
+	test("sdl 'Test - track'", args::Ui::Tui);
+	test("sdl --no-tui 'Test - track'", args::Ui::Lines(LineFormat::Plain));
+	test("sdl 'Test - track' --json", args::Ui::Lines(LineFormat::Json));
+	test("sdl --no-tui --events run.jsonl 'Test - track'", args::Ui::Lines(LineFormat::Plain));
+}
+
+
+#[test]
+fn test_url() {
+	let url = "https://www.beatport.com/track/kinetic-rampage/15161234";
+
+	match command_line(&format!("sdl {}", url)) {
+		args::Command::Download(args) => assert_eq!(
+			args.input,
+			args::Input::Url(url.parse().expect("invalid url"))
+		),
+		other => panic!("unexpected command: {:?}", other),
+	}
+
+	assert!(
+		args::parse(vec!["sdl", "-d", "6:41", url]).is_err()
+	);
+}
+
+
+#[test]
+fn test_reference() {
+	let test = |command, expected| {
+		match command_line(command) {
+			args::Command::Download(args) => assert_eq!(args.input, expected),
+			other => panic!("unexpected command: {:?}", other),
+		}
+	};
+
+	test(
+		"sdl --reference rip.mp3",
+		args::Input::Reference {
+			path: "rip.mp3".into(),
+			track: None,
+		}
+	);
+
+	test(
+		"sdl -r rip.mp3 'Test - track'",
+		args::Input::Reference {
+			path: "rip.mp3".into(),
+			track: Some(Track::new("Test - track").expect("invalid track")),
+		}
+	);
+
+	assert!(args::parse(vec!["sdl", "-r", "rip.mp3", "-d", "1:00", "Test - track"]).is_err());
+
+	let error = args::parse(vec!["sdl", "-r", "rip.mp3", "https://www.beatport.com/track/a/1"])
+		.expect_err("reference with url should conflict");
+
+	assert!(error.message.contains("reference"));
+	assert!(args::parse(vec!["sdl"]).is_err());
+}
+
+
+#[test]
+fn test_force() {
+	let test = |command, force| {
+		match command_line(command) {
+			args::Command::Download(args) => assert_eq!(args.force, force),
+			args::Command::Batch(args) => assert_eq!(args.force, force),
+			other => panic!("unexpected command: {:?}", other),
+		}
+	};
+
+	test("sdl 'Test - track'", false);
+	test("sdl -f 'Test - track'", true);
+	test("sdl 'Test - track' --force", true);
+	test("sdl --force batch tracks.txt", true);
+	test("sdl batch -f tracks.txt", true);
+}
+
+
+#[test]
+fn test_no_cache() {
+	let test = |command, no_cache| {
+		match command_line(command) {
+			args::Command::Download(args) => assert_eq!(args.no_cache, no_cache),
+			args::Command::Batch(args) => assert_eq!(args.no_cache, no_cache),
+			other => panic!("unexpected command: {:?}", other),
+		}
+	};
+
+	test("sdl 'Test - track'", false);
+	test("sdl --no-cache 'Test - track'", true);
+	test("sdl batch tracks.txt --no-cache", true);
+}
+
+
+#[test]
+fn test_metasources() {
+	let test = |command, metasources| {
+		match command_line(command) {
+			args::Command::Download(args) => assert_eq!(args.metasources, metasources),
+			args::Command::Batch(args) => assert_eq!(args.metasources, metasources),
+			other => panic!("unexpected command: {:?}", other),
+		}
+	};
+
+	test("sdl 'Test - track'", None);
+	test("sdl --discogs 'Test - track'", Some(EnumSet::only(MetaSources::Discogs)));
+	test(
+		"sdl --beatport --bandcamp --musicbrainz --discogs 'Test - track'",
+		Some(EnumSet::all())
+	);
+	test("sdl batch tracks.txt", None);
+}
+
+
+#[test]
+fn test_batch() {
+	let test = |command, jobs, output: Option<&str>, events: Option<&str>, tracksources| {
+		assert_eq!(
+			command_line(command),
+			args::Command::Batch(
+				args::BatchArgs {
+					log_level: log::Level::Info,
+					tracklist: "tracks.txt".into(),
+					jobs,
+					output: output.map(Into::into),
+					events: events.map(Into::into),
+					force: false,
+					no_cache: false,
+					metasources: None,
+					tracksources,
+				}
+			)
+		);
+	};
+
+	test("sdl batch tracks.txt", 4, None, None, EnumSet::all());
+	test("sdl batch -j 2 tracks.txt", 2, None, None, EnumSet::all());
+	test("sdl batch tracks.txt --jobs 8", 8, None, None, EnumSet::all());
+	test("sdl --slider batch tracks.txt", 4, None, None, EnumSet::only(TrackSources::Slider));
+	test("sdl batch --slider tracks.txt", 4, None, None, EnumSet::only(TrackSources::Slider));
+	test("sdl -o music batch tracks.txt", 4, Some("music"), None, EnumSet::all());
+	test("sdl batch tracks.txt --output music", 4, Some("music"), None, EnumSet::all());
+	test("sdl --events run.jsonl batch tracks.txt", 4, None, Some("run.jsonl"), EnumSet::all());
+	test("sdl batch tracks.txt --events run.jsonl", 4, None, Some("run.jsonl"), EnumSet::all());
+}
@@ -6,103 +6,451 @@ use futures::{
 };
 
 use crate::{
+	config,
+	history::{self, History},
+	rank,
+	output::{sidecar, Output},
 	track::Track,
 	modules::{
-		metasource::{Module as MetaSource, MetaSources},
-		tracksource::{Module as TrackSource, TrackSources},
+		Module,
+		item::progress::{Progress as ItemProgress, NoProgress, LineProgress, LineFormat, LineSink, Tee},
+		metasource::{
+			Module as MetaSource,
+			MetaSources,
+			reconcile::{self, Candidate},
+		},
+		tracksource::{Module as TrackSource, TrackSources, Download, Downloads},
 		beatport::SourceParams as BeatportParams,
 		bandcamp::SourceParams as BandcampParams,
-		slider::SourceProgress as SliderParams,
+		musicbrainz::SourceParams as MusicBrainzParams,
+		discogs::SourceParams as DiscogsParams,
+		slider::SourceParams as SliderParams,
 		zippy::SourceParams as ZippyParams,
-		music2k::SourceProgress as Music2kParams,
+		music2k::SourceParams as Music2kParams,
+		plugin::SourceParams as PluginParams,
+		library::{
+			Found as LibraryFound,
+			Owned as LibraryOwned,
+			SourceParams as LibraryParams,
+		},
 	},
 	util::error::{self, AggregateError},
 };
 pub use crate::modules::{
-	google::{
-		Module as Google,
-		SearchError as GoogleError,
-	},
-	beatport::{
-		Module as Beatport,
-		tui::Reporter as BeatportReporter,
-	},
-	bandcamp::{
-		Module as Bandcamp,
-		tui::Reporter as BandcampReporter,
-	},
+	websearch::Error as WebSearchError,
+	beatport::Module as Beatport,
+	bandcamp::Module as Bandcamp,
+	musicbrainz::Module as MusicBrainz,
+	discogs::Module as Discogs,
 	slider::{
 		Module as Slider,
-		tui::Reporter as SliderReporter,
-	},
-	zippy::{
-		Module as Zippy,
-		tui::Reporter as ZippyReporter,
+		SourceProgress as SliderProgress,
 	},
+	zippy::Module as Zippy,
 	music2k::{
 		Module as Music2k,
-		tui::Reporter as Music2kReporter,
+		SourceProgress as Music2kProgress,
 	},
+	plugin::Module as Plugin,
+	library::Module as Library,
 };
 
 
-#[derive(Debug)]
-pub struct Backend {
-	pub metasources: EnumSet<MetaSources>,
-	pub tracksources: EnumSet<TrackSources>,
+/// The configured web search backend, behind the results cache.
+pub type WebSearch = crate::modules::websearch::Cached<crate::modules::websearch::Backend>;
 
-	pub google: Google,
 
-	pub beatport: Beatport<Google>,
-	pub beatport_reporter: BeatportReporter<GoogleError>,
+pub type BeatportProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::beatport::Status<WebSearchError>,
+		ItemStatus = crate::modules::beatport::ItemStatus,
+	> + Send
+>;
 
-	pub bandcamp: Bandcamp<Google>,
-	pub bandcamp_reporter: BandcampReporter<GoogleError>,
 
-	pub slider: Slider,
-	pub slider_reporter: SliderReporter,
+pub type BandcampProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::bandcamp::Status<WebSearchError>,
+		ItemStatus = crate::modules::bandcamp::ItemStatus,
+	> + Send
+>;
+
+
+pub type MusicBrainzProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::musicbrainz::Status,
+		ItemStatus = crate::modules::musicbrainz::ItemStatus,
+	> + Send
+>;
+
+
+pub type DiscogsProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::discogs::Status,
+		ItemStatus = crate::modules::discogs::ItemStatus,
+	> + Send
+>;
+
+
+pub type ReconcileProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = reconcile::Status,
+		ItemStatus = reconcile::ItemStatus,
+	> + Send
+>;
+
+
+pub type ZippyProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::zippy::Status<WebSearchError>,
+		ItemStatus = crate::modules::zippy::ItemStatus,
+	> + Send
+>;
+
 
-	pub zippy: Zippy<Google>,
-	pub zippy_reporter: ZippyReporter<GoogleError>,
+pub type PluginProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::plugin::Status,
+		ItemStatus = crate::modules::plugin::ItemStatus,
+	> + Send
+>;
 
+
+pub type LibraryProgress = Box<
+	dyn ItemProgress<
+		Id = u8,
+		Item = str,
+		Status = crate::modules::library::Status,
+		ItemStatus = crate::modules::library::ItemStatus,
+	> + Send
+>;
+
+
+/// The configured modules. These are cheap to clone, so that many backends may share
+/// the same configuration.
+#[derive(Debug, Clone)]
+pub struct Modules {
+	pub websearch: WebSearch,
+	pub beatport: Beatport<WebSearch>,
+	pub bandcamp: Bandcamp<WebSearch>,
+	pub musicbrainz: MusicBrainz,
+	pub discogs: Discogs,
+	pub slider: Slider,
+	pub zippy: Zippy<WebSearch>,
 	pub music2k: Music2k,
-	pub music2k_reporter: Music2kReporter,
+	pub plugin: Plugin,
+	pub library: Library,
+}
+
+
+impl Modules {
+	pub fn load(cfg: &str) -> anyhow::Result<Self> {
+		let websearch_cfg = config::read(cfg)?;
+		let beatport_cfg = config::read(cfg)?;
+		let bandcamp_cfg = config::read(cfg)?;
+		let musicbrainz_cfg = config::read(cfg)?;
+		let discogs_cfg = config::read(cfg)?;
+		let slider_cfg = config::read(cfg)?;
+		let zippy_cfg = config::read(cfg)?;
+		let music2k_cfg = config::read(cfg)?;
+		let plugin_cfg = config::read(cfg)?;
+		let library_cfg = config::read(cfg)?;
+
+		log::debug!("websearch cfg: {:#?}", websearch_cfg);
+		log::debug!("beatport cfg: {:#?}", beatport_cfg);
+		log::debug!("bandcamp cfg: {:#?}", bandcamp_cfg);
+		log::debug!("musicbrainz cfg: {:#?}", musicbrainz_cfg);
+		log::debug!("discogs cfg: {:#?}", discogs_cfg);
+		log::debug!("slider cfg: {:#?}", slider_cfg);
+		log::debug!("zippy cfg: {:#?}", zippy_cfg);
+		log::debug!("music2k cfg: {:#?}", music2k_cfg);
+		log::debug!("plugin cfg: {:#?}", plugin_cfg);
+		log::debug!("library cfg: {:#?}", library_cfg);
+
+		Ok(
+			Self {
+				websearch: WebSearch::new(websearch_cfg),
+				beatport: Beatport::new(beatport_cfg),
+				bandcamp: Bandcamp::new(bandcamp_cfg),
+				musicbrainz: MusicBrainz::new(musicbrainz_cfg),
+				discogs: Discogs::new(discogs_cfg),
+				slider: Slider::new(slider_cfg),
+				zippy: Zippy::new(zippy_cfg),
+				music2k: Music2k::new(music2k_cfg),
+				plugin: Plugin::new(plugin_cfg),
+				library: Library::new(library_cfg),
+			}
+		)
+	}
+}
+
+
+/// Where each module reports its progress to.
+pub struct Progress {
+	pub beatport: BeatportProgress,
+	pub bandcamp: BandcampProgress,
+	pub musicbrainz: MusicBrainzProgress,
+	pub discogs: DiscogsProgress,
+	pub reconcile: ReconcileProgress,
+	pub slider: SliderProgress,
+	pub zippy: ZippyProgress,
+	pub music2k: Music2kProgress,
+	pub plugin: PluginProgress,
+	pub library: LibraryProgress,
+}
+
+
+impl Progress {
+	/// Discard all progress reports.
+	pub fn none() -> Self {
+		Self {
+			beatport: Box::new(NoProgress::new()),
+			bandcamp: Box::new(NoProgress::new()),
+			musicbrainz: Box::new(NoProgress::new()),
+			discogs: Box::new(NoProgress::new()),
+			reconcile: Box::new(NoProgress::new()),
+			slider: SliderProgress(
+				Box::new(NoProgress::new())
+			),
+			zippy: Box::new(NoProgress::new()),
+			music2k: Music2kProgress(
+				Box::new(NoProgress::new())
+			),
+			plugin: Box::new(NoProgress::new()),
+			library: Box::new(NoProgress::new()),
+		}
+	}
+}
+
+
+impl Progress {
+	/// Report progress as lines on the given sink, tagged with the track.
+	pub fn lines(format: LineFormat, sink: LineSink, track: &str) -> Self {
+		Self {
+			beatport: Box::new(LineProgress::new(track, "beatport", format, sink.clone())),
+			bandcamp: Box::new(LineProgress::new(track, "bandcamp", format, sink.clone())),
+			musicbrainz: Box::new(LineProgress::new(track, "musicbrainz", format, sink.clone())),
+			discogs: Box::new(LineProgress::new(track, "discogs", format, sink.clone())),
+			reconcile: Box::new(LineProgress::new(track, "reconcile", format, sink.clone())),
+			slider: SliderProgress(
+				Box::new(LineProgress::new(track, "slider", format, sink.clone()))
+			),
+			zippy: Box::new(LineProgress::new(track, "zippy", format, sink.clone())),
+			music2k: Music2kProgress(
+				Box::new(LineProgress::new(track, "music2k", format, sink.clone()))
+			),
+			plugin: Box::new(LineProgress::new(track, "plugin", format, sink.clone())),
+			library: Box::new(LineProgress::new(track, "library", format, sink)),
+		}
+	}
+
+
+	/// Report progress to both self and other.
+	pub fn tee(self, other: Self) -> Self {
+		Self {
+			beatport: Box::new(Tee(self.beatport, other.beatport)),
+			bandcamp: Box::new(Tee(self.bandcamp, other.bandcamp)),
+			musicbrainz: Box::new(Tee(self.musicbrainz, other.musicbrainz)),
+			discogs: Box::new(Tee(self.discogs, other.discogs)),
+			reconcile: Box::new(Tee(self.reconcile, other.reconcile)),
+			slider: SliderProgress(
+				Box::new(Tee(self.slider.0, other.slider.0))
+			),
+			zippy: Box::new(Tee(self.zippy, other.zippy)),
+			music2k: Music2kProgress(
+				Box::new(Tee(self.music2k.0, other.music2k.0))
+			),
+			plugin: Box::new(Tee(self.plugin, other.plugin)),
+			library: Box::new(Tee(self.library, other.library)),
+		}
+	}
+}
+
+
+impl std::fmt::Debug for Progress {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Progress")
+			.finish()
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Backend {
+	pub metasources: EnumSet<MetaSources>,
+	/// Whether the metasources were given in the command line, instead of all by default.
+	pub explicit_metasources: bool,
+	pub tracksources: EnumSet<TrackSources>,
+
+	pub ranking: rank::Config,
+	pub reconcile: reconcile::Config,
+	pub output: Output,
+
+	pub history: History,
+	/// Download the track even if it is in the history.
+	pub force: bool,
+
+	pub modules: Modules,
+	pub progress: Progress,
 }
 
 
 impl Backend {
-	pub async fn run(self, track: &mut Track) -> anyhow::Result<()> {
+	/// Fetch the track, returning the downloaded files. Metasource errors are only fatal when
+	/// no duration was found, and track source errors when nothing was downloaded. Fails with
+	/// `history::Present` if the track was already downloaded, unless forced, and with
+	/// `library::Owned` if it is in the library and isn't copied from there.
+	pub async fn run(self, track: &mut Track) -> anyhow::Result<Box<[Download]>> {
+		if !self.force {
+			if let Some(entry) = self.history.find(track.id())? {
+				return Err(history::Present(entry).into());
+			}
+		}
+
+		let modules = self.modules;
+		let progress = self.progress;
+
 		{
-			let mut success = false;
-
-			if !success && self.metasources.contains(MetaSources::Beatport) {
-				success = self.beatport
-					.fill_metadata(
-						track,
-						BeatportParams {
-							progress: Box::new(self.beatport_reporter),
-							// We shouldn't have to clone here, but the params was designed to be owned.
-							websearch: self.google.clone(),
-						}
-					)
-					.await?;
-			}
-
-			if !success && self.metasources.contains(MetaSources::Bandcamp) {
-				success = self.bandcamp
-					.fill_metadata(
-						track,
-						BandcampParams {
-							progress: Box::new(self.bandcamp_reporter),
-							// We shouldn't have to clone here, but the params was designed to be owned.
-							websearch: self.google.clone(),
-						}
-					)
-					.await?;
-			}
-
-			if !success {
-				anyhow::bail!("failed to fetch track duration");
+			let skipped = track.duration.is_some();
+
+			let metasources_futures = FuturesUnordered::new();
+
+			if self.metasources.contains(MetaSources::Beatport) {
+				let params = BeatportParams {
+					progress: progress.beatport,
+					// We shouldn't have to clone here, but the params was designed to be owned.
+					websearch: modules.websearch.clone(),
+				};
+
+				metasources_futures.push(
+					candidate(MetaSources::Beatport, &modules.beatport, track, params)
+				);
+			}
+
+			if self.metasources.contains(MetaSources::Bandcamp) {
+				let params = BandcampParams {
+					progress: progress.bandcamp,
+					// We shouldn't have to clone here, but the params was designed to be owned.
+					websearch: modules.websearch.clone(),
+				};
+
+				metasources_futures.push(
+					candidate(MetaSources::Bandcamp, &modules.bandcamp, track, params)
+				);
+			}
+
+			if self.metasources.contains(MetaSources::MusicBrainz) {
+				let params = MusicBrainzParams {
+					progress: progress.musicbrainz,
+				};
+
+				metasources_futures.push(
+					candidate(MetaSources::MusicBrainz, &modules.musicbrainz, track, params)
+				);
+			}
+
+			if self.metasources.contains(MetaSources::Discogs) {
+				let params = DiscogsParams {
+					progress: progress.discogs,
+					required: self.explicit_metasources,
+				};
+
+				metasources_futures.push(
+					candidate(MetaSources::Discogs, &modules.discogs, track, params)
+				);
+			}
+
+			let results: Vec<anyhow::Result<Option<Candidate>>> = metasources_futures
+				.collect()
+				.await;
+
+			let mut candidates = Vec::new();
+			let mut errors = AggregateError::default();
+
+			for result in results {
+				match result {
+					Ok(Some(candidate)) => candidates.push(candidate),
+					Ok(None) => (),
+					Err(error) => errors.extend(Some(error)),
+				}
+			}
+
+			// Completion order is arbitrary, so sort for a stable report.
+			candidates.sort_by_key(|candidate| reconcile::priority(candidate.source));
+
+			let progress = progress.reconcile;
+
+			if skipped {
+				progress.finish(&reconcile::Status::Skipped);
+			}
+			else if let Some(reconciliation) = reconcile::reconcile(&candidates, self.reconcile) {
+				if !errors.is_empty() {
+					log::warn!("some metasources failed: {}", errors);
+				}
+
+				progress.size_hint((candidates.len(), Some(candidates.len())));
+
+				for (ix, (candidate, status)) in candidates
+					.iter()
+					.zip(reconciliation.statuses.iter())
+					.enumerate()
+				{
+					let metadata = candidate.metadata.to_string();
+
+					let label = if metadata.is_empty() {
+						format!("{}: {}", candidate.source, candidate.duration)
+					}
+					else {
+						format!("{}: {} | {}", candidate.source, candidate.duration, metadata)
+					};
+
+					progress.item(ix as u8, &label);
+					progress.item_status(ix as u8, status);
+				}
+
+				let chosen = candidates.swap_remove(reconciliation.chosen);
+
+				log::info!(
+					"track duration {} from {}: {}",
+					chosen.duration,
+					chosen.source,
+					reconciliation.confidence
+				);
+
+				track.duration = Some(chosen.duration);
+				track.metadata = chosen.metadata;
+
+				progress.finish(
+					&reconcile::Status::Done {
+						confidence: reconciliation.confidence,
+						policy: self.reconcile.policy,
+						metadata: track.metadata.clone(),
+					}
+				);
+			}
+			else {
+				progress.finish(&reconcile::Status::NoCandidates);
+
+				if errors.is_empty() {
+					anyhow::bail!("failed to fetch track duration");
+				}
+				else {
+					return Err(errors.into());
+				}
 			}
 		}
 
@@ -111,42 +459,93 @@ impl Backend {
 		{
 			let track = &track;
 
+			let ranker = rank::Ranker::new(self.ranking);
+			let downloads = Downloads::new();
+
+			let found = LibraryFound::new();
+
+			// The library goes first, as owned tracks needn't be downloaded.
+			if self.tracksources.contains(TrackSources::Library) {
+				let params = LibraryParams {
+					progress: progress.library,
+					output: self.output.clone(),
+					downloads: downloads.clone(),
+					found: found.clone(),
+				};
+
+				if let Err(error) = modules.library.fetch(&track, params).await {
+					log::warn!("library search failed: {}", error);
+				}
+			}
+
+			let web = match found.take() {
+				// Reported, rather than copied.
+				Some(path) if downloads.is_empty() => return Err(LibraryOwned(path).into()),
+				Some(_) => false,
+				None => true,
+			};
+
 			let tracksources_futures = FuturesUnordered::<LocalBoxFuture<anyhow::Result<()>>>::new();
 
-			if self.tracksources.contains(TrackSources::Slider) {
-				let params = SliderParams(
-					Box::new(self.slider_reporter)
-				);
+			if web && self.tracksources.contains(TrackSources::Slider) {
+				let params = SliderParams {
+					progress: progress.slider,
+					ranking: ranker.participant(),
+					output: self.output.clone(),
+					downloads: downloads.clone(),
+				};
 
 				tracksources_futures.push(
-					self.slider
+					modules.slider
 						.fetch(&track, params)
 						.map(error::anyhow_result)
 						.boxed_local()
 				);
 			}
 
-			if self.tracksources.contains(TrackSources::Zippy) {
+			if web && self.tracksources.contains(TrackSources::Zippy) {
 				let params = ZippyParams {
-					progress: Box::new(self.zippy_reporter),
-					websearch: self.google,
+					progress: progress.zippy,
+					websearch: modules.websearch,
+					ranking: ranker.participant(),
+					output: self.output.clone(),
+					downloads: downloads.clone(),
 				};
 
 				tracksources_futures.push(
-					self.zippy
+					modules.zippy
 						.fetch(&track, params)
 						.map(error::anyhow_result)
 						.boxed_local()
 				);
 			}
 
-			if self.tracksources.contains(TrackSources::Music2k) {
-				let params = Music2kParams(
-					Box::new(self.music2k_reporter)
+			if web && self.tracksources.contains(TrackSources::Music2k) {
+				let params = Music2kParams {
+					progress: progress.music2k,
+					ranking: ranker.participant(),
+					output: self.output.clone(),
+					downloads: downloads.clone(),
+				};
+
+				tracksources_futures.push(
+					modules.music2k
+						.fetch(&track, params)
+						.map(error::anyhow_result)
+						.boxed_local()
 				);
+			}
+
+			if web && self.tracksources.contains(TrackSources::Plugin) {
+				let params = PluginParams {
+					progress: progress.plugin,
+					ranking: ranker.participant(),
+					output: self.output.clone(),
+					downloads: downloads.clone(),
+				};
 
 				tracksources_futures.push(
-					self.music2k
+					modules.plugin
 						.fetch(&track, params)
 						.map(error::anyhow_result)
 						.boxed_local()
@@ -162,8 +561,31 @@ impl Backend {
 				.collect()
 				.await;
 
-			if errors.is_empty() {
-				Ok(())
+			let downloads = downloads.take();
+
+			for download in downloads.iter() {
+				// Flagged files may be truncated or low quality, so don't skip the track later.
+				if !download.flagged {
+					let entry = history::Entry::new(track.id(), download);
+
+					if let Err(error) = self.history.record(&entry) {
+						log::warn!("failed to record download in history: {}", error);
+					}
+				}
+
+				if self.output.sidecar {
+					if let Err(error) = sidecar::write(&download.path, track) {
+						log::warn!("failed to write sidecar for {:#?}: {}", download.path, error);
+					}
+				}
+			}
+
+			if errors.is_empty() || !downloads.is_empty() {
+				if !errors.is_empty() {
+					log::warn!("some track sources failed: {}", errors);
+				}
+
+				Ok(downloads)
 			}
 			else {
 				Err(
@@ -173,3 +595,38 @@ impl Backend {
 		}
 	}
 }
+
+
+/// Run a metasource on its own copy of the track, so that many may run concurrently.
+fn candidate<'a, M>(
+	source: MetaSources,
+	module: &'a M,
+	track: &Track,
+	params: M::Params,
+) -> LocalBoxFuture<'a, anyhow::Result<Option<Candidate>>>
+where
+	M: MetaSource,
+	M::Params: 'a,
+	M::Error: Send + Sync + 'static,
+{
+	let mut track = track.clone();
+
+	async move {
+		let found = module
+			.fill_metadata(&mut track, params)
+			.await?;
+
+		Ok(
+			track.duration
+				.filter(|_| found)
+				.map(
+					|duration| Candidate {
+						source,
+						duration,
+						metadata: track.metadata,
+					}
+				)
+		)
+	}
+		.boxed_local()
+}
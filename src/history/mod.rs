@@ -0,0 +1,227 @@
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, BufRead, BufReader, ErrorKind, Write},
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use thiserror::Error;
+
+use crate::{
+	modules::tracksource::{Download, TrackSources},
+	track::{Duration, Id},
+};
+
+
+const FILENAME: &str = "history.jsonl";
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("xdg error: {0}")]
+	Xdg(xdg::BaseDirectoriesError),
+
+	#[error("io error: {0}")]
+	Io(io::Error),
+
+	#[error("serialization error: {0}")]
+	Json(serde_json::Error),
+}
+
+
+/// A successful download.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Entry {
+	/// The normalized track id.
+	pub id: Box<str>,
+	pub source: TrackSources,
+	pub url: Box<str>,
+	pub path: PathBuf,
+	pub duration: Option<Duration>,
+	/// In kbps.
+	pub bitrate: Option<u16>,
+}
+
+
+impl Entry {
+	pub fn new(id: &Id, download: &Download) -> Self {
+		Self {
+			id: normalize(id),
+			source: download.source,
+			url: download.url.clone(),
+			path: download.path.clone(),
+			duration: download.metadata.map(|metadata| metadata.duration),
+			bitrate: download.metadata.map(|metadata| metadata.bitrate),
+		}
+	}
+}
+
+
+/// The track was already downloaded in a previous run.
+#[derive(Debug, Error)]
+#[error(
+	"track already downloaded from {} to {}, use --force to download it again",
+	.0.source,
+	.0.path.display()
+)]
+pub struct Present(pub Entry);
+
+
+/// The record of past downloads, stored as JSON lines. Entries are only appended, so
+/// concurrent runs won't clobber each other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct History {
+	path: PathBuf,
+}
+
+
+impl History {
+	/// Open the history in the XDG data directory.
+	pub fn open() -> Result<Self, Error> {
+		let xdg_dirs = xdg::BaseDirectories
+			::with_prefix("slizzy")
+			.map_err(Error::Xdg)?;
+
+		let path = xdg_dirs
+			.place_data_file(FILENAME)
+			.map_err(Error::Io)?;
+
+		log::debug!("history file: {:#?}", path);
+
+		Ok(Self::at(path))
+	}
+
+
+	pub fn at<P: Into<PathBuf>>(path: P) -> Self {
+		Self {
+			path: path.into(),
+		}
+	}
+
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+
+	/// Find the latest download of the track, if any. Invalid entries are skipped.
+	pub fn find(&self, id: &Id) -> Result<Option<Entry>, Error> {
+		let file = match File::open(&self.path) {
+			Ok(file) => file,
+			Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+			Err(error) => return Err(Error::Io(error)),
+		};
+
+		let id = normalize(id);
+
+		let mut found = None;
+
+		for line in BufReader::new(file).lines() {
+			let line = line.map_err(Error::Io)?;
+
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			match serde_json::from_str::<Entry>(&line) {
+				Ok(entry) if entry.id == id => found = Some(entry),
+				Ok(_) => (),
+				Err(error) => log::warn!("invalid history entry: {}: {}", error, line),
+			}
+		}
+
+		Ok(found)
+	}
+
+
+	pub fn record(&self, entry: &Entry) -> Result<(), Error> {
+		let mut line = serde_json
+			::to_string(entry)
+			.map_err(Error::Json)?;
+
+		line.push('\n');
+
+		OpenOptions
+			::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.and_then(
+				|mut file| file.write_all(line.as_bytes())
+			)
+			.map_err(Error::Io)
+	}
+}
+
+
+/// The history key of a track id: lowercase, with collapsed whitespace.
+pub fn normalize(id: &Id) -> Box<str> {
+	id
+		.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.to_lowercase()
+		.into_boxed_str()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::util::mp3;
+
+
+	fn id(id: &str) -> Id {
+		id.parse().expect("invalid track id")
+	}
+
+
+	#[test]
+	fn test_history() {
+		let path = std::env::temp_dir().join(
+			format!("slizzy-history-test-{}.jsonl", std::process::id())
+		);
+		let _ = std::fs::remove_file(&path);
+
+		let history = History::at(&path);
+
+		assert_eq!(
+			history.find(&id("Artist - Track (Mix)")).expect("failed to read history"),
+			None
+		);
+
+		let download = Download {
+			source: TrackSources::Slider,
+			url: "https://example.com/track.mp3".into(),
+			path: "Artist - Track (Mix).mp3".into(),
+			metadata: Some(
+				mp3::Metadata {
+					duration: Duration::from_seconds(300),
+					bitrate: 320,
+				}
+			),
+			flagged: false,
+		};
+
+		let entry = Entry::new(&id("Artist - Track (Mix)"), &download);
+
+		history
+			.record(&entry)
+			.expect("failed to record download");
+
+		assert_eq!(
+			history.find(&id("artist  -  TRACK (mix)")).expect("failed to read history"),
+			Some(entry)
+		);
+
+		assert_eq!(
+			history.find(&id("Artist - Other track")).expect("failed to read history"),
+			None
+		);
+
+		let _ = std::fs::remove_file(&path);
+	}
+}
@@ -8,15 +8,28 @@ mod track;
 mod util;
 mod web;
 mod sim;
+mod rank;
+mod output;
+mod history;
 mod modules;
 mod tui;
 mod logger;
+mod tracklist;
+mod page;
+mod reference;
 
 use std::io;
 
+use enumset::EnumSet;
+
+use futures::stream::{self, StreamExt};
+
 use crate::{
-	args::{Args, Command},
-	modules::Module,
+	args::{Args, BatchArgs, Command, Input, Ui},
+	modules::{
+		item::progress::{LineFormat, LineSink},
+		tracksource::Download,
+	},
 	util::future::abortable::{Aborted, abortable},
 };
 
@@ -47,13 +60,12 @@ fn run() -> anyhow::Result<()> {
 			Ok(())
 		},
 		Command::Download(args) => download(args),
+		Command::Batch(args) => batch(args),
 	}
 }
 
 
-fn download(args: Args) -> anyhow::Result<()> {
-	let logger = logger::setup(args.log_level);
-
+fn load_config() -> anyhow::Result<Box<str>> {
 	let cfg = match config::load() {
 		Err(error) if error.is_not_found() => {
 			eprintln!(
@@ -69,73 +81,197 @@ fn download(args: Args) -> anyhow::Result<()> {
 		other => other,
 	}?;
 
-	let mut track = args.track;
-
-	let google_cfg = config::read(&cfg)?;
-	let beatport_cfg = config::read(&cfg)?;
-	let bandcamp_cfg = config::read(&cfg)?;
-	let slider_cfg = config::read(&cfg)?;
-	let zippy_cfg = config::read(&cfg)?;
-	let music2k_cfg = config::read(&cfg)?;
-
-	log::debug!("google cfg: {:#?}", google_cfg);
-	log::debug!("beatport cfg: {:#?}", beatport_cfg);
-	log::debug!("bandcamp cfg: {:#?}", bandcamp_cfg);
-	log::debug!("slider cfg: {:#?}", slider_cfg);
-	log::debug!("zippy cfg: {:#?}", slider_cfg);
-	log::debug!("music2k cfg: {:#?}", slider_cfg);
-
-	let google = modules::google::Module::new(google_cfg);
-	let beatport = modules::beatport::Module::new(beatport_cfg);
-	let bandcamp = modules::bandcamp::Module::new(bandcamp_cfg);
-	let slider = modules::slider::Module::new(slider_cfg);
-	let zippy = modules::zippy::Module::new(zippy_cfg);
-	let music2k = modules::music2k::Module::new(music2k_cfg);
+	Ok(cfg)
+}
 
-	let terminal = tui::terminal()?;
-	let input = tui::StdinReader::new();
 
-	let (beatport_widget, beatport_reporter) = modules::beatport::tui::Widget::new();
-	let (bandcamp_widget, bandcamp_reporter) = modules::bandcamp::tui::Widget::new();
-	let (slider_widget, slider_reporter) = modules::slider::tui::Widget::new();
-	let (zippy_widget, zippy_reporter) = modules::zippy::tui::Widget::new();
-	let (music2k_widget, music2k_reporter) = modules::music2k::tui::Widget::new();
+fn load_output(cfg: &str, dir: Option<&std::path::Path>) -> anyhow::Result<output::Output> {
+	let mut output: output::Output = config::read(cfg)?;
+
+	if let Some(dir) = dir {
+		output.dir = dir.into();
+	}
 
-	let backend = backend::Backend {
-		metasources: args.metasources,
-		tracksources: args.tracksources,
+	log::debug!("output: {:#?}", output);
 
-		google,
+	std::fs::create_dir_all(&output.dir)?;
 
-		beatport,
-		beatport_reporter,
+	Ok(output)
+}
+
+
+fn download(args: Args) -> anyhow::Result<()> {
+	let logger = logger::setup(args.log_level);
+
+	let cfg = load_config()?;
 
-		bandcamp,
-		bandcamp_reporter,
+	let mut modules = backend::Modules::load(&cfg)?;
+
+	if args.no_cache {
+		modules.websearch.disable_cache();
+	}
 
-		slider,
-		slider_reporter,
+	let ranking = config::read(&cfg)?;
 
-		zippy,
-		zippy_reporter,
+	let reconcile = config::read(&cfg)?;
+
+	let output = load_output(&cfg, args.output.as_deref())?;
+
+	let events = load_events(args.events.as_deref())?;
+
+	let history = history::History::open()?;
+	let force = args.force;
+
+	let mut track = match args.input {
+		Input::Track(track) => track,
+		Input::Url(url) => {
+			let track = futures::executor::block_on(
+				page::fetch(&url)
+			)?;
+
+			log::info!("track from page: {}", track);
+
+			track
+		},
+		Input::Reference { path, track } => {
+			let track = reference::read(&path, track)?;
 
-		music2k,
-		music2k_reporter,
+			log::info!("track from reference: {}", track);
+
+			track
+		},
 	};
 
+	let explicit_metasources = args.metasources.is_some();
+	let metasources = args.metasources.unwrap_or_else(EnumSet::all);
+	let tracksources = args.tracksources;
+
+	let track_id = track.to_string();
+
+	let backend = |progress: backend::Progress| backend::Backend {
+		metasources,
+		explicit_metasources,
+		tracksources,
+
+		ranking,
+		reconcile,
+		output,
+
+		history,
+		force,
+
+		modules,
+
+		progress: match events {
+			Some(sink) => progress.tee(
+				backend::Progress::lines(LineFormat::Json, sink, &track_id)
+			),
+			None => progress,
+		},
+	};
+
+	let result = match args.ui {
+		Ui::Tui => download_tui(backend, &mut track),
+
+		Ui::Lines(format) => {
+			let backend = backend(
+				backend::Progress::lines(format, LineSink::stdout(), &track_id)
+			);
+
+			futures::executor::block_on(
+				backend.run(&mut track)
+			)
+		},
+	};
+
+	match args.ui {
+		Ui::Tui => logger.dump(io::stdout().lock())?,
+		// Keep stdout clean for the progress lines.
+		Ui::Lines(_) => logger.dump(io::stderr().lock())?,
+	};
+
+	let downloads = match require_downloads(result) {
+		Err(error) if skipped(&error) => {
+			eprintln!("{}", error);
+			return Ok(());
+		},
+		other => other?,
+	};
+
+	for download in downloads.iter() {
+		eprintln!("Downloaded: {}", download.path.display());
+	}
+
+	Ok(())
+}
+
+
+/// Whether the track wasn't downloaded because it is already owned.
+fn skipped(error: &anyhow::Error) -> bool {
+	error.is::<history::Present>() || error.is::<modules::library::Owned>()
+}
+
+
+fn download_tui<B>(backend: B, track: &mut track::Track) -> anyhow::Result<Box<[Download]>>
+where
+	B: FnOnce(backend::Progress) -> backend::Backend
+{
+	let terminal = tui::terminal()?;
+	let input = tui::StdinReader::new();
+
+	let (beatport_widget, beatport_reporter) = modules::beatport::tui::Widget::new();
+	let (bandcamp_widget, bandcamp_reporter) = modules::bandcamp::tui::Widget::new();
+	let (musicbrainz_widget, musicbrainz_reporter) = modules::musicbrainz::tui::Widget::new();
+	let (discogs_widget, discogs_reporter) = modules::discogs::tui::Widget::new();
+	let (reconcile_widget, reconcile_reporter) = modules::metasource::reconcile::tui::Widget::new();
+	let (slider_widget, slider_reporter) = modules::slider::tui::Widget::new();
+	let (zippy_widget, zippy_reporter) = modules::zippy::tui::Widget::new();
+	let (music2k_widget, music2k_reporter) = modules::music2k::tui::Widget::new();
+	let (plugin_widget, plugin_reporter) = modules::plugin::tui::Widget::new();
+	let (library_widget, library_reporter) = modules::library::tui::Widget::new();
+
+	let backend = backend(
+		backend::Progress {
+			beatport: Box::new(beatport_reporter),
+			bandcamp: Box::new(bandcamp_reporter),
+			musicbrainz: Box::new(musicbrainz_reporter),
+			discogs: Box::new(discogs_reporter),
+			reconcile: Box::new(reconcile_reporter),
+			slider: backend::SliderProgress(
+				Box::new(slider_reporter)
+			),
+			zippy: Box::new(zippy_reporter),
+			music2k: backend::Music2kProgress(
+				Box::new(music2k_reporter)
+			),
+			plugin: Box::new(plugin_reporter),
+			library: Box::new(library_reporter),
+		}
+	);
+
+	let track_id = track.to_string().into();
+	let metadata = track.metadata.clone();
+
 	let (backend_fut, backend_abort) = abortable(
-		backend.run(&mut track)
+		backend.run(track)
 	);
 
 	let window = tui::main::Window {
 		aborter: backend_abort,
 		terminal,
 		input,
+		track: track_id,
+		metadata,
 		beatport_widget,
 		bandcamp_widget,
+		musicbrainz_widget,
+		discogs_widget,
+		reconcile_widget,
 		slider_widget,
 		zippy_widget,
 		music2k_widget,
+		plugin_widget,
+		library_widget,
 	};
 
 	let ui_handle = window.run();
@@ -146,17 +282,180 @@ fn download(args: Args) -> anyhow::Result<()> {
 		.join()
 		.expect("ui thread failed to join")?;
 
-	let result = match result {
+	match result {
 		Ok(result) => result,
 		Err(Aborted) => {
 			log::info!("slizzy aborted");
-			Ok(())
+			Ok(Box::new([]))
 		}
-	};
+	}
+}
+
+
+/// Open the file to write progress events to, if any.
+fn load_events(path: Option<&std::path::Path>) -> anyhow::Result<Option<LineSink>> {
+	path
+		.map(
+			|path| LineSink
+				::file(path)
+				.map_err(
+					|error| anyhow::anyhow!("failed to open events file {}: {}", path.display(), error)
+				)
+		)
+		.transpose()
+}
+
+
+/// Fail when no file was downloaded, so that the exit code tells whether the track was
+/// obtained.
+fn require_downloads(
+	result: anyhow::Result<Box<[Download]>>
+) -> anyhow::Result<Box<[Download]>> {
+	let downloads = result?;
+
+	if downloads.is_empty() {
+		anyhow::bail!("no track was downloaded");
+	}
+
+	Ok(downloads)
+}
+
+
+fn batch(args: BatchArgs) -> anyhow::Result<()> {
+	let logger = logger::setup(args.log_level);
+
+	let cfg = load_config()?;
+
+	let mut modules = backend::Modules::load(&cfg)?;
+
+	if args.no_cache {
+		modules.websearch.disable_cache();
+	}
+
+	let ranking = config::read(&cfg)?;
+
+	let reconcile = config::read(&cfg)?;
+
+	let output = load_output(&cfg, args.output.as_deref())?;
+
+	let events = load_events(args.events.as_deref())?;
+
+	let history = history::History::open()?;
+
+	let tracklist = config::load_from(&args.tracklist)?;
+
+	let mut invalid = Vec::new();
+
+	let tracks = tracklist::parse_any(&tracklist)?
+		.into_iter()
+		.filter_map(
+			|entry| entry
+				.map_err(
+					|error| {
+						log::error!("invalid tracklist entry: {}", error);
+						invalid.push(error);
+					}
+				)
+				.ok()
+		);
+
+	let runs = stream
+		::iter(tracks)
+		.map(
+			|tracklist::Entry { line, mut track }| {
+				let backend = backend::Backend {
+					metasources: args.metasources.unwrap_or_else(EnumSet::all),
+					explicit_metasources: args.metasources.is_some(),
+					tracksources: args.tracksources,
+					ranking,
+					reconcile,
+					output: output.clone(),
+					history: history.clone(),
+					force: args.force,
+					modules: modules.clone(),
+					progress: match &events {
+						Some(sink) => backend::Progress::lines(
+							LineFormat::Json,
+							sink.clone(),
+							&track.to_string(),
+						),
+						None => backend::Progress::none(),
+					},
+				};
+
+				async move {
+					log::info!("batch: starting line {}: {}", line, track);
+
+					let result = require_downloads(
+						backend.run(&mut track).await
+					);
+
+					(line, track, result)
+				}
+			}
+		)
+		.buffer_unordered(args.jobs)
+		.collect::<Vec<_>>();
+
+	let results = futures::executor::block_on(runs);
 
 	logger.dump(
 		io::stdout().lock()
 	)?;
 
-	result
+	let failed = invalid.len() + results
+		.iter()
+		.filter(
+			|(_, _, result)| matches!(result, Err(error) if !skipped(error))
+		)
+		.count();
+
+	let total = invalid.len() + results.len();
+
+	let mut summary: Vec<(usize, String)> = results
+		.into_iter()
+		.map(
+			|(line, track, result)| match result {
+				Ok(downloads) => {
+					let paths: Vec<_> = downloads
+						.iter()
+						.map(|download| download.path.display().to_string())
+						.collect();
+
+					(line, format!("line {}: {}: done: {}", line, track, paths.join(", ")))
+				},
+				Err(error) if skipped(&error) => (
+					line,
+					format!("line {}: {}: skipped: {}", line, track, error)
+				),
+				Err(error) => (line, format!("line {}: {}: failed: {}", line, track, error)),
+			}
+		)
+		.chain(
+			invalid
+				.into_iter()
+				.map(
+					|error| (error.line, error.to_string())
+				)
+		)
+		.collect();
+
+	summary.sort_by_key(
+		|(line, _)| *line
+	);
+
+	println!("Batch summary:");
+
+	for (_, text) in summary.iter() {
+		println!("  {}", text);
+	}
+
+	if failed == 0 {
+		Ok(())
+	}
+	else {
+		Err(
+			anyhow::anyhow!("{} of {} tracklist entries failed", failed, total)
+		)
+	}
 }
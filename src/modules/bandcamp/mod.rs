@@ -1,14 +1,14 @@
 pub mod tui;
+pub mod scraper;
 mod config;
 mod source;
-mod scraper;
 
 use async_trait::async_trait;
 
 use crate::track::Track;
 use super::{metasource, websearch};
 pub use config::Config;
-pub use source::{Params as SourceParams, Status, ItemStatus};
+pub use source::{Params as SourceParams, Status, ItemStatus, scrap};
 
 
 #[derive(Debug, Clone)]
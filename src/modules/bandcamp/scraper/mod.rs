@@ -16,6 +16,19 @@ pub use crate::web::scraping::Error;
 pub struct Data {
 	/// This can be the track id or title, depending on the label.
 	pub track: Result<Box<str>, Error>,
+	/// The artist credited by the page, which may be the label for compilations.
+	pub artist: Option<Box<str>>,
+	pub duration: Result<track::Duration, Error>,
+	pub metadata: track::Metadata,
+	/// The tracklist, when the page is an album.
+	pub album: Option<Box<[AlbumTrack]>>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlbumTrack {
+	/// Like the track of a track page, this can be the track id or title.
+	pub title: Box<str>,
 	pub duration: Result<track::Duration, Error>,
 }
 
@@ -23,24 +36,98 @@ pub struct Data {
 pub fn scrap(doc: &Html) -> Data {
 	log::trace!("scraping html: {:#?}", doc);
 
+	let application_data = scrap_application_data(doc);
+
+	let metadata = application_data
+		.as_ref()
+		.map(scrap_metadata)
+		.unwrap_or_default();
+
+	let album = application_data
+		.as_ref()
+		.ok()
+		.and_then(scrap_album);
+
+	let artist = application_data
+		.as_ref()
+		.ok()
+		.and_then(|application_data| application_data.by_artist.as_ref())
+		.and_then(|artist| artist.name.clone());
+
 	Data {
 		track: scrap_track(doc),
-		duration: scrap_duration(doc)
+		artist,
+		duration: application_data.and_then(
+			|application_data| scrap_duration(application_data.additional_properties)
+		),
+		metadata,
+		album,
 	}
 }
 
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Copy, Default)]
 struct AdditionalProperties {
 	duration_secs: Option<f64>,
 }
 
 
+#[derive(Debug, Deserialize)]
+struct Named {
+	name: Option<Box<str>>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ItemList {
+	#[serde(alias = "itemListElement")]
+	#[serde(default)]
+	items: Vec<ListItem>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ListItem {
+	item: Recording,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+	name: Box<str>,
+
+	#[serde(alias = "additionalProperty")]
+	#[serde(deserialize_with = "deserialize_properties")]
+	#[serde(default)]
+	additional_properties: AdditionalProperties,
+}
+
+
 #[derive(Debug, Deserialize)]
 struct ApplicationData {
+	#[serde(rename = "@type")]
+	#[serde(default)]
+	kind: Option<Box<str>>,
+
 	#[serde(alias = "additionalProperty")]
 	#[serde(deserialize_with = "deserialize_properties")]
+	#[serde(default)]
 	additional_properties: AdditionalProperties,
+
+	/// The tracklist of album pages.
+	#[serde(default)]
+	track: Option<ItemList>,
+
+	#[serde(alias = "datePublished")]
+	#[serde(default)]
+	date_published: Option<Box<str>>,
+
+	#[serde(default)]
+	publisher: Option<Named>,
+
+	#[serde(alias = "byArtist")]
+	#[serde(default)]
+	by_artist: Option<Named>,
 }
 
 
@@ -91,23 +178,45 @@ where
 }
 
 
-fn scrap_duration(doc: &Html) -> Result<track::Duration, Error> {
+fn scrap_application_data(doc: &Html) -> Result<ApplicationData, Error> {
 	let json = doc
 		.find("script[type = 'application/ld+json']")?
 		.text_first()?;
 
-	let application_data: ApplicationData = serde_json
+	serde_json
 		::from_str(json)
 		.map_err(
 			|error| Error::Format(
 				format!("failed to parse json: {}", error).into()
 			)
-		)?;
+		)
+}
 
-	let duration_secs = application_data
-		.additional_properties
+
+fn scrap_album(application_data: &ApplicationData) -> Option<Box<[AlbumTrack]>> {
+	if application_data.kind.as_deref() != Some("MusicAlbum") {
+		return None;
+	}
+
+	let tracks = application_data.track
+		.iter()
+		.flat_map(|list| list.items.iter())
+		.map(
+			|ListItem { item }| AlbumTrack {
+				title: item.name.clone(),
+				duration: scrap_duration(item.additional_properties),
+			}
+		)
+		.collect();
+
+	Some(tracks)
+}
+
+
+fn scrap_duration(properties: AdditionalProperties) -> Result<track::Duration, Error> {
+	let duration_secs = properties
 		.duration_secs
-    .ok_or_else(
+		.ok_or_else(
 			|| Error::Format("missing duration_secs property".into())
 		)?;
 
@@ -127,6 +236,42 @@ fn scrap_duration(doc: &Html) -> Result<track::Duration, Error> {
 }
 
 
+fn scrap_metadata(application_data: &ApplicationData) -> track::Metadata {
+	track::Metadata {
+		label: application_data.publisher
+			.as_ref()
+			.and_then(|publisher| publisher.name.clone()),
+		release_date: application_data.date_published
+			.as_deref()
+			.and_then(parse_date),
+		..track::Metadata::default()
+	}
+}
+
+
+/// Parse a date like `17 May 2021 12:24:53 GMT` into `2021-05-17`.
+fn parse_date(date: &str) -> Option<Box<str>> {
+	const MONTHS: [&str; 12] = [
+		"Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+	];
+
+	let mut parts = date.split_whitespace();
+
+	let day: u8 = parts.next()?.parse().ok()?;
+	let month = parts.next()?;
+	let year: u16 = parts.next()?.parse().ok()?;
+
+	let month = MONTHS
+		.iter()
+		.position(|&name| name == month)?
+		+ 1;
+
+	Some(
+		format!("{:04}-{:02}-{:02}", year, month, day).into()
+	)
+}
+
+
 fn scrap_track(doc: &Html) -> Result<Box<str>, Error> {
 	let track = doc
 		.find("h2.trackTitle")?
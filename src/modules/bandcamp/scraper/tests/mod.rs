@@ -1,7 +1,7 @@
 use super::*;
 
 use crate::{
-	track::Duration,
+	track::{Duration, Metadata},
 	web::scraping,
 };
 
@@ -18,7 +18,50 @@ fn test_scrap() {
 		data,
 		Data {
 			track: Ok("Kinetic Rampage".into()),
+			artist: Some("SRAMAANA".into()),
 			duration: Ok(Duration::new(5, 20)),
+			metadata: Metadata {
+				label: Some("skryptom records".into()),
+				release_date: Some("2021-05-17".into()),
+				..Metadata::default()
+			},
+			album: None,
 		}
 	);
 }
+
+
+#[test]
+fn test_scrap_album() {
+	let doc = include_str!("album.html");
+
+	let doc = scraping::Html::parse_document(&doc);
+
+	let data = scrap(&doc);
+
+	assert_eq!(data.track.as_deref(), Ok("Dystopian Odyssey EP"));
+	assert!(data.duration.is_err());
+
+	assert_eq!(
+		data.album,
+		Some(
+			vec![
+				AlbumTrack {
+					title: "Dystopian Odyssey".into(),
+					duration: Ok(Duration::new(6, 2)),
+				},
+				AlbumTrack {
+					title: "Kinetic Rampage".into(),
+					duration: Ok(Duration::new(5, 20)),
+				},
+				AlbumTrack {
+					title: "Unreleased Dub".into(),
+					duration: Err(
+						scraping::Error::Format("missing duration_secs property".into())
+					),
+				},
+			]
+				.into()
+		)
+	);
+}
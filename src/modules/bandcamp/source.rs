@@ -1,25 +1,47 @@
 use thiserror::Error;
 
+use serde::Serialize;
+
 use futures::stream::StreamExt;
 
 use crate::{
 	report_wrapped,
 	net::{url::Url, http},
 	sim::{self, Sim},
-	track::{Track, IdCleaner, Duration},
+	track::{Track, IdCleaner, Duration, Metadata},
 	web::scraping,
 };
 use super::scraper;
 use super::super::{item, websearch};
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Status<WSError: std::error::Error> {
 	Skipped,
 	Searching,
 	Done,
 	MatchNotFound,
-	Error(WSError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		WSError
+	),
+}
+
+
+impl<WSError> std::fmt::Display for Status<WSError>
+where
+	WSError: std::error::Error,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Skipped => f.write_str("skipped"),
+			Status::Searching => f.write_str("searching"),
+			Status::Done => f.write_str("done"),
+			Status::MatchNotFound => f.write_str("match not found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+		}
+	}
 }
 
 
@@ -36,12 +58,32 @@ pub enum ItemError {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ItemStatus {
 	Selected,
 	Filtered,
 	TitleMismatch(Sim),
-	Error(ItemError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Selected => f.write_str("selected"),
+			ItemStatus::Filtered => f.write_str("not a track or album page"),
+			ItemStatus::TitleMismatch(sim) => write!(
+				f,
+				"title mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+		}
+	}
 }
 
 
@@ -70,7 +112,8 @@ where
 }
 
 
-async fn scrap(url: &Url) -> Result<scraper::Data, http::Error> {
+/// Fetch and scrap a page.
+pub async fn scrap(url: &Url) -> Result<scraper::Data, http::Error> {
 	let page = http::Request
 		::new(url)
 		.send()
@@ -98,7 +141,7 @@ fn select<WSError>(
 		Status = Status<WSError>,
 		ItemStatus = ItemStatus,
 	>
-) -> Option<Duration>
+) -> Option<(Duration, Metadata)>
 where
 	WSError: std::error::Error,
 {
@@ -117,40 +160,78 @@ where
 		)
 		.ok()?;
 
-	let item_track = item.track
-		.map_err(
-			|error| report_error(ItemError::TitleNotFound(error))
-		)
-		.ok()?;
+	// Track can be the title or the entire ID, depending on the label.
+	let similarity = |item_track: &str| {
+		let item_track = id_cleaner.clean(item_track);
 
-	let duration = item.duration
-		.map_err(
-			|error| report_error(ItemError::DurationNotFound(error))
+		sim::str(
+			&item_track,
+			if item_track.contains(" - ") {
+				track.id().as_ref()
+			}
+			else {
+				track.id().title()
+			}
 		)
-		.ok()?;
+	};
+
+	let (item_track, duration, similarity) = match item.album {
+		Some(album) => {
+			let best = album
+				.into_vec() // box has no owned iterator
+				.into_iter()
+				.filter_map(
+					|album_track| {
+						let duration = album_track.duration.ok()?;
+						let similarity = similarity(&album_track.title);
+
+						Some((album_track.title, duration, similarity))
+					}
+				)
+				.max_by_key(|&(_, _, similarity)| similarity);
+
+			match best {
+				Some(best) => best,
+				None => {
+					report_error(
+						ItemError::DurationNotFound(
+							scraping::Error::Format("no album track with duration".into())
+						)
+					);
+
+					return None;
+				}
+			}
+		},
+
+		None => {
+			let item_track = item.track
+				.map_err(
+					|error| report_error(ItemError::TitleNotFound(error))
+				)
+				.ok()?;
+
+			let duration = item.duration
+				.map_err(
+					|error| report_error(ItemError::DurationNotFound(error))
+				)
+				.ok()?;
+
+			let similarity = similarity(&item_track);
+
+			(item_track, duration, similarity)
+		},
+	};
 
 	progress.item(
 		id,
 		&format!("{} [{}]", item_track, duration)
 	);
 
-	let item_track = id_cleaner.clean(&item_track);
-
-	let similarity = sim::str(
-		&item_track,
-		// Track can be the title or the entire ID, depending on the label.
-		if item_track.contains(" - ") {
-			track.id().as_ref()
-		}
-		else {
-			track.id().title()
-		}
-	);
-
 	if similarity >= sim_threshold {
 		progress.item_status(id, &ItemStatus::Selected);
 
-		Some(duration)
+		Some((duration, item.metadata))
 	}
 	else {
 		progress.item_status(
@@ -213,13 +294,15 @@ where
 
 				progress.item(id, url.as_ref());
 
-				let is_track = url.as_ref().contains("/track/");
+				let url = url.as_ref();
+
+				let is_page = url.contains("/track/") || url.contains("/album/");
 
-				if !is_track {
+				if !is_page {
 					progress.item_status(id, &ItemStatus::Filtered);
 				}
 
-				is_track
+				is_page
 			}
 		);
 
@@ -235,7 +318,7 @@ where
 		.collect();
 
 	while let Some((id, item)) = items.next().await {
-		let duration = select(
+		let selected = select(
 			id,
 			item,
 			track,
@@ -244,8 +327,9 @@ where
 			progress
 		);
 
-		if duration.is_some() {
-			track.duration = duration;
+		if let Some((duration, metadata)) = selected {
+			track.duration = Some(duration);
+			track.metadata = metadata;
 
 			progress.finish(&Status::Done);
 
@@ -76,7 +76,7 @@ impl<'a> Renderer<'a> {
 						),
 
 						ItemStatus::Filtered => (
-							format!("{} | not a track page", label)
+							format!("{} | not a track or album page", label)
 								.into(),
 							Style
 								::default()
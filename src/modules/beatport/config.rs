@@ -1,14 +1,23 @@
 use serde::{Deserialize, Deserializer, de::DeserializeOwned};
 
 use crate::{
+	net::url::Url,
 	sim::Sim,
 	track::IdCleaner,
 };
 
 
+const DEFAULT_BASE_URL: &str = "https://www.beatport.com";
+
+
 #[derive(Debug, Clone)]
 pub struct Config<SearchConfig> {
 	pub sim_threshold: Sim,
+	/// Search Beatport itself before using the web search.
+	pub native_search: bool,
+	pub base_url: Url,
+	/// Use the web search when the native search finds nothing.
+	pub websearch_fallback: bool,
 	pub search: SearchConfig,
 	pub id_cleaner: IdCleaner,
 }
@@ -21,9 +30,18 @@ where
 	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
 		let config = ConfigFile::deserialize(deserializer)?;
 
+		let base_url = config.beatport.base_url
+			.as_deref()
+			.unwrap_or(DEFAULT_BASE_URL)
+			.parse()
+			.map_err(serde::de::Error::custom)?;
+
 		Ok(
 			Config {
 				sim_threshold: config.beatport.sim_threshold,
+				native_search: config.beatport.native_search,
+				base_url,
+				websearch_fallback: config.beatport.websearch_fallback,
 				search: config.beatport.search,
 				id_cleaner: config.slizzy.id_clean,
 			}
@@ -53,7 +71,57 @@ struct Slizzy {
 struct Beatport<SearchConfig> where SearchConfig: DeserializeOwned {
 	sim_threshold: Sim,
 
+	#[serde(default = "default_native_search")]
+	native_search: bool,
+
+	#[serde(default)]
+	base_url: Option<Box<str>>,
+
+	#[serde(default = "default_websearch_fallback")]
+	websearch_fallback: bool,
+
 	#[serde(flatten)]
 	#[serde(bound(deserialize = "SearchConfig: DeserializeOwned"))]
 	search: SearchConfig,
 }
+
+
+fn default_native_search() -> bool {
+	true
+}
+
+
+fn default_websearch_fallback() -> bool {
+	true
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::modules::websearch::SiteConfig;
+
+	const SLIZZY: &str = r#"
+		[slizzy]
+		id-clean = [' ?\(?Original Mix\)?']
+	"#;
+
+
+	#[test]
+	fn test_config() {
+		let config: Config<SiteConfig> = crate::config
+			::read(format!("{}\n[beatport]\nsim-threshold = 70", SLIZZY))
+			.expect("failed to read config");
+
+		assert!(config.native_search);
+		assert!(config.websearch_fallback);
+		assert_eq!(config.search, SiteConfig::default());
+
+		// A malformed search section must not be silently dropped.
+		let config: Result<Config<SiteConfig>, _> = crate::config
+			::read(format!("{}\n[beatport]\nsim-threshold = 70\nsite = 5", SLIZZY));
+
+		assert!(config.is_err());
+	}
+}
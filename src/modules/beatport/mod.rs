@@ -1,14 +1,15 @@
 pub mod tui;
+pub mod scraper;
 mod config;
+mod search;
 mod source;
-mod scraper;
 
 use async_trait::async_trait;
 
 use crate::track::Track;
 use super::{metasource, websearch};
 pub use config::Config;
-pub use source::{Params as SourceParams, Status, ItemStatus};
+pub use source::{Params as SourceParams, Status, ItemStatus, Error, scrap};
 
 
 #[derive(Debug, Clone)]
@@ -32,7 +33,7 @@ where
 	WS: websearch::Module,
 {
 	type Params = SourceParams<WS>;
-	type Error = WS::Error;
+	type Error = Error<WS::Error>;
 
 	async fn fill_metadata(
 		&self,
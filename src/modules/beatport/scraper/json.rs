@@ -0,0 +1,389 @@
+//! The JSON payloads embedded in track pages, which are more stable than the markup.
+
+use serde::Deserialize;
+
+use crate::{
+	track,
+	web::scraping::{Find, Html, Selector, Text},
+};
+use super::{Data, Error, Metadata};
+
+
+#[derive(Debug, Deserialize)]
+struct Named {
+	name: Box<str>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Release {
+	#[serde(default)]
+	label: Option<Named>,
+	#[serde(default)]
+	catalog_number: Option<Box<str>>,
+}
+
+
+/// The track object, as in Beatport's API.
+#[derive(Debug, Deserialize)]
+struct Track {
+	name: Box<str>,
+	#[serde(default)]
+	mix_name: Option<Box<str>>,
+	#[serde(default)]
+	artists: Vec<Named>,
+	#[serde(default)]
+	length_ms: Option<u64>,
+	#[serde(default)]
+	length: Option<Box<str>>,
+	#[serde(default)]
+	bpm: Option<u16>,
+	#[serde(default)]
+	key: Option<Named>,
+	#[serde(default)]
+	genre: Option<Named>,
+	#[serde(default)]
+	publish_date: Option<Box<str>>,
+	#[serde(default)]
+	catalog_number: Option<Box<str>>,
+	#[serde(default)]
+	release: Option<Release>,
+}
+
+
+impl Track {
+	/// Whether this is really a track, as other objects like labels and releases have a
+	/// name too.
+	fn is_track(&self) -> bool {
+		!self.artists.is_empty() && (self.length_ms.is_some() || self.length.is_some())
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NextData {
+	props: NextProps,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NextProps {
+	page_props: PageProps,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageProps {
+	#[serde(default)]
+	track: Option<Track>,
+	#[serde(default)]
+	dehydrated_state: Option<DehydratedState>,
+}
+
+
+/// The react-query cache, which holds the track in newer pages.
+#[derive(Debug, Deserialize)]
+struct DehydratedState {
+	queries: Vec<Query>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Query {
+	state: QueryState,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct QueryState {
+	data: serde_json::Value,
+}
+
+
+/// A schema.org MusicRecording.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Recording {
+	#[serde(rename = "@type")]
+	kind: Box<str>,
+	name: Box<str>,
+	#[serde(default)]
+	by_artist: Artists,
+	/// In ISO 8601 format, like `PT6M12S`.
+	#[serde(default)]
+	duration: Option<Box<str>>,
+	#[serde(default)]
+	date_published: Option<Box<str>>,
+	#[serde(default)]
+	genre: Option<Box<str>>,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Artists {
+	Many(Vec<Named>),
+	One(Named),
+}
+
+
+impl Default for Artists {
+	fn default() -> Self { Artists::Many(Vec::new()) }
+}
+
+
+impl Artists {
+	fn names(&self) -> Vec<&str> {
+		match self {
+			Artists::Many(artists) => artists
+				.iter()
+				.map(|artist| artist.name.as_ref())
+				.collect(),
+			Artists::One(artist) => vec![&artist.name],
+		}
+	}
+}
+
+
+/// Scrap the track from the Next.js state.
+pub fn scrap_next_data(doc: &Html) -> Result<Data, Error> {
+	let json = doc
+		.find("script#__NEXT_DATA__")?
+		.text_first()?;
+
+	let data: NextData = parse(json)?;
+
+	let page_props = data.props.page_props;
+
+	let queries = page_props.dehydrated_state
+		.into_iter()
+		.flat_map(|state| state.queries)
+		.filter_map(
+			|query| serde_json
+				::from_value(query.state.data)
+				.ok()
+		);
+
+	let track = page_props.track
+		.into_iter()
+		.chain(queries)
+		.find(Track::is_track)
+		.ok_or_else(
+			|| Error::Format("track not found in next data".into())
+		)?;
+
+	Ok(track.into())
+}
+
+
+/// Scrap the track from the schema.org ld+json payloads.
+pub fn scrap_ld_json(doc: &Html) -> Result<Data, Error> {
+	let selector = Selector
+		::parse("script[type = 'application/ld+json']")
+		.expect("invalid css selector");
+
+	doc
+		.select(&selector)
+		.filter_map(
+			|script| script
+				.text_first()
+				.ok()
+		)
+		.filter_map(
+			|json| parse::<Recording>(json).ok()
+		)
+		.find(|recording| recording.kind.as_ref() == "MusicRecording")
+		.map(Into::into)
+		.ok_or_else(
+			|| Error::NotFound("ld+json MusicRecording".into())
+		)
+}
+
+
+fn parse<'a, T: Deserialize<'a>>(json: &'a str) -> Result<T, Error> {
+	serde_json
+		::from_str(json)
+		.map_err(
+			|error| Error::Format(
+				format!("failed to parse json: {}", error).into()
+			)
+		)
+}
+
+
+fn missing(field: &str) -> Error {
+	Error::Format(
+		format!("missing {} field", field).into()
+	)
+}
+
+
+fn track_id(artists: &[&str], name: &str, mix: Option<&str>) -> Result<Box<str>, Error> {
+	if artists.is_empty() {
+		return Err(missing("artists"));
+	}
+
+	let artists = artists.join(", ");
+
+	Ok(
+		match mix.filter(|&mix| mix != "Original Mix") {
+			Some(mix) => format!("{} - {} ({})", artists, name, mix),
+			None => format!("{} - {}", artists, name),
+		}
+			.into_boxed_str()
+	)
+}
+
+
+fn duration_ms(ms: u64) -> Result<track::Duration, Error> {
+	let seconds = (ms + 500) / 1000;
+
+	if seconds > u16::MAX as u64 {
+		return Err(
+			Error::Format(
+				format!("duration out of range: {}ms", ms).into()
+			)
+		);
+	}
+
+	Ok(
+		track::Duration::from_seconds(seconds as u16)
+	)
+}
+
+
+/// Parse an ISO 8601 duration like `PT1H6M12S`.
+fn duration_iso(duration: &str) -> Result<track::Duration, Error> {
+	let invalid = || Error::Format(
+		format!("invalid duration: {}", duration).into()
+	);
+
+	let mut rest = duration
+		.strip_prefix("PT")
+		.ok_or_else(invalid)?;
+
+	let mut seconds: u32 = 0;
+
+	while !rest.is_empty() {
+		let end = rest
+			.find(|c: char| !c.is_ascii_digit())
+			.ok_or_else(invalid)?;
+
+		let value: u32 = rest[..end]
+			.parse()
+			.map_err(|_| invalid())?;
+
+		let unit_char = rest[end..]
+			.chars()
+			.next()
+			.ok_or_else(invalid)?;
+
+		let unit = match unit_char {
+			'H' => 3600,
+			'M' => 60,
+			'S' => 1,
+			_ => return Err(invalid()),
+		};
+
+		seconds = value
+			.checked_mul(unit)
+			.and_then(|value| seconds.checked_add(value))
+			.ok_or_else(invalid)?;
+
+		rest = &rest[end + unit_char.len_utf8()..];
+	}
+
+	duration_ms(seconds as u64 * 1000)
+}
+
+
+fn ok_or_missing<T>(value: Option<T>, field: &str) -> Result<T, Error> {
+	value.ok_or_else(|| missing(field))
+}
+
+
+impl From<Track> for Data {
+	fn from(track: Track) -> Self {
+		let artists: Vec<&str> = track.artists
+			.iter()
+			.map(|artist| artist.name.as_ref())
+			.collect();
+
+		let duration = match (track.length_ms, &track.length) {
+			(Some(ms), _) => duration_ms(ms),
+			(None, Some(length)) => length
+				.parse()
+				.map_err(|_| missing("length")),
+			(None, None) => Err(missing("length")),
+		};
+
+		let (label, release_catalog_number) = match track.release {
+			Some(release) => (release.label.map(|label| label.name), release.catalog_number),
+			None => (None, None),
+		};
+
+		Data {
+			track_id: track_id(&artists, &track.name, track.mix_name.as_deref()),
+			duration,
+			metadata: Metadata {
+				bpm: ok_or_missing(track.bpm, "bpm"),
+				key: ok_or_missing(track.key.map(|key| key.name), "key"),
+				genre: ok_or_missing(track.genre.map(|genre| genre.name), "genre"),
+				label: ok_or_missing(label, "label"),
+				release_date: ok_or_missing(track.publish_date, "publish date"),
+				catalog_number: ok_or_missing(
+					track.catalog_number.or(release_catalog_number),
+					"catalog number"
+				),
+			},
+		}
+	}
+}
+
+
+impl From<Recording> for Data {
+	fn from(recording: Recording) -> Self {
+		Data {
+			track_id: track_id(&recording.by_artist.names(), &recording.name, None),
+			duration: match &recording.duration {
+				Some(duration) => duration_iso(duration),
+				None => Err(missing("duration")),
+			},
+			metadata: Metadata {
+				bpm: Err(missing("bpm")),
+				key: Err(missing("key")),
+				genre: ok_or_missing(recording.genre, "genre"),
+				label: Err(missing("label")),
+				release_date: ok_or_missing(recording.date_published, "date published"),
+				catalog_number: Err(missing("catalog number")),
+			},
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_duration_iso() {
+		assert_eq!(duration_iso("PT6M41S"), Ok(track::Duration::new(6, 41)));
+		assert_eq!(duration_iso("PT1H2M3S"), Ok(track::Duration::new(62, 3)));
+		assert!(duration_iso("PT5Ä").is_err());
+		assert!(duration_iso("PT5").is_err());
+		assert!(duration_iso("PT4000000H").is_err());
+		assert!(duration_iso("6:41").is_err());
+	}
+
+
+	#[test]
+	fn test_duration_ms() {
+		assert_eq!(duration_ms(401_234), Ok(track::Duration::new(6, 41)));
+		assert_eq!(duration_ms(401_999), Ok(track::Duration::new(6, 42)));
+	}
+}
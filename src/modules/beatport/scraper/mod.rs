@@ -0,0 +1,179 @@
+mod json;
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	track,
+	web::scraping::{Attr, Find, Html, Text},
+};
+pub use crate::web::scraping::Error;
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Data {
+	pub track_id: Result<Box<str>, Error>,
+	pub duration: Result<track::Duration, Error>,
+	pub metadata: Metadata,
+}
+
+
+/// The release metadata. Each field is scraped independently, so that a missing field
+/// doesn't lose the others.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Metadata {
+	pub bpm: Result<u16, Error>,
+	pub key: Result<Box<str>, Error>,
+	pub genre: Result<Box<str>, Error>,
+	pub label: Result<Box<str>, Error>,
+	pub release_date: Result<Box<str>, Error>,
+	pub catalog_number: Result<Box<str>, Error>,
+}
+
+
+impl From<Metadata> for track::Metadata {
+	/// The metadata is optional, so missing fields are ignored.
+	fn from(metadata: Metadata) -> Self {
+		fn ok<T>(field: &str, value: Result<T, Error>) -> Option<T> {
+			value
+				.map_err(
+					|error| log::debug!("beatport {} not found: {}", field, error)
+				)
+				.ok()
+		}
+
+		Self {
+			label: ok("label", metadata.label),
+			release_date: ok("release date", metadata.release_date),
+			bpm: ok("bpm", metadata.bpm),
+			key: ok("key", metadata.key),
+			genre: ok("genre", metadata.genre),
+			catalog_number: ok("catalog number", metadata.catalog_number),
+		}
+	}
+}
+
+
+/// Scrap the page's embedded JSON, falling back to the markup when there is none.
+pub fn scrap(doc: &Html) -> Data {
+	log::trace!("scraping html: {:#?}", doc);
+
+	json
+		::scrap_next_data(doc)
+		.and_then(complete)
+		.or_else(
+			|error| {
+				log::debug!("beatport next data not available: {}", error);
+				json
+					::scrap_ld_json(doc)
+					.and_then(complete)
+			}
+		)
+		.unwrap_or_else(
+			|error| {
+				log::debug!("beatport ld+json not available, using selectors: {}", error);
+				scrap_selectors(doc)
+			}
+		)
+}
+
+
+/// Require the track id and duration, so that incomplete payloads fall back to the next
+/// scraper.
+fn complete(data: Data) -> Result<Data, Error> {
+	if let Err(error) = &data.track_id {
+		return Err(error.clone());
+	}
+
+	if let Err(error) = &data.duration {
+		return Err(error.clone());
+	}
+
+	Ok(data)
+}
+
+
+fn scrap_selectors(doc: &Html) -> Data {
+	Data {
+		track_id: scrap_id(doc),
+		duration: scrap_duration(doc),
+		metadata: scrap_metadata(doc),
+	}
+}
+
+
+fn scrap_metadata(doc: &Html) -> Metadata {
+	let value = |selector| -> Result<Box<str>, Error> {
+		doc
+			.find(selector)?
+			.text_first()
+			.map(Into::into)
+	};
+
+	Metadata {
+		bpm: value("li.interior-track-bpm > span.value")
+			.and_then(
+				|bpm| bpm
+					.parse()
+					.or(
+						Err(
+							Error::Format(
+								"invalid bpm".into()
+							)
+						)
+					)
+			),
+		key: value("li.interior-track-key > span.value"),
+		genre: value("li.interior-track-genre > span.value > a"),
+		label: value("li.interior-track-labels > span.value > a"),
+		release_date: value("li.interior-track-released > span.value"),
+		catalog_number: value("li.interior-track-catalog-number > span.value"),
+	}
+}
+
+
+fn scrap_duration(doc: &Html) -> Result<track::Duration, Error> {
+	doc
+		.find("li.interior-track-length > span.value")?
+		.text_first()?
+		.parse()
+		.or(
+			Err(
+				Error::Format(
+					"invalid track duration".into()
+				)
+			)
+		)
+}
+
+
+fn scrap_id(doc: &Html) -> Result<Box<str>, Error> {
+	let artists = doc
+		.find("div.interior-track-actions")?
+		.attr("data-ec-d1")?;
+
+	let name = doc
+		.find("div.interior-title > h1")?
+		.text_first()?;
+
+	let mix = doc
+		.find("div.interior-title > h1.remixed")
+		.and_then(
+			|e| e.text_first()
+		)
+		.ok()
+		.filter(|&s| s != "Original Mix");
+
+	Ok(
+		if let Some(mix) = mix {
+			format!("{} - {} ({})", artists, name, mix)
+				.into_boxed_str()
+		}
+		else {
+			format!("{} - {}", artists, name)
+				.into_boxed_str()
+		}
+	)
+}
+
+
@@ -0,0 +1,118 @@
+use super::*;
+
+use crate::{
+	track::{self, Duration},
+	web::scraping,
+};
+
+
+fn scrap_fixture(page: &str) -> Data {
+	let doc = scraping::Html::parse_document(page);
+
+	scrap(&doc)
+}
+
+
+#[test]
+fn test_scrap_next_data() {
+	let data = scrap_fixture(include_str!("next.html"));
+
+	assert_eq!(data.track_id.as_deref(), Ok("Somne, Stranger - Kinetic Rampage (Extended Mix)"));
+	assert_eq!(data.duration, Ok(Duration::new(6, 41)));
+
+	assert_eq!(
+		track::Metadata::from(data.metadata),
+		track::Metadata {
+			label: Some("Skryptom Records".into()),
+			release_date: Some("2021-05-17".into()),
+			bpm: Some(140),
+			key: Some("F Minor".into()),
+			genre: Some("Techno (Peak Time / Driving)".into()),
+			catalog_number: Some("SKR038".into()),
+		}
+	);
+}
+
+
+#[test]
+fn test_scrap_ld_json() {
+	let data = scrap_fixture(include_str!("ld.html"));
+
+	assert_eq!(data.track_id.as_deref(), Ok("Somne - Kinetic Rampage"));
+	assert_eq!(data.duration, Ok(Duration::new(5, 20)));
+
+	assert_eq!(
+		track::Metadata::from(data.metadata),
+		track::Metadata {
+			release_date: Some("2021-05-17".into()),
+			genre: Some("Techno (Peak Time / Driving)".into()),
+			..track::Metadata::default()
+		}
+	);
+}
+
+
+#[test]
+fn test_scrap_selectors() {
+	let data = scrap_fixture(include_str!("legacy.html"));
+
+	assert_eq!(data.track_id.as_deref(), Ok("Somne - Kinetic Rampage"));
+	assert_eq!(data.duration, Ok(Duration::new(5, 20)));
+
+	assert_eq!(data.metadata.bpm, Ok(140));
+	assert!(data.metadata.label.is_err());
+	assert!(data.metadata.catalog_number.is_err());
+
+	assert_eq!(
+		track::Metadata::from(data.metadata),
+		track::Metadata {
+			release_date: Some("2021-05-17".into()),
+			bpm: Some(140),
+			key: Some("F min".into()),
+			genre: Some("Techno (Peak Time / Driving)".into()),
+			..track::Metadata::default()
+		}
+	);
+}
+
+
+#[test]
+fn test_scrap_next_data_queries() {
+	// Other queries, like the label's, come before the track.
+	let data = scrap_fixture(
+		r#"<html><body><script id="__NEXT_DATA__" type="application/json">
+			{"props":{"pageProps":{"dehydratedState":{"queries":[
+				{"state":{"data":{"id":34567,"name":"Skryptom Records"}}},
+				{"state":{"data":{"id":6,"name":"Techno (Peak Time / Driving)"}}},
+				{"state":{"data":{
+					"artists":[{"name":"Somne"}],
+					"name":"Kinetic Rampage",
+					"mix_name":"Original Mix",
+					"length_ms":401999
+				}}}
+			]}}}}
+		</script></body></html>"#
+	);
+
+	assert_eq!(data.track_id.as_deref(), Ok("Somne - Kinetic Rampage"));
+	assert_eq!(data.duration, Ok(Duration::new(6, 42)));
+
+	// An unusable duration falls back to the ld+json payload.
+	let data = scrap_fixture(
+		r#"<html><body>
+			<script id="__NEXT_DATA__" type="application/json">
+				{"props":{"pageProps":{"track":{
+					"artists":[{"name":"Somne"}],
+					"name":"Kinetic Rampage",
+					"length":"unknown"
+				}}}}
+			</script>
+			<script type="application/ld+json">
+				{"@type":"MusicRecording","name":"Kinetic Rampage","byArtist":{"name":"Somne"},"duration":"PT5M20S"}
+			</script>
+		</body></html>"#
+	);
+
+	assert_eq!(data.track_id.as_deref(), Ok("Somne - Kinetic Rampage"));
+	assert_eq!(data.duration, Ok(Duration::new(5, 20)));
+}
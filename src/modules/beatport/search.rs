@@ -0,0 +1,192 @@
+//! Beatport's own track search, which doesn't spend web search queries.
+
+use std::collections::HashSet;
+
+use crate::{
+	net::{http, url::Url},
+	web::scraping::{Find, Html, Selector, Text},
+};
+
+
+/// How many track pages to take from the results.
+const MAX_RESULTS: usize = 10;
+
+
+/// Search Beatport's tracks, returning the track page urls.
+pub async fn search(base_url: &Url, query: &str) -> Result<Box<[Url]>, http::Error> {
+	let url = base_url
+		.dissect()
+		.push_path("search/tracks")
+		.expect("invalid url")
+		.append_query("q", query)
+		.assemble();
+
+	log::debug!("beatport search url: {}", url);
+
+	let page = http::Request
+		::new(&url)
+		.send()
+		.await?
+		.body_string()
+		.await?;
+
+	let doc = Html::parse_document(&page);
+
+	Ok(
+		scrap_urls(&doc, base_url)
+	)
+}
+
+
+/// Collect the track pages from the search results, either from the Next.js state or
+/// from the result links.
+fn scrap_urls(doc: &Html, base_url: &Url) -> Box<[Url]> {
+	let mut paths = scrap_next_data(doc);
+
+	if paths.is_empty() {
+		let selector = Selector
+			::parse("a[href *= '/track/']")
+			.expect("invalid css selector");
+
+		paths = doc
+			.select(&selector)
+			.filter_map(|link| link.value().attr("href"))
+			.map(Into::into)
+			.collect();
+	}
+
+	let mut seen = HashSet::new();
+
+	paths
+		.into_iter()
+		.filter(|path| seen.insert(path.clone()))
+		.filter_map(
+			|path| if path.starts_with('/') {
+				base_url
+					.dissect()
+					.push_path(&path)
+					.ok()
+					.map(|url| url.assemble())
+			}
+			else {
+				path.parse().ok()
+			}
+		)
+		.take(MAX_RESULTS)
+		.collect()
+}
+
+
+fn scrap_next_data(doc: &Html) -> Vec<String> {
+	let json = doc
+		.find("script#__NEXT_DATA__")
+		.and_then(|script| script.text_first());
+
+	let data: serde_json::Value = match json.map(serde_json::from_str) {
+		Ok(Ok(data)) => data,
+		_ => return Vec::new(),
+	};
+
+	let mut paths = Vec::new();
+
+	collect_tracks(&data, &mut paths);
+
+	paths
+}
+
+
+/// Find the track objects anywhere in the state, as its layout changes often.
+fn collect_tracks(value: &serde_json::Value, paths: &mut Vec<String>) {
+	match value {
+		serde_json::Value::Object(object) => {
+			let id = object
+				.get("track_id")
+				.and_then(serde_json::Value::as_u64);
+
+			let name = object
+				.get("track_name")
+				.and_then(serde_json::Value::as_str);
+
+			if let (Some(id), Some(name)) = (id, name) {
+				paths.push(format!("/track/{}/{}", slug(name), id));
+			}
+
+			for value in object.values() {
+				collect_tracks(value, paths);
+			}
+		},
+
+		serde_json::Value::Array(values) => {
+			for value in values {
+				collect_tracks(value, paths);
+			}
+		},
+
+		_ => { },
+	}
+}
+
+
+/// Beatport redirects by the track id, so the slug needs not be exact.
+fn slug(name: &str) -> String {
+	name
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join("-")
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn urls(page: &str) -> Vec<String> {
+		let base_url: Url = "https://www.beatport.com"
+			.parse()
+			.expect("invalid url");
+
+		let doc = Html::parse_document(page);
+
+		scrap_urls(&doc, &base_url)
+			.iter()
+			.map(ToString::to_string)
+			.collect()
+	}
+
+	#[test]
+	fn test_scrap_urls() {
+		assert_eq!(
+			urls(
+				r#"
+					<script id="__NEXT_DATA__" type="application/json">
+						{"props":{"pageProps":{"dehydratedState":{"queries":[{"state":{"data":{"data":[
+							{"track_id":15161234,"track_name":"Kinetic Rampage","mix_name":"Extended Mix"},
+							{"track_id":15161235,"track_name":"Dystopian Odyssey (Remastered)"},
+							{"track_id":15161234,"track_name":"Kinetic Rampage"}
+						]}}}]}}}}
+					</script>
+				"#
+			),
+			[
+				"https://www.beatport.com/track/kinetic-rampage/15161234",
+				"https://www.beatport.com/track/dystopian-odyssey-remastered/15161235",
+			]
+		);
+
+		assert_eq!(
+			urls(
+				r#"
+					<ul>
+						<li class="bucket-item track"><a href="/track/kinetic-rampage/15161234">Kinetic Rampage</a></li>
+						<li class="bucket-item track"><a href="/artist/somne/734361">Somne</a></li>
+						<li class="bucket-item track"><a href="/track/kinetic-rampage/15161234">Play</a></li>
+					</ul>
+				"#
+			),
+			["https://www.beatport.com/track/kinetic-rampage/15161234"]
+		);
+	}
+}
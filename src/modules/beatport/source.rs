@@ -1,25 +1,57 @@
 use thiserror::Error;
 
+use serde::Serialize;
+
 use futures::stream::StreamExt;
 
 use crate::{
 	report_wrapped,
 	net::{url::Url, http},
 	sim::{self, Sim},
-	track::{Track, IdCleaner, Duration},
+	track::{Track, IdCleaner, Duration, Metadata},
 	web::scraping,
 };
-use super::scraper;
+use super::{scraper, search};
 use super::super::{item, websearch};
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Error)]
+pub enum Error<WSError: std::error::Error> {
+	#[error("search error: {0}")]
+	Search(http::Error),
+
+	#[error("web search error: {0}")]
+	WebSearch(WSError),
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Status<WSError: std::error::Error> {
 	Skipped,
 	Searching,
 	Done,
 	MatchNotFound,
-	Error(WSError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error<WSError>
+	),
+}
+
+
+impl<WSError> std::fmt::Display for Status<WSError>
+where
+	WSError: std::error::Error,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Skipped => f.write_str("skipped"),
+			Status::Searching => f.write_str("searching"),
+			Status::Done => f.write_str("done"),
+			Status::MatchNotFound => f.write_str("match not found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+		}
+	}
 }
 
 
@@ -36,12 +68,32 @@ pub enum ItemError {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ItemStatus {
 	Selected,
 	Filtered,
 	TitleMismatch(Sim),
-	Error(ItemError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Selected => f.write_str("selected"),
+			ItemStatus::Filtered => f.write_str("not a track page"),
+			ItemStatus::TitleMismatch(sim) => write!(
+				f,
+				"title mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+		}
+	}
 }
 
 
@@ -70,7 +122,8 @@ where
 }
 
 
-async fn scrap(url: &Url) -> Result<scraper::Data, http::Error> {
+/// Fetch and scrap a page.
+pub async fn scrap(url: &Url) -> Result<scraper::Data, http::Error> {
 	let page = http::Request
 		::new(url)
 		.send()
@@ -98,7 +151,7 @@ fn select<WSError>(
 		Status = Status<WSError>,
 		ItemStatus = ItemStatus,
 	>
-) -> Option<Duration>
+) -> Option<(Duration, Metadata)>
 where
 	WSError: std::error::Error,
 {
@@ -137,7 +190,7 @@ where
 	if similarity >= sim_threshold {
 		progress.item_status(id, &ItemStatus::Selected);
 
-		Some(duration)
+		Some((duration, item.metadata.into()))
 	}
 	else {
 		progress.item_status(
@@ -150,11 +203,53 @@ where
 }
 
 
+/// Search Beatport itself, falling back to the web search when nothing is found. The
+/// fallback is skipped when the web search isn't configured for this module.
+async fn search_urls<WS>(
+	module: &super::Module<WS>,
+	track: &Track,
+	websearch: &WS,
+) -> Result<Box<[Url]>, Error<WS::Error>>
+where
+	WS: websearch::Module,
+{
+	let config = &module.config;
+
+	let native_error = if config.native_search {
+		match search::search(&config.base_url, track.query_str()).await {
+			Ok(urls) if !urls.is_empty() => return Ok(urls),
+			Ok(_) => None,
+			Err(error) => {
+				log::warn!("beatport search failed: {}", error);
+				Some(error)
+			},
+		}
+	}
+	else {
+		None
+	};
+
+	let websearch_enabled = !config.native_search
+		|| (config.websearch_fallback && websearch.can_search(&config.search));
+
+	match (websearch_enabled, native_error) {
+		(true, _) => websearch
+			.search(track.query_str(), &config.search)
+			.await
+			.map_err(Error::WebSearch),
+
+		(false, Some(error)) => Err(Error::Search(error)),
+
+		(false, None) => Ok(Box::new([])),
+	}
+}
+
+
 pub async fn fill_metadata<WS>(
 	module: &super::Module<WS>,
 	track: &mut Track,
 	params: Params<WS>
-) -> Result<bool, WS::Error>
+) -> Result<bool, Error<WS::Error>>
 where
 	WS: websearch::Module,
 {
@@ -170,11 +265,7 @@ where
 		return Ok(true);
 	}
 
-	let urls = params.websearch
-		.search(
-			track.query_str(),
-			&module.config.search
-		)
+	let urls = search_urls(module, track, &params.websearch)
 		.await
 		.map_err(
 			|error| report_wrapped!(
@@ -224,7 +315,7 @@ where
 		.collect();
 
 	while let Some((id, item)) = items.next().await {
-		let duration = select(
+		let selected = select(
 			id,
 			item,
 			track_id,
@@ -233,8 +324,9 @@ where
 			progress
 		);
 
-		if duration.is_some() {
-			track.duration = duration;
+		if let Some((duration, metadata)) = selected {
+			track.duration = Some(duration);
+			track.metadata = metadata;
 
 			progress.finish(&Status::Done);
 
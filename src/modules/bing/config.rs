@@ -0,0 +1,32 @@
+use serde::{Deserialize, Deserializer};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Config {
+	pub key: Box<str>,
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Config {
+				key: config.bing.key
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	bing: Bing,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Bing {
+	key: Box<str>,
+}
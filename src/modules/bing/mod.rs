@@ -0,0 +1,161 @@
+mod config;
+
+use lazy_static::lazy_static;
+
+use async_trait::async_trait;
+
+use serde::Deserialize;
+
+use super::websearch;
+use crate::net::{http, url::Url};
+pub use config::Config;
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Module {
+	config: Config,
+}
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(config: Config) -> Self {
+		Module { config }
+	}
+}
+
+
+pub type SearchConfig = websearch::SiteConfig;
+
+
+pub type SearchError = http::Error;
+
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Results {
+	#[serde(default)]
+	web_pages: Option<WebPages>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct WebPages {
+	value: Vec<Item>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Item {
+	url: Box<str>,
+}
+
+
+fn parse_results(results: Results) -> Box<[Url]> {
+	websearch::parse_urls(
+		"bing",
+		results.web_pages
+			.into_iter()
+			.flat_map(|pages| pages.value)
+			.map(|item| item.url)
+	)
+}
+
+
+#[async_trait]
+impl websearch::Module for Module {
+	type SearchConfig = SearchConfig;
+	type Error = SearchError;
+
+	async fn search(
+		&self,
+		query: &str,
+		search_config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error> {
+		lazy_static! {
+			static ref BASE_URL: Url = "https://api.bing.microsoft.com/v7.0/search"
+				.parse()
+				.expect("invalid bing api base url");
+		}
+
+		let url = BASE_URL
+			.dissect()
+			.append_query("q", &search_config.query(query))
+			.append_query("responseFilter", "Webpages")
+			.assemble();
+
+		log::debug!("bing query url: {}", url);
+
+		let mut request = http::Request::new(&url);
+
+		request
+			.headers_mut()
+			.insert(
+				http::headers::Name::from_static("ocp-apim-subscription-key"),
+				http::headers::Value
+					::from_str(&self.config.key)
+					.map_err(
+						|error| http::Error::Request(anyhow::Error::new(error).into())
+					)?,
+			);
+
+		let results = request
+			.send()
+			.await?
+			.body_json::<Results>()
+			.await?;
+
+		Ok(parse_results(results))
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_results() {
+		let results: Results = serde_json
+			::from_str(
+				r#"{
+					"_type": "SearchResponse",
+					"queryContext": { "originalQuery": "Somne Kinetic Rampage" },
+					"webPages": {
+						"webSearchUrl": "https://www.bing.com/search?q=Somne+Kinetic+Rampage",
+						"totalEstimatedMatches": 2,
+						"value": [
+							{
+								"id": "https://api.bing.microsoft.com/api/v7/#WebPages.0",
+								"name": "Kinetic Rampage",
+								"url": "https://www.beatport.com/track/kinetic-rampage/15161234"
+							},
+							{
+								"id": "https://api.bing.microsoft.com/api/v7/#WebPages.1",
+								"name": "Kinetic Rampage",
+								"url": "https://skryptom.bandcamp.com/track/kinetic-rampage"
+							}
+						]
+					}
+				}"#
+			)
+			.expect("failed to parse results");
+
+		assert_eq!(
+			parse_results(results).as_ref(),
+			[
+				"https://www.beatport.com/track/kinetic-rampage/15161234".parse().expect("invalid url"),
+				"https://skryptom.bandcamp.com/track/kinetic-rampage".parse().expect("invalid url"),
+			] as [Url; 2]
+		);
+
+		// No web pages are returned when nothing is found.
+		let empty: Results = serde_json
+			::from_str(r#"{ "_type": "SearchResponse" }"#)
+			.expect("failed to parse results");
+
+		assert!(parse_results(empty).is_empty());
+	}
+}
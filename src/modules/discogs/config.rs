@@ -0,0 +1,75 @@
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+	net::url::Url,
+	sim::Sim,
+};
+
+
+const DEFAULT_BASE_URL: &str = "https://api.discogs.com";
+
+
+#[derive(Debug, Clone)]
+pub struct Config {
+	pub base_url: Url,
+	/// Personal access token, required by the search API.
+	pub token: Option<Box<str>>,
+	pub sim_threshold: Sim,
+	/// How many releases to inspect.
+	pub limit: u8,
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		let base_url = config.discogs.base_url
+			.as_deref()
+			.unwrap_or(DEFAULT_BASE_URL)
+			.parse()
+			.map_err(serde::de::Error::custom)?;
+
+		Ok(
+			Config {
+				base_url,
+				token: config.discogs.token,
+				sim_threshold: config.discogs.sim_threshold,
+				limit: config.discogs.limit,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	// Optional, so that older config files remain valid.
+	#[serde(default)]
+	discogs: Discogs,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+#[serde(default)]
+struct Discogs {
+	base_url: Option<Box<str>>,
+	token: Option<Box<str>>,
+	sim_threshold: Sim,
+	limit: u8,
+}
+
+
+impl Default for Discogs {
+	fn default() -> Self {
+		Self {
+			base_url: None,
+			token: None,
+			sim_threshold: Sim::try_from(70u8).expect("invalid default similarity"),
+			limit: 5,
+		}
+	}
+}
@@ -0,0 +1,42 @@
+pub mod tui;
+mod config;
+mod source;
+
+use async_trait::async_trait;
+
+use crate::track::Track;
+use super::metasource;
+pub use config::Config;
+pub use source::{Error as SourceError, Params as SourceParams, Status, ItemStatus};
+
+
+#[derive(Debug, Clone)]
+pub struct Module {
+	config: Config,
+}
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(config: Self::Config) -> Self {
+		Module { config }
+	}
+}
+
+
+#[async_trait(?Send)]
+impl metasource::Module for Module {
+	type Params = SourceParams;
+	type Error = SourceError;
+
+	async fn fill_metadata(
+		&self,
+		track: &mut Track,
+		params: Self::Params
+	) -> Result<bool, Self::Error> {
+		source
+			::fill_metadata(self, track, params)
+			.await
+	}
+}
@@ -0,0 +1,153 @@
+use lazy_static::lazy_static;
+
+use regex::Regex;
+
+use serde::Deserialize;
+
+
+/// The response of the database search.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Search {
+	pub results: Box<[SearchResult]>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SearchResult {
+	pub id: u64,
+	/// In the `Artists - Release` format.
+	pub title: Box<str>,
+	pub resource_url: Box<str>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+	pub title: Box<str>,
+	#[serde(default)]
+	pub artists: Box<[Artist]>,
+	#[serde(default)]
+	pub labels: Box<[Label]>,
+	pub released: Option<Box<str>>,
+	#[serde(default)]
+	pub tracklist: Box<[Track]>,
+}
+
+
+impl Release {
+	/// All tracks, including the sub tracks of index tracks.
+	pub fn tracks(&self) -> impl Iterator<Item = &Track> {
+		self.tracklist
+			.iter()
+			.flat_map(
+				|track| std::iter::once(track).chain(track.sub_tracks.iter())
+			)
+			.filter(
+				|track| track.kind.as_ref() == "track"
+			)
+	}
+
+
+	pub fn label(&self) -> Option<String> {
+		self.labels
+			.first()
+			.map(
+				|label| strip_suffix(&label.name)
+			)
+	}
+
+
+	/// The release date, without the unknown parts. Discogs uses zeros for unknown month or
+	/// day, as in `1999-03-00`.
+	pub fn release_date(&self) -> Option<&str> {
+		let mut date = self.released.as_deref()?;
+
+		while let Some(stripped) = date.strip_suffix("-00") {
+			date = stripped;
+		}
+
+		Some(date).filter(
+			|date| !date.is_empty() && *date != "0"
+		)
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Track {
+	pub position: Box<str>,
+	#[serde(rename = "type_", default = "default_kind")]
+	pub kind: Box<str>,
+	pub title: Box<str>,
+	#[serde(default)]
+	pub duration: Box<str>,
+	/// Only present when they differ from the release artists.
+	#[serde(default)]
+	pub artists: Box<[Artist]>,
+	#[serde(default)]
+	pub sub_tracks: Box<[Track]>,
+}
+
+
+fn default_kind() -> Box<str> {
+	"track".into()
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Artist {
+	pub name: Box<str>,
+	#[serde(default)]
+	pub join: Box<str>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Label {
+	pub name: Box<str>,
+}
+
+
+/// Join the artists by their join phrases, as displayed by Discogs.
+pub fn artists(artists: &[Artist]) -> String {
+	let mut result = String::new();
+
+	for (ix, artist) in artists.iter().enumerate() {
+		if ix > 0 {
+			match artist_join(&artists[ix - 1]) {
+				"," => result.push_str(", "),
+				join => {
+					result.push(' ');
+					result.push_str(join);
+					result.push(' ');
+				}
+			}
+		}
+
+		result.push_str(&strip_suffix(&artist.name));
+	}
+
+	result
+}
+
+
+fn artist_join(artist: &Artist) -> &str {
+	match artist.join.trim() {
+		"" => ",",
+		join => join,
+	}
+}
+
+
+/// Remove the numeric suffix that disambiguates homonymous names, as in `Somne (2)`.
+fn strip_suffix(name: &str) -> String {
+	lazy_static! {
+		static ref SUFFIX_PATTERN: Regex = Regex
+			::new(r"\s*\(\d+\)$")
+			.expect("invalid regex");
+	}
+
+	SUFFIX_PATTERN
+		.replace(name.trim_end_matches('*'), "")
+		.into_owned()
+}
@@ -0,0 +1,483 @@
+mod api;
+
+use std::cmp::max;
+
+use thiserror::Error;
+
+use serde::Serialize;
+
+use futures::stream::StreamExt;
+
+use crate::{
+	report_wrapped,
+	net::{url::Url, http},
+	sim::{self, Sim},
+	track::{Track, Id, Duration, Metadata},
+};
+use super::super::item;
+
+
+// Discogs requires a meaningful user agent.
+const USER_AGENT: &str = concat!(
+	"slizzy/",
+	env!("CARGO_PKG_VERSION"),
+	" +https://github.com/gahag/rslizzy"
+);
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("missing token, please set [discogs] token in the config file")]
+	MissingToken,
+
+	#[error("http error: {0}")]
+	Http(http::Error),
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Status {
+	Skipped,
+	Searching,
+	Done,
+	MatchNotFound,
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
+}
+
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Skipped => f.write_str("skipped"),
+			Status::Searching => f.write_str("searching"),
+			Status::Done => f.write_str("done"),
+			Status::MatchNotFound => f.write_str("match not found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+		}
+	}
+}
+
+
+#[derive(Debug, Error)]
+pub enum ItemError {
+	#[error("http error: {0}")]
+	Http(http::Error),
+
+	#[error("invalid url: {0}")]
+	Url(url::ParseError),
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ItemStatus {
+	Selected,
+	EmptyTracklist,
+	TitleMismatch(Sim),
+	MissingDuration,
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Selected => f.write_str("selected"),
+			ItemStatus::EmptyTracklist => f.write_str("empty tracklist"),
+			ItemStatus::TitleMismatch(sim) => write!(
+				f,
+				"title mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			ItemStatus::MissingDuration => f.write_str("missing duration"),
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+		}
+	}
+}
+
+
+pub struct Params {
+	pub progress: Box<
+		dyn item::progress::Progress<
+			Id = u8,
+			Item = str,
+			Status = Status,
+			ItemStatus = ItemStatus,
+		> + Send
+	>,
+	/// Fail without a token, rather than skipping, as when Discogs is explicitly selected.
+	pub required: bool,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.field("required", &self.required)
+			.finish()
+	}
+}
+
+
+async fn get<T>(url: &Url, token: &str) -> Result<T, http::Error>
+where
+	T: serde::de::DeserializeOwned
+{
+	let mut request = http::Request::new(url);
+
+	let headers = request.headers_mut();
+
+	headers.insert(
+		http::headers::USER_AGENT,
+		http::headers::Value::from_static(USER_AGENT),
+	);
+
+	headers.insert(
+		http::headers::AUTHORIZATION,
+		http::headers::Value
+			::from_str(&format!("Discogs token={}", token))
+			.map_err(
+				|error| http::Error::Request(anyhow::Error::new(error).into())
+			)?,
+	);
+
+	request
+		.send()
+		.await?
+		.body_json()
+		.await
+		.map_err(Into::into)
+}
+
+
+async fn search(
+	config: &super::Config,
+	token: &str,
+	track: &Track,
+) -> Result<Box<[api::SearchResult]>, http::Error> {
+	let url = config.base_url
+		.dissect()
+		.push_path("database/search")
+		.expect("invalid url")
+		.append_query("type", "release")
+		.append_query("artist", track.id().artists())
+		.append_query("track", track.id().name())
+		.append_query("per_page", &config.limit.to_string())
+		.assemble();
+
+	log::debug!("discogs url: {}", url);
+
+	let search: api::Search = get(&url, token).await?;
+
+	log::trace!("discogs results: {:#?}", search.results);
+
+	Ok(search.results)
+}
+
+
+async fn fetch_release(url: &str, token: &str) -> Result<api::Release, ItemError> {
+	let url: Url = url
+		.parse()
+		.map_err(ItemError::Url)?;
+
+	get(&url, token)
+		.await
+		.map_err(ItemError::Http)
+}
+
+
+/// Parse a `m:ss` or `h:mm:ss` duration.
+fn parse_duration(duration: &str) -> Option<Duration> {
+	let seconds = duration
+		.trim()
+		.split(':')
+		.try_fold(
+			0u32,
+			|total, part| Some(total * 60 + part.parse::<u32>().ok()?)
+		)?;
+
+	if seconds == 0 || seconds > u16::MAX as u32 {
+		None
+	}
+	else {
+		Some(Duration::from_seconds(seconds as u16))
+	}
+}
+
+
+/// Whether the mix is the original one, which Discogs usually omits.
+fn is_original(mix: &str) -> bool {
+	mix
+		.to_lowercase()
+		.contains("original")
+}
+
+
+fn similarity(title: &str, id: &Id) -> Sim {
+	match id.mix() {
+		Some(mix) if !is_original(mix) => sim::str(title, id.title()),
+		_ => max(
+			sim::str(title, id.name()),
+			sim::str(title, id.title()),
+		),
+	}
+}
+
+
+/// Walk the release tracklist, finding the track most similar to the id.
+fn find_track<'a>(release: &'a api::Release, id: &Id) -> Option<(&'a api::Track, Sim)> {
+	let release_artists = api::artists(&release.artists);
+
+	release
+		.tracks()
+		.map(
+			|track| {
+				let title = similarity(&track.title, id);
+
+				let artists = if track.artists.is_empty() {
+					sim::str(&release_artists, id.artists())
+				}
+				else {
+					sim::str(&api::artists(&track.artists), id.artists())
+				};
+
+				(track, title.min(artists))
+			}
+		)
+		.max_by_key(
+			|(_, sim)| *sim
+		)
+}
+
+
+fn select(
+	id: u8,
+	release: Result<api::Release, ItemError>,
+	track: &Track,
+	sim_threshold: Sim,
+	progress: &dyn item::progress::Progress<
+		Id = u8,
+		Item = str,
+		Status = Status,
+		ItemStatus = ItemStatus,
+	>
+) -> Option<(Duration, Metadata)> {
+	let release = release
+		.map_err(
+			|error| progress.item_status(id, &ItemStatus::Error(error))
+		)
+		.ok()?;
+
+	let (item, similarity) = match find_track(&release, track.id()) {
+		Some(found) => found,
+		None => {
+			progress.item_status(id, &ItemStatus::EmptyTracklist);
+			return None;
+		}
+	};
+
+	progress.item(
+		id,
+		&format!(
+			"{} | {}. {} [{}]",
+			release.title,
+			item.position,
+			item.title,
+			if item.duration.is_empty() { "?" } else { item.duration.as_ref() }
+		)
+	);
+
+	if similarity < sim_threshold {
+		progress.item_status(id, &ItemStatus::TitleMismatch(similarity));
+		return None;
+	}
+
+	let duration = match parse_duration(&item.duration) {
+		Some(duration) => duration,
+		None => {
+			progress.item_status(id, &ItemStatus::MissingDuration);
+			return None;
+		}
+	};
+
+	progress.item_status(id, &ItemStatus::Selected);
+
+	let metadata = Metadata {
+		label: release.label().map(Into::into),
+		release_date: release.release_date().map(Into::into),
+		..Default::default()
+	};
+
+	Some((duration, metadata))
+}
+
+
+pub async fn fill_metadata(
+	module: &super::Module,
+	track: &mut Track,
+	params: Params,
+) -> Result<bool, Error> {
+	let progress = params.progress.as_ref();
+
+	if track.duration.is_none() {
+		progress.status(&Status::Searching);
+	}
+	else {
+		progress.finish(&Status::Skipped);
+		return Ok(true);
+	}
+
+	let token = match module.config.token.as_deref() {
+		Some(token) => token,
+		None if !params.required => {
+			log::debug!("discogs skipped: no token configured");
+			progress.finish(&Status::Skipped);
+			return Ok(false);
+		},
+		None => return Err(
+			report_wrapped!(
+				Status::Error(Error::MissingToken),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		),
+	};
+
+	let results = search(&module.config, token, track)
+		.await
+		.map_err(
+			|error| report_wrapped!(
+				Status::Error(Error::Http(error)),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		)?;
+
+	progress.size_hint(
+		(results.len(), Some(results.len()))
+	);
+
+	for (id, result) in results.iter().enumerate() {
+		progress.item(id as u8, &result.title);
+	}
+
+	let mut items: futures::stream::FuturesUnordered<_> = results
+		.iter()
+		.enumerate()
+		.map(
+			|(id, result)| async move {
+				(
+					id as u8,
+					fetch_release(&result.resource_url, token).await
+				)
+			}
+		)
+		.collect();
+
+	while let Some((id, release)) = items.next().await {
+		let selected = select(
+			id,
+			release,
+			track,
+			module.config.sim_threshold,
+			progress
+		);
+
+		if let Some((duration, metadata)) = selected {
+			track.duration = Some(duration);
+			track.metadata = metadata;
+
+			progress.finish(&Status::Done);
+
+			return Ok(true)
+		}
+	}
+
+	progress.finish(&Status::MatchNotFound);
+
+	Ok(false)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+
+	const RELEASE: &str = r#"{
+		"title": "Vertere EP",
+		"artists": [
+			{ "name": "Somne (2)", "join": "&" },
+			{ "name": "Mind Against*", "join": "" }
+		],
+		"labels": [{ "name": "Skryptöm Records (3)" }],
+		"released": "2021-05-00",
+		"tracklist": [
+			{ "position": "", "type_": "heading", "title": "Side A", "duration": "" },
+			{ "position": "A1", "type_": "track", "title": "Vertere (Original Mix)", "duration": "9:14" },
+			{ "position": "A2", "type_": "track", "title": "Teleport", "duration": "" },
+			{
+				"position": "B", "type_": "index", "title": "Medley", "duration": "",
+				"sub_tracks": [
+					{ "position": "B1", "type_": "track", "title": "Somewhere", "duration": "1:02:03" }
+				]
+			}
+		]
+	}"#;
+
+
+	fn release() -> api::Release {
+		serde_json
+			::from_str(RELEASE)
+			.expect("failed to parse release")
+	}
+
+
+	#[test]
+	fn test_release() {
+		let release = release();
+
+		assert_eq!(api::artists(&release.artists), "Somne & Mind Against");
+		assert_eq!(release.label().as_deref(), Some("Skryptöm Records"));
+		assert_eq!(release.release_date(), Some("2021-05"));
+		assert_eq!(
+			release
+				.tracks()
+				.map(|track| track.position.as_ref())
+				.collect::<Vec<_>>(),
+			vec!["A1", "A2", "B1"]
+		);
+	}
+
+
+	#[test]
+	fn test_find_track() {
+		let release = release();
+
+		let id: Id = "Somne & Mind Against - Vertere"
+			.parse()
+			.expect("invalid track id");
+
+		let (track, _) = find_track(&release, &id).expect("track not found");
+
+		assert_eq!(track.position.as_ref(), "A1");
+		assert_eq!(parse_duration(&track.duration), Some(Duration::new(9, 14)));
+	}
+
+
+	#[test]
+	fn test_parse_duration() {
+		assert_eq!(parse_duration("6:24"), Some(Duration::new(6, 24)));
+		assert_eq!(parse_duration("1:02:03"), Some(Duration::from_seconds(3723)));
+		assert_eq!(parse_duration(""), None);
+		assert_eq!(parse_duration("?"), None);
+	}
+}
@@ -0,0 +1,68 @@
+use crate::sim::Sim;
+use super::{BackendStatus, BackendItemStatus};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+	Initializing,
+	Skipped,
+	Searching,
+	Done,
+	MatchNotFound,
+	Error(Box<str>),
+}
+
+
+impl Default for Status {
+	fn default() -> Self { Status::Initializing }
+}
+
+
+impl From<&BackendStatus> for Status {
+	fn from(status: &BackendStatus) -> Self {
+		match status {
+			BackendStatus::Skipped => Status::Skipped,
+			BackendStatus::Searching => Status::Searching,
+			BackendStatus::Done => Status::Done,
+			BackendStatus::MatchNotFound => Status::MatchNotFound,
+			BackendStatus::Error(error) => Status::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+	Fetching,
+	Selected,
+	EmptyTracklist,
+	TitleMismatch(Sim),
+	MissingDuration,
+	Error(Box<str>),
+}
+
+
+impl Default for ItemStatus {
+	fn default() -> Self { ItemStatus::Fetching }
+}
+
+
+impl From<&BackendItemStatus> for ItemStatus {
+	fn from(status: &BackendItemStatus) -> Self {
+		match status {
+			BackendItemStatus::Selected => ItemStatus::Selected,
+			BackendItemStatus::EmptyTracklist => ItemStatus::EmptyTracklist,
+			BackendItemStatus::TitleMismatch(sim) => ItemStatus::TitleMismatch(*sim),
+			BackendItemStatus::MissingDuration => ItemStatus::MissingDuration,
+			BackendItemStatus::Error(error) => ItemStatus::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+		}
+	}
+}
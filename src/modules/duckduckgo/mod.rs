@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+
+use async_trait::async_trait;
+
+use serde::Deserialize;
+
+use super::websearch;
+use crate::{
+	net::{http, url::Url},
+	web::scraping::{Html, Selector},
+};
+
+
+/// DuckDuckGo's HTML results need no key, so there's nothing to configure.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+pub struct Config { }
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Module;
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(_: Config) -> Self {
+		Module
+	}
+}
+
+
+pub type SearchConfig = websearch::SiteConfig;
+
+
+pub type SearchError = http::Error;
+
+
+#[async_trait]
+impl websearch::Module for Module {
+	type SearchConfig = SearchConfig;
+	type Error = SearchError;
+
+	async fn search(
+		&self,
+		query: &str,
+		search_config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error> {
+		lazy_static! {
+			static ref BASE_URL: Url = "https://html.duckduckgo.com/html/"
+				.parse()
+				.expect("invalid duckduckgo base url");
+		}
+
+		let url = BASE_URL
+			.dissect()
+			.append_query("q", &search_config.query(query))
+			.assemble();
+
+		log::debug!("duckduckgo query url: {}", url);
+
+		let page = http::Request
+			::new(&url)
+			.send()
+			.await?
+			.body_string()
+			.await?;
+
+		let doc = Html::parse_document(&page);
+
+		Ok(
+			websearch::parse_urls(
+				"duckduckgo",
+				scrap_links(&doc)
+			)
+		)
+	}
+}
+
+
+/// The result links, which point to a redirect with the target in the `uddg` parameter.
+fn scrap_links(doc: &Html) -> impl Iterator<Item = Box<str>> + '_ {
+	lazy_static! {
+		static ref SELECTOR: Selector = Selector
+			::parse("a.result__a")
+			.expect("invalid css selector");
+	}
+
+	doc
+		.select(&SELECTOR)
+		.filter_map(|link| link.value().attr("href"))
+		.filter_map(
+			|href| {
+				// Redirect links are protocol relative.
+				let href: Cow<str> = if href.starts_with("//") {
+					format!("https:{}", href).into()
+				}
+				else {
+					href.into()
+				};
+
+				let url: Url = href.parse().ok()?;
+
+				let target = url
+					.dissect()
+					.query_pairs()
+					.find(|(name, _)| name == "uddg")
+					.map(|(_, target)| target.into_owned());
+
+				Some(
+					target
+						.unwrap_or_else(|| href.into_owned())
+						.into_boxed_str()
+				)
+			}
+		)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_scrap_links() {
+		let doc = Html::parse_document(
+			r#"
+				<div class="result results_links results_links_deep web-result">
+					<h2 class="result__title">
+						<a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.beatport.com%2Ftrack%2Fkinetic%2Drampage%2F15161234&amp;rut=abc">Kinetic Rampage</a>
+					</h2>
+				</div>
+				<div class="result">
+					<h2 class="result__title">
+						<a rel="nofollow" class="result__a" href="https://skryptom.bandcamp.com/track/kinetic-rampage">Kinetic Rampage</a>
+					</h2>
+				</div>
+			"#
+		);
+
+		assert_eq!(
+			scrap_links(&doc).collect::<Vec<_>>(),
+			[
+				"https://www.beatport.com/track/kinetic-rampage/15161234".into(),
+				"https://skryptom.bandcamp.com/track/kinetic-rampage".into(),
+			] as [Box<str>; 2]
+		);
+	}
+}
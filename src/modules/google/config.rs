@@ -1,19 +1,41 @@
 use serde::{Deserialize, Deserializer};
 
 
+/// The custom search API returns at most 100 results per query.
+const MAX_RESULTS: u8 = 100;
+
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Config {
-	pub key: Box<str>,
+	/// Used in order, moving on to the next when one exhausts its daily quota.
+	pub keys: Box<[Box<str>]>,
+	/// The engine for the modules that don't configure their own.
+	pub custom_search: Option<Box<str>>,
+	/// How many results to fetch, paging through them 10 at a time.
+	pub results: u8,
 }
 
 
 impl<'de> Deserialize<'de> for Config {
 	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+
 		let config = ConfigFile::deserialize(deserializer)?;
 
+		if let Keys::Many(keys) = &config.google.key {
+			if keys.is_empty() {
+				return Err(D::Error::custom("google: key list is empty"));
+			}
+		}
+
 		Ok(
 			Config {
-				key: config.google.key
+				keys: match config.google.key {
+					Keys::One(key) => Box::new([key]),
+					Keys::Many(keys) => keys,
+				},
+				custom_search: config.google.custom_search,
+				results: config.google.results.clamp(1, MAX_RESULTS),
 			}
 		)
 	}
@@ -27,6 +49,24 @@ struct ConfigFile {
 
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
 struct Google {
-	key: Box<str>,
+	key: Keys,
+	#[serde(default)]
+	custom_search: Option<Box<str>>,
+	#[serde(default = "default_results")]
+	results: u8,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+	One(Box<str>),
+	Many(Box<[Box<str>]>),
+}
+
+
+fn default_results() -> u8 {
+	10
 }
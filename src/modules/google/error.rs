@@ -0,0 +1,212 @@
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::net::http;
+
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+	#[error("{0}")]
+	Http(#[from] http::Error),
+
+	#[error("daily quota exceeded for every key")]
+	QuotaExceeded,
+
+	#[error("invalid api key")]
+	InvalidKey,
+
+	#[error("invalid custom search engine id")]
+	InvalidCustomSearch,
+
+	#[error("api error {code}: {message}")]
+	Api {
+		code: u16,
+		message: Box<str>,
+	},
+}
+
+
+/// The error payload of the API.
+#[derive(Debug, Deserialize)]
+pub struct ErrorBody {
+	error: ApiError,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+	code: u16,
+	#[serde(default)]
+	message: Box<str>,
+	#[serde(default)]
+	status: Box<str>,
+	#[serde(default)]
+	errors: Box<[Reason]>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Reason {
+	#[serde(default)]
+	reason: Box<str>,
+}
+
+
+impl From<ErrorBody> for SearchError {
+	fn from(body: ErrorBody) -> Self {
+		let error = body.error;
+
+		let has_reason = |reasons: &[&str]| error.errors
+			.iter()
+			.any(|reason| reasons.contains(&reason.reason.as_ref()));
+
+		// Per minute throttling is reported as rateLimitExceeded too, but must not exhaust the
+		// key for the whole day.
+		let daily_rate_limit = has_reason(&["rateLimitExceeded"])
+			&& error.message.to_lowercase().contains("per day");
+
+		if has_reason(&["dailyLimitExceeded", "quotaExceeded"]) || daily_rate_limit {
+			SearchError::QuotaExceeded
+		}
+		else if has_reason(&["keyInvalid"]) || error.message.contains("API key not valid") {
+			SearchError::InvalidKey
+		}
+		// The query parameters are all valid, so an invalid argument can only be the engine id.
+		else if has_reason(&["invalid"]) || error.status.as_ref() == "INVALID_ARGUMENT" {
+			SearchError::InvalidCustomSearch
+		}
+		else {
+			SearchError::Api {
+				code: error.code,
+				message: error.message,
+			}
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+
+	fn error(payload: serde_json::Value) -> SearchError {
+		serde_json
+			::from_value::<ErrorBody>(payload)
+			.expect("invalid test payload")
+			.into()
+	}
+
+
+	#[test]
+	fn test_errors() {
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 429,
+						"message": "Quota exceeded for quota metric 'Queries' and limit 'Queries per day'.",
+						"errors": [
+							{
+								"message": "Quota exceeded for quota metric 'Queries' and limit 'Queries per day'.",
+								"domain": "global",
+								"reason": "rateLimitExceeded"
+							}
+						],
+						"status": "RESOURCE_EXHAUSTED"
+					}
+				})),
+				SearchError::QuotaExceeded
+			)
+		);
+
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 429,
+						"message": "Quota exceeded for quota metric 'Queries' and limit 'Queries per minute per user'.",
+						"errors": [
+							{
+								"message": "Quota exceeded for quota metric 'Queries' and limit 'Queries per minute per user'.",
+								"domain": "global",
+								"reason": "rateLimitExceeded"
+							}
+						],
+						"status": "RESOURCE_EXHAUSTED"
+					}
+				})),
+				SearchError::Api { code: 429, .. }
+			)
+		);
+
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 403,
+						"message": "Daily Limit Exceeded",
+						"errors": [
+							{ "domain": "usageLimits", "reason": "dailyLimitExceeded" }
+						]
+					}
+				})),
+				SearchError::QuotaExceeded
+			)
+		);
+
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 400,
+						"message": "API key not valid. Please pass a valid API key.",
+						"errors": [
+							{
+								"message": "API key not valid. Please pass a valid API key.",
+								"domain": "global",
+								"reason": "badRequest"
+							}
+						],
+						"status": "INVALID_ARGUMENT"
+					}
+				})),
+				SearchError::InvalidKey
+			)
+		);
+
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 400,
+						"message": "Request contains an invalid argument.",
+						"errors": [
+							{
+								"message": "Request contains an invalid argument.",
+								"domain": "global",
+								"reason": "badRequest"
+							}
+						],
+						"status": "INVALID_ARGUMENT"
+					}
+				})),
+				SearchError::InvalidCustomSearch
+			)
+		);
+
+		assert!(
+			matches!(
+				error(serde_json::json!({
+					"error": {
+						"code": 500,
+						"message": "Backend Error"
+					}
+				})),
+				SearchError::Api { code: 500, .. }
+			)
+		);
+	}
+}
@@ -1,7 +1,13 @@
 mod config;
+mod error;
 mod items;
+mod quota;
 
-use std::convert::TryInto;
+use std::{
+	collections::HashSet,
+	convert::TryInto,
+	sync::{Arc, Mutex},
+};
 
 use lazy_static::lazy_static;
 
@@ -14,13 +20,18 @@ use crate::net::{
 	http,
 	url::{self, Url}
 };
+use error::ErrorBody;
 use items::Items;
+use quota::Quota;
 pub use config::Config;
+pub use error::SearchError;
 
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct Module {
 	config: Config,
+	/// Shared by all clones, so that concurrent searches rotate keys together.
+	quota: Arc<Mutex<Quota>>,
 }
 
 
@@ -28,7 +39,10 @@ impl super::Module for Module {
 	type Config = Config;
 
 	fn new(config: Config) -> Self {
-		Module { config }
+		Module {
+			config,
+			quota: Arc::new(Mutex::new(Quota::open())),
+		}
 	}
 }
 
@@ -37,10 +51,92 @@ impl super::Module for Module {
 #[serde(rename_all(deserialize = "kebab-case"))]
 pub struct SearchConfig {
 	pub custom_search: Box<str>,
+	/// Restrict the results to this site, so that one engine may serve many modules.
+	#[serde(default)]
+	pub site_search: Option<Box<str>>,
 }
 
 
-pub type SearchError = http::Error;
+/// The most results per request allowed by the API.
+const PAGE_SIZE: u8 = 10;
+
+
+impl Module {
+	/// The engine for the modules that don't configure their own.
+	pub fn custom_search(&self) -> Option<&str> {
+		self.config.custom_search.as_deref()
+	}
+
+
+	fn available_key(&self) -> Option<&str> {
+		self.quota
+			.lock()
+			.expect("quota mutex poisoned")
+			.available(&self.config.keys)
+	}
+
+
+	fn exhaust_key(&self, key: &str) {
+		let position = self.config.keys
+			.iter()
+			.position(|k| k.as_ref() == key)
+			.unwrap_or_default();
+
+		log::warn!(
+			"google key {} of {} exhausted its daily quota",
+			position + 1,
+			self.config.keys.len()
+		);
+
+		self.quota
+			.lock()
+			.expect("quota mutex poisoned")
+			.exhaust(key)
+	}
+
+
+	async fn page(&self, url: &Url) -> Result<Items, SearchError> {
+		let mut response = http::Request
+			::new(url)
+			.set_require_success(false)
+			.send()
+			.await
+			.map_err(Into::<http::Error>::into)?;
+
+		if response.status().is_success() {
+			let items = response
+				.body_json()
+				.await
+				.map_err(Into::<http::Error>::into)?;
+
+			return Ok(items);
+		}
+
+		let status = response.status();
+
+		match response.body_json::<ErrorBody>().await {
+			Ok(error) => Err(error.into()),
+			Err(error) => {
+				log::debug!("google error body parse failed: {}", error);
+
+				Err(
+					Into::<http::Error>::into(http::request::Error::status(&status))
+						.into()
+				)
+			}
+		}
+	}
+}
+
+
+/// The `start` and `num` parameters of each page, to get the given amount of results.
+fn pages(results: u8) -> impl Iterator<Item = (u8, u8)> {
+	(0 .. results)
+		.step_by(PAGE_SIZE as usize)
+		.map(
+			move |offset| (offset + 1, PAGE_SIZE.min(results - offset))
+		)
+}
 
 
 #[async_trait]
@@ -59,43 +155,81 @@ impl websearch::Module for Module {
 				.expect("invalid google api base url");
 		}
 
-		let url = BASE_URL
-			.clone()
-			.extend_query(
-				&[
-					("q", query),
-					("key", &self.config.key),
-					("cx", &search_config.custom_search)
-				]
-			)
-			.assemble();
-
-		log::debug!("google query url: {}", url);
-
-		let items = http::Request
-			::new(&url)
-			.send()
-			.await
-			.map_err(Into::<http::Error>::into)?
-			.body_json::<Items>()
-			.await
-			.map_err(Into::<http::Error>::into)?;
+		let mut seen = HashSet::new();
+		let mut urls = Vec::new();
+
+		'pages: for (start, num) in pages(self.config.results) {
+			let items = loop {
+				let key = match self.available_key() {
+					Some(key) => key,
+					// Keep the results of the pages already fetched.
+					None if start > 1 => {
+						log::warn!("google quota exceeded after {} results", urls.len());
+						break 'pages;
+					},
+					None => return Err(SearchError::QuotaExceeded),
+				};
+
+				let mut url = BASE_URL.clone();
+
+				url.extend_query(
+					&[
+						("q", query),
+						("key", key),
+						("cx", &search_config.custom_search),
+						("start", &start.to_string()),
+						("num", &num.to_string()),
+					]
+				);
+
+				if let Some(site) = &search_config.site_search {
+					url.append_query("siteSearch", site);
+				}
 
-		let items = items
-			.into_iter()
-			.filter_map(
-				|item| {
-					let result = item.parse();
+				let url = url.assemble();
 
-					if result.is_err() {
-						log::debug!("google url parse failed: {}", item);
-					}
+				log::debug!("google query url: {}", url);
 
-					result.ok()
+				match self.page(&url).await {
+					Err(SearchError::QuotaExceeded) => self.exhaust_key(key),
+					result => break result?,
 				}
-			)
-			.collect();
+			};
+
+			let count = items.0.len();
+
+			// Pages may overlap when results shift between requests.
+			let items = items
+				.into_iter()
+				.filter(|item| seen.insert(item.clone()));
+
+			urls.extend(
+				websearch::parse_urls("google", items)
+					.into_vec()
+			);
+
+			// A short page is the last one.
+			if count < num as usize {
+				break;
+			}
+		}
+
+		let items = urls.into_boxed_slice();
 
 		Ok(items)
 	}
 }
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_pages() {
+		assert_eq!(pages(10).collect::<Vec<_>>(), [(1, 10)]);
+		assert_eq!(pages(25).collect::<Vec<_>>(), [(1, 10), (11, 10), (21, 5)]);
+		assert_eq!(pages(3).collect::<Vec<_>>(), [(1, 3)]);
+	}
+}
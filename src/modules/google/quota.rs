@@ -0,0 +1,188 @@
+use std::{
+	collections::HashSet,
+	fs,
+	path::PathBuf,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::hash;
+
+
+const FILENAME: &str = "google-quota.json";
+
+
+/// Which keys have exhausted their daily quota. Persisted, so that later runs on the same
+/// day won't waste requests on them.
+#[derive(Debug, Default)]
+pub struct Quota {
+	path: Option<PathBuf>,
+	state: State,
+}
+
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+struct State {
+	day: u64,
+	/// Fingerprints, so that the keys themselves aren't written.
+	exhausted: HashSet<u64>,
+}
+
+
+impl Quota {
+	/// Load the state from the XDG data directory. Failures are logged, falling back to an
+	/// in memory state, as the quota is merely an optimization.
+	pub fn open() -> Self {
+		let path = xdg::BaseDirectories
+			::with_prefix("slizzy")
+			.map_err(anyhow::Error::from)
+			.and_then(
+				|dirs| dirs
+					.place_data_file(FILENAME)
+					.map_err(Into::into)
+			);
+
+		match path {
+			Ok(path) => Self::at(path),
+			Err(error) => {
+				log::warn!("google quota state unavailable: {}", error);
+				Self::default()
+			}
+		}
+	}
+
+
+	pub fn at<P: Into<PathBuf>>(path: P) -> Self {
+		let path = path.into();
+
+		let state = fs
+			::read(&path)
+			.ok()
+			.and_then(
+				|data| serde_json
+					::from_slice(&data)
+					.map_err(|error| log::warn!("invalid google quota state: {}", error))
+					.ok()
+			)
+			.unwrap_or_default();
+
+		Self {
+			path: Some(path),
+			state,
+		}
+	}
+
+
+	/// The first key that still has quota for the day.
+	pub fn available<'a>(&mut self, keys: &'a [Box<str>]) -> Option<&'a str> {
+		self.expire(today());
+
+		keys
+			.iter()
+			.find(|key| !self.state.exhausted.contains(&fingerprint(key)))
+			.map(AsRef::as_ref)
+	}
+
+
+	pub fn exhaust(&mut self, key: &str) {
+		self.expire(today());
+
+		self.state.exhausted.insert(fingerprint(key));
+
+		self.save();
+	}
+
+
+	fn expire(&mut self, day: u64) {
+		if self.state.day != day {
+			self.state = State {
+				day,
+				exhausted: HashSet::new(),
+			};
+		}
+	}
+
+
+	fn save(&self) {
+		let path = match &self.path {
+			Some(path) => path,
+			None => return,
+		};
+
+		let result = serde_json
+			::to_vec(&self.state)
+			.map_err(anyhow::Error::from)
+			.and_then(
+				|data| fs
+					::write(path, data)
+					.map_err(Into::into)
+			);
+
+		if let Err(error) = result {
+			log::warn!("failed to save google quota state: {}", error);
+		}
+	}
+}
+
+
+/// The quota resets at midnight, Pacific time. Daylight saving is ignored, which at worst
+/// keeps a key exhausted for an extra hour.
+fn today() -> u64 {
+	const PACIFIC_OFFSET: u64 = 8 * 60 * 60;
+
+	let now = SystemTime
+		::now()
+		.duration_since(UNIX_EPOCH)
+		.expect("system time before unix epoch")
+		.as_secs();
+
+	(now - PACIFIC_OFFSET) / (24 * 60 * 60)
+}
+
+
+/// A stable hash, as it is persisted.
+fn fingerprint(key: &str) -> u64 {
+	hash::fnv1a(key.as_bytes())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_quota() {
+		let path = std::env::temp_dir().join(
+			format!("slizzy-google-quota-test-{}.json", std::process::id())
+		);
+		let _ = fs::remove_file(&path);
+
+		let keys: Box<[Box<str>]> = Box::new(["first".into(), "second".into()]);
+
+		let mut quota = Quota::at(&path);
+
+		assert_eq!(quota.available(&keys), Some("first"));
+
+		quota.exhaust("first");
+
+		assert_eq!(quota.available(&keys), Some("second"));
+
+		// Persisted for later runs.
+		let mut quota = Quota::at(&path);
+
+		assert_eq!(quota.available(&keys), Some("second"));
+
+		quota.exhaust("second");
+
+		assert_eq!(quota.available(&keys), None);
+
+		// Reset on the next day.
+		quota.expire(today() + 1);
+
+		assert_eq!(quota.state.exhausted.len(), 0);
+
+		let _ = fs::remove_file(&path);
+	}
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+
+
+/// The serialized form of a progress report, one per line in JSON output. Statuses and
+/// filters are serialized as `{"type": ..., "value": ...}`, and durations as seconds.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a, Id, Item, Status, ItemStatus>
+where
+	Item: ?Sized,
+	Status: ?Sized,
+	ItemStatus: ?Sized,
+{
+	SizeHint {
+		lower: usize,
+		upper: Option<usize>,
+	},
+	Item {
+		id: Id,
+		item: &'a Item,
+	},
+	ItemStatus {
+		id: Id,
+		status: &'a ItemStatus,
+	},
+	Status {
+		status: &'a Status,
+	},
+	Finish {
+		status: &'a Status,
+	},
+}
+
+
+/// An event, along with where it came from.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Record<'a, E> {
+	pub track: &'a str,
+	pub module: &'a str,
+	#[serde(flatten)]
+	pub event: E,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::{
+		modules::music2k::{ItemStatus, Filter, Status},
+		track::Duration,
+	};
+
+
+	#[test]
+	fn test_serialize() {
+		let status = ItemStatus::Filtered(
+			Filter::Duration(Duration::from_seconds(90))
+		);
+
+		let event: Event<u8, str, Status, ItemStatus> = Event::ItemStatus { id: 3, status: &status };
+
+		let record = Record {
+			track: "Test - track",
+			module: "music2k",
+			event,
+		};
+
+		assert_eq!(
+			serde_json::to_string(&record).expect("failed to serialize event"),
+			concat!(
+				r#"{"track":"Test - track","module":"music2k","event":"item_status","id":3,"#,
+				r#""status":{"type":"filtered","value":{"type":"duration","value":90}}}"#,
+			)
+		);
+	}
+}
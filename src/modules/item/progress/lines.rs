@@ -0,0 +1,179 @@
+use std::{
+	collections::HashMap,
+	fmt::Display,
+	fs::File,
+	io::{self, Write},
+	marker::PhantomData,
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+
+use super::{Progress, event::{Event, Record}};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+	/// Human readable lines.
+	Plain,
+	/// One JSON event per line.
+	Json,
+}
+
+
+/// Where lines are written to. Shared by all reporters, so that lines don't interleave.
+#[derive(Clone)]
+pub struct Sink(Arc<Mutex<dyn Write + Send>>);
+
+
+impl Sink {
+	pub fn stdout() -> Self {
+		Self(
+			Arc::new(Mutex::new(io::stdout()))
+		)
+	}
+
+
+	pub fn file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = File::create(path)?;
+
+		Ok(
+			Self(
+				Arc::new(Mutex::new(io::LineWriter::new(file)))
+			)
+		)
+	}
+
+
+	fn write_line(&self, line: &str) {
+		let mut out = self.0
+			.lock()
+			.expect("sink mutex poisoned");
+
+		// There's nothing sensible to do if the output is closed.
+		let _ = writeln!(out, "{}", line);
+	}
+}
+
+
+impl std::fmt::Debug for Sink {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Sink")
+			.finish()
+	}
+}
+
+
+/// Reports progress as lines, for when there is no terminal. Repeated item statuses are
+/// omitted, so that downloads don't flood the output.
+#[derive(Debug)]
+pub struct LineProgress<Id, Item, ItemStatus, Status>
+where
+	Id: 'static,
+	Item: 'static + ?Sized,
+	ItemStatus: 'static + ?Sized,
+	Status: 'static + ?Sized,
+{
+	track: Box<str>,
+	module: &'static str,
+	format: Format,
+	sink: Sink,
+	last_status: Mutex<HashMap<Id, String>>,
+	item: PhantomData<&'static Item>,
+	item_status: PhantomData<&'static ItemStatus>,
+	status: PhantomData<&'static Status>,
+}
+
+
+impl<Id, Item, ItemStatus, Status> LineProgress<Id, Item, ItemStatus, Status>
+where
+	Id: Eq + std::hash::Hash + Display + Serialize,
+	Item: Display + Serialize + ?Sized,
+	ItemStatus: Display + Serialize + ?Sized,
+	Status: Display + Serialize + ?Sized,
+{
+	pub fn new(track: &str, module: &'static str, format: Format, sink: Sink) -> Self {
+		LineProgress {
+			track: track.into(),
+			module,
+			format,
+			sink,
+			last_status: Mutex::new(HashMap::new()),
+			item: PhantomData,
+			item_status: PhantomData,
+			status: PhantomData,
+		}
+	}
+
+
+	fn print(&self, event: Event<&Id, Item, Status, ItemStatus>) {
+		let line = match self.format {
+			Format::Plain => match event {
+				Event::SizeHint { .. } => return,
+				Event::Item { id, item } => format!("{} #{}: {}", self.module, id, item),
+				Event::ItemStatus { id, status } => format!("{} #{}: {}", self.module, id, status),
+				Event::Status { status } => format!("{}: {}", self.module, status),
+				Event::Finish { status } => format!("{}: {}", self.module, status),
+			},
+
+			Format::Json => serde_json
+				::to_string(
+					&Record {
+						track: &self.track,
+						module: self.module,
+						event,
+					}
+				)
+				.expect("failed to serialize event"),
+		};
+
+		self.sink.write_line(&line);
+	}
+}
+
+
+impl<Id, Item, ItemStatus, Status> Progress for LineProgress<Id, Item, ItemStatus, Status>
+where
+	Id: Eq + Ord + std::hash::Hash + Display + Serialize,
+	Item: Display + Serialize + ?Sized,
+	ItemStatus: Display + Serialize + ?Sized,
+	Status: Display + Serialize + ?Sized,
+{
+	type Id = Id;
+	type Item = Item;
+	type Status = Status;
+	type ItemStatus = ItemStatus;
+
+	fn size_hint(&self, (lower, upper): (usize, Option<usize>)) {
+		self.print(Event::SizeHint { lower, upper });
+	}
+
+	fn item(&self, id: Self::Id, item: &Self::Item) {
+		self.print(Event::Item { id: &id, item });
+	}
+
+	fn item_status(&self, id: Self::Id, status: &Self::ItemStatus) {
+		let text = status.to_string();
+
+		let mut last_status = self.last_status
+			.lock()
+			.expect("progress mutex poisoned");
+
+		if last_status.get(&id) == Some(&text) {
+			return;
+		}
+
+		self.print(Event::ItemStatus { id: &id, status });
+
+		last_status.insert(id, text);
+	}
+
+	fn status(&self, status: &Self::Status) {
+		self.print(Event::Status { status });
+	}
+
+	fn finish(&self, status: &Self::Status) {
+		self.print(Event::Finish { status });
+	}
+}
@@ -1,6 +1,11 @@
+pub mod event;
+mod lines;
 mod noprogress;
+mod tee;
 
+pub use lines::{LineProgress, Format as LineFormat, Sink as LineSink};
 pub use noprogress::NoProgress;
+pub use tee::Tee;
 
 
 pub trait Progress {
@@ -22,6 +27,37 @@ pub trait Progress {
 }
 
 
+impl<P> Progress for Box<P>
+where
+	P: Progress + ?Sized
+{
+	type Id = P::Id;
+	type Item = P::Item;
+	type Status = P::Status;
+	type ItemStatus = P::ItemStatus;
+
+	fn size_hint(&self, hint: (usize, Option<usize>)) {
+		self.as_ref().size_hint(hint)
+	}
+
+	fn item(&self, id: Self::Id, item: &Self::Item) {
+		self.as_ref().item(id, item)
+	}
+
+	fn item_status(&self, id: Self::Id, status: &Self::ItemStatus) {
+		self.as_ref().item_status(id, status)
+	}
+
+	fn status(&self, status: &Self::Status) {
+		self.as_ref().status(status)
+	}
+
+	fn finish(&self, status: &Self::Status) {
+		self.as_ref().finish(status)
+	}
+}
+
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Message<Id, Item, Status, ItemStatus> {
 	SizeHint(usize, Option<usize>),
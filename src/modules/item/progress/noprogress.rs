@@ -7,9 +7,9 @@ use super::Progress;
 pub struct NoProgress<Id, Item, ItemStatus, Status>
 where
 	Id: 'static,
-	Item: 'static,
-	ItemStatus: 'static,
-	Status: 'static,
+	Item: 'static + ?Sized,
+	ItemStatus: 'static + ?Sized,
+	Status: 'static + ?Sized,
 {
 	id: PhantomData<&'static Id>,
 	item: PhantomData<&'static Item>,
@@ -18,7 +18,12 @@ where
 }
 
 
-impl<Id, Item, ItemStatus, Status> NoProgress<Id, Item, ItemStatus, Status> {
+impl<Id, Item, ItemStatus, Status> NoProgress<Id, Item, ItemStatus, Status>
+where
+	Item: ?Sized,
+	ItemStatus: ?Sized,
+	Status: ?Sized,
+{
 	pub fn new() -> Self {
 		NoProgress {
 			id: PhantomData,
@@ -32,7 +37,10 @@ impl<Id, Item, ItemStatus, Status> NoProgress<Id, Item, ItemStatus, Status> {
 
 impl<Id, Item, ItemStatus, Status> Progress for NoProgress<Id, Item, ItemStatus, Status>
 where
-	Id: Eq + Ord + std::hash::Hash
+	Id: Eq + Ord + std::hash::Hash,
+	Item: ?Sized,
+	ItemStatus: ?Sized,
+	Status: ?Sized,
 {
 	type Id = Id;
 	type Item = Item;
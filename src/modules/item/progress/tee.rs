@@ -0,0 +1,49 @@
+use super::Progress;
+
+
+/// Reports progress to two reporters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tee<A, B>(pub A, pub B);
+
+
+impl<A, B> Progress for Tee<A, B>
+where
+	A: Progress,
+	A::Id: Clone,
+	B: Progress<
+		Id = A::Id,
+		Item = A::Item,
+		Status = A::Status,
+		ItemStatus = A::ItemStatus,
+	>,
+{
+	type Id = A::Id;
+	type Item = A::Item;
+	type Status = A::Status;
+	type ItemStatus = A::ItemStatus;
+
+	fn size_hint(&self, hint: (usize, Option<usize>)) {
+		self.0.size_hint(hint);
+		self.1.size_hint(hint);
+	}
+
+	fn item(&self, id: Self::Id, item: &Self::Item) {
+		self.0.item(id.clone(), item);
+		self.1.item(id, item);
+	}
+
+	fn item_status(&self, id: Self::Id, status: &Self::ItemStatus) {
+		self.0.item_status(id.clone(), status);
+		self.1.item_status(id, status);
+	}
+
+	fn status(&self, status: &Self::Status) {
+		self.0.status(status);
+		self.1.status(status);
+	}
+
+	fn finish(&self, status: &Self::Status) {
+		self.0.finish(status);
+		self.1.finish(status);
+	}
+}
@@ -0,0 +1,104 @@
+use std::{convert::TryFrom, path::PathBuf};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+	sim::Sim,
+	track::{IdCleaner, Duration},
+};
+
+
+#[derive(Debug, Clone)]
+pub struct Config {
+	pub directories: Box<[PathBuf]>,
+	pub sim_threshold: Sim,
+	pub on_match: OnMatch,
+	pub duration_tolerance: u16,
+	pub id_cleaner: IdCleaner,
+}
+
+
+/// What to do when the track is already in the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnMatch {
+	/// Report the library file, skipping the download.
+	Report,
+	/// Copy the library file to the output directory.
+	Copy,
+}
+
+
+impl Default for OnMatch {
+	fn default() -> Self { OnMatch::Report }
+}
+
+
+impl Config {
+	pub fn duration_range(&self, duration: Duration) -> std::ops::Range<Duration> {
+		let seconds = duration.as_seconds();
+
+		std::ops::Range {
+			start: Duration::from_seconds(
+				seconds.saturating_sub(self.duration_tolerance)
+			),
+			end: Duration::from_seconds(
+				seconds.saturating_add(self.duration_tolerance)
+			),
+		}
+	}
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Config {
+				duration_tolerance : config.slizzy.duration_tolerance,
+				id_cleaner         : config.slizzy.id_clean,
+				directories        : config.library.directories,
+				sim_threshold      : config.library.sim_threshold,
+				on_match           : config.library.on_match,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	slizzy: Slizzy,
+	// Optional, so that older config files remain valid.
+	#[serde(default)]
+	library: Library,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+struct Slizzy {
+	duration_tolerance: u16,
+	id_clean: IdCleaner,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all(deserialize = "kebab-case"))]
+struct Library {
+	directories: Box<[PathBuf]>,
+	sim_threshold: Sim,
+	on_match: OnMatch,
+}
+
+
+impl Default for Library {
+	fn default() -> Self {
+		Self {
+			directories: Default::default(),
+			sim_threshold: Sim::try_from(80_u8).expect("invalid default library sim threshold"),
+			on_match: OnMatch::default(),
+		}
+	}
+}
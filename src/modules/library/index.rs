@@ -0,0 +1,337 @@
+use std::{
+	collections::HashMap,
+	fs,
+	io::{self, ErrorKind},
+	path::{Path, PathBuf},
+	time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use thiserror::Error;
+
+use crate::{
+	reference,
+	track::{Duration, Track},
+};
+
+
+const FILENAME: &str = "library.json";
+
+const EXTENSIONS: &[&str] = &["mp3", "m4a", "mp4"];
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("xdg error: {0}")]
+	Xdg(xdg::BaseDirectoriesError),
+
+	#[error("io error: {0}")]
+	Io(io::Error),
+
+	#[error("serialization error: {0}")]
+	Json(serde_json::Error),
+}
+
+
+/// An indexed library file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Entry {
+	pub id: Box<str>,
+	pub duration: Duration,
+	/// The file modification time, in seconds since the unix epoch, to detect changes.
+	pub modified: u64,
+}
+
+
+/// The library files, by path. Stored as JSON, so that only new or modified files must be
+/// read on later runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+	path: PathBuf,
+	pub entries: HashMap<PathBuf, Entry>,
+	/// The modification time of unreadable files, so that they are only retried when changed.
+	pub failed: HashMap<PathBuf, u64>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct IndexFile {
+	entries: HashMap<PathBuf, Entry>,
+	#[serde(default)]
+	failed: HashMap<PathBuf, u64>,
+}
+
+
+impl Index {
+	/// Open the index in the XDG data directory.
+	pub fn open() -> Result<Self, Error> {
+		let xdg_dirs = xdg::BaseDirectories
+			::with_prefix("slizzy")
+			.map_err(Error::Xdg)?;
+
+		let path = xdg_dirs
+			.place_data_file(FILENAME)
+			.map_err(Error::Io)?;
+
+		log::debug!("library index file: {:#?}", path);
+
+		Self::at(path)
+	}
+
+
+	pub fn at<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
+		let path = path.into();
+
+		let file = match fs::read(&path) {
+			// A corrupt index is rebuilt rather than disabling the library.
+			Ok(data) => serde_json
+				::from_slice(&data)
+				.unwrap_or_else(
+					|error| {
+						log::warn!("invalid library index, rebuilding it: {}", error);
+						IndexFile::default()
+					}
+				),
+			Err(error) if error.kind() == ErrorKind::NotFound => IndexFile::default(),
+			Err(error) => return Err(Error::Io(error)),
+		};
+
+		Ok(
+			Self {
+				path,
+				entries: file.entries,
+				failed: file.failed,
+			}
+		)
+	}
+
+
+	/// Index the files in the directories, recursively. Unchanged files are kept, and files
+	/// that are gone are dropped. Unreadable files are logged and recorded as failed.
+	pub fn update(&mut self, directories: &[PathBuf]) {
+		let mut files = Vec::new();
+
+		for directory in directories {
+			scan(directory, &mut files);
+		}
+
+		let mut entries = HashMap::with_capacity(files.len());
+		let mut failed = HashMap::new();
+
+		for (path, modified) in files {
+			if self.failed.remove(&path) == Some(modified) {
+				failed.insert(path, modified);
+				continue;
+			}
+
+			let entry = match self.entries.remove(&path) {
+				Some(entry) if entry.modified == modified => entry,
+				_ => match read(&path) {
+					Ok((id, duration)) => Entry { id, duration, modified },
+					Err(error) => {
+						log::debug!("skipping library file {:#?}: {}", path, error);
+						failed.insert(path, modified);
+						continue;
+					}
+				},
+			};
+
+			entries.insert(path, entry);
+		}
+
+		log::debug!("library index: {} files, {} unreadable", entries.len(), failed.len());
+
+		self.entries = entries;
+		self.failed = failed;
+	}
+
+
+	/// Write to a temporary file first, so that the index is replaced atomically.
+	pub fn save(&self) -> Result<(), Error> {
+		let data = serde_json
+			::to_vec(
+				&IndexFile {
+					entries: self.entries.clone(),
+					failed: self.failed.clone(),
+				}
+			)
+			.map_err(Error::Json)?;
+
+		let temp = self.path.with_extension(
+			format!("{}.tmp", std::process::id())
+		);
+
+		fs
+			::write(&temp, data)
+			.and_then(|()| fs::rename(&temp, &self.path))
+			.map_err(
+				|error| {
+					let _ = fs::remove_file(&temp);
+					Error::Io(error)
+				}
+			)
+	}
+}
+
+
+/// Collect the supported files with their modification time.
+fn scan(directory: &Path, files: &mut Vec<(PathBuf, u64)>) {
+	let dir = match fs::read_dir(directory) {
+		Ok(dir) => dir,
+		Err(error) => {
+			log::warn!("failed to read library directory {:#?}: {}", directory, error);
+			return;
+		}
+	};
+
+	for entry in dir.filter_map(Result::ok) {
+		let path = entry.path();
+
+		let metadata = match entry.metadata() {
+			Ok(metadata) => metadata,
+			Err(_) => continue,
+		};
+
+		if metadata.is_dir() {
+			scan(&path, files);
+			continue;
+		}
+
+		let supported = path
+			.extension()
+			.and_then(|extension| extension.to_str())
+			.map(|extension| EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+			.unwrap_or(false);
+
+		if supported {
+			files.push((path, modified(&metadata)));
+		}
+	}
+}
+
+
+/// The modification time, in seconds since the unix epoch.
+fn modified(metadata: &fs::Metadata) -> u64 {
+	metadata
+		.modified()
+		.ok()
+		.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+		.map(|time| time.as_secs())
+		.unwrap_or_default()
+}
+
+
+/// Read the track id from the tags, or else from the filename.
+fn read(path: &Path) -> Result<(Box<str>, Duration), reference::Error> {
+	let (duration, text) = reference::probe(path)?;
+
+	let track = match reference::from_tags(text) {
+		Err(reference::Error::MissingTags) => from_filename(path),
+		other => other,
+	}?;
+
+	Ok((track.id().as_ref().into(), duration))
+}
+
+
+fn from_filename(path: &Path) -> Result<Track, reference::Error> {
+	let stem = path
+		.file_stem()
+		.and_then(|stem| stem.to_str())
+		.ok_or(reference::Error::MissingTags)?;
+
+	Track
+		::new(stem)
+		.map_err(reference::Error::Id)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_filename() {
+		let track = from_filename(Path::new("/music/Somne - Kinetic Rampage (Extended Mix).mp3"))
+			.expect("failed to parse filename");
+
+		assert_eq!(track.id().as_ref(), "Somne - Kinetic Rampage (Extended Mix)");
+	}
+
+
+	#[test]
+	fn test_index() {
+		let dir = std::env::temp_dir().join(
+			format!("slizzy-library-test-{}", std::process::id())
+		);
+		let music = dir.join("music");
+		fs::create_dir_all(music.join("nested")).expect("failed to create test dir");
+
+		// Unreadable as mp3, so it must be recorded as failed.
+		fs::write(music.join("nested/Artist - Track.mp3"), b"not an mp3").expect("failed to write test file");
+		fs::write(music.join("cover.jpg"), b"not audio").expect("failed to write test file");
+
+		let mut index = Index::at(dir.join(FILENAME)).expect("failed to open index");
+
+		index.entries.insert(
+			music.join("gone.mp3"),
+			Entry {
+				id: "Artist - Gone".into(),
+				duration: Duration::from_seconds(300),
+				modified: 0,
+			}
+		);
+
+		index.update(std::slice::from_ref(&music));
+
+		assert!(index.entries.is_empty());
+		let unreadable = music.join("nested/Artist - Track.mp3");
+		let unreadable_modified = modified(
+			&fs::metadata(&unreadable).expect("failed to stat test file")
+		);
+
+		assert_eq!(
+			index.failed,
+			vec![(unreadable.clone(), unreadable_modified)]
+				.into_iter()
+				.collect()
+		);
+
+		let entry = Entry {
+			id: "Artist - Track".into(),
+			duration: Duration::from_seconds(300),
+			modified: 1,
+		};
+
+		index.entries.insert(music.join("nested/Artist - Track.mp3"), entry.clone());
+
+		index.save().expect("failed to save index");
+
+		let index = Index::at(dir.join(FILENAME)).expect("failed to open index");
+
+		assert_eq!(
+			index.entries.get(&music.join("nested/Artist - Track.mp3")),
+			Some(&entry)
+		);
+
+		assert_eq!(index.failed.get(&unreadable), Some(&unreadable_modified));
+
+		assert_eq!(
+			fs::read_dir(&dir)
+				.expect("failed to read test dir")
+				.count(),
+			2, // The index and the music directory, without temporary files.
+		);
+
+		// A torn write is rebuilt instead of failing.
+		fs::write(dir.join(FILENAME), b"{\"entries\": {").expect("failed to write test index");
+
+		let index = Index::at(dir.join(FILENAME)).expect("failed to open corrupt index");
+
+		assert!(index.entries.is_empty());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
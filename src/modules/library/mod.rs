@@ -0,0 +1,60 @@
+mod config;
+mod index;
+mod source;
+pub mod tui;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use futures::lock::Mutex;
+
+use crate::track::Track;
+use super::tracksource;
+pub use config::{Config, OnMatch};
+pub use source::{
+	Error as SourceError,
+	Params as SourceParams,
+	Status,
+	ItemStatus,
+	Found,
+	Owned,
+};
+
+
+/// Searches the local music library, so that owned tracks aren't downloaded again.
+#[derive(Debug, Clone)]
+pub struct Module {
+	config: Config,
+	/// Loaded and updated once per process, and shared by the clones.
+	index: Arc<Mutex<Option<Arc<index::Index>>>>,
+}
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(config: Config) -> Self {
+		Module {
+			config,
+			index: Arc::new(Mutex::new(None)),
+		}
+	}
+}
+
+
+#[async_trait(?Send)]
+impl tracksource::Module for Module {
+	type Params = SourceParams;
+	type Error = SourceError;
+
+	async fn fetch(
+		&self,
+		track: &Track,
+		params: Self::Params
+	) -> Result<(), Self::Error> {
+		source
+			::fetch(self, track, params)
+			.await
+	}
+}
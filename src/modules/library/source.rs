@@ -0,0 +1,529 @@
+use std::{
+	cell::RefCell,
+	fs::{self, File},
+	io,
+	path::{Path, PathBuf},
+	rc::Rc,
+	sync::Arc,
+	thread,
+};
+
+use thiserror::Error;
+
+use serde::Serialize;
+
+use futures::channel::oneshot;
+
+use crate::{
+	report_wrapped,
+	track::Track,
+	output::Output,
+	sim::{self, Sim},
+	rank,
+	util::io::fs::file,
+};
+use super::super::{item, tracksource};
+use super::{OnMatch, index::{self, Entry, Index}};
+
+
+/// How many of the best matches to report.
+const MAX_MATCHES: usize = 10;
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("index error: {0}")]
+	Index(index::Error),
+
+	#[error("copy error: {0}")]
+	Copy(io::Error),
+}
+
+
+/// The track is already in the library.
+#[derive(Debug, Error)]
+#[error("track already in the library at {}", .0.display())]
+pub struct Owned(pub PathBuf);
+
+
+/// The library file matching the track, if any. Clones share the same slot.
+#[derive(Debug, Clone, Default)]
+pub struct Found(Rc<RefCell<Option<PathBuf>>>);
+
+
+impl Found {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+
+	fn set(&self, path: PathBuf) {
+		self.0.replace(Some(path));
+	}
+
+
+	pub fn take(&self) -> Option<PathBuf> {
+		self.0.take()
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ItemStatus {
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		io::Error
+	),
+	Match(Sim),
+	Owned(Sim),
+	Copied(Sim),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+			ItemStatus::Match(sim) => write!(f, "match: {}% similarity", sim.value()),
+			ItemStatus::Owned(sim) => write!(f, "already owned: {}% similarity", sim.value()),
+			ItemStatus::Copied(sim) => write!(f, "copied: {}% similarity", sim.value()),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Status {
+	Indexing,
+	Searching,
+	NoDirectories,
+	NotFound,
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
+	Done,
+}
+
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Indexing => f.write_str("indexing"),
+			Status::Searching => f.write_str("searching"),
+			Status::NoDirectories => f.write_str("no directories configured"),
+			Status::NotFound => f.write_str("not in the library"),
+			Status::Error(error) => write!(f, "error: {}", error),
+			Status::Done => f.write_str("done"),
+		}
+	}
+}
+
+
+pub struct Params {
+	pub progress: Box<
+		dyn item::progress::Progress<
+			Id = u8,
+			Item = str,
+			Status = Status,
+			ItemStatus = ItemStatus,
+		> + Send
+	>,
+	pub output: Output,
+	pub downloads: tracksource::Downloads,
+	pub found: Found,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.field("output", &self.output)
+			.field("downloads", &self.downloads)
+			.field("found", &self.found)
+			.finish()
+	}
+}
+
+
+/// The index, loaded and updated on the first fetch only, as scanning the directories may
+/// take a while.
+async fn shared_index(module: &super::Module) -> Result<Arc<Index>, index::Error> {
+	let mut shared = module.index.lock().await;
+
+	if let Some(index) = shared.as_ref() {
+		return Ok(index.clone());
+	}
+
+	let index = Arc::new(
+		load_index(module.config.directories.clone()).await?
+	);
+
+	*shared = Some(index.clone());
+
+	Ok(index)
+}
+
+
+/// Update the index in its own thread, as reading new files may take a while.
+async fn load_index(directories: Box<[PathBuf]>) -> Result<Index, index::Error> {
+	let (tx, rx) = oneshot::channel();
+
+	thread::spawn(
+		move || {
+			let result = Index
+				::open()
+				.and_then(
+					|mut index| {
+						index.update(&directories);
+						index.save()?;
+						Ok(index)
+					}
+				);
+
+			// The receiver is only dropped if the fetch is aborted.
+			let _ = tx.send(result);
+		}
+	);
+
+	rx
+		.await
+		.expect("library thread dropped the channel")
+}
+
+
+/// The entries matching the track, best first. The duration is checked first, as it is
+/// much cheaper than the similarity.
+fn find<'a>(
+	config: &super::Config,
+	entries: impl IntoIterator<Item = (&'a PathBuf, &'a Entry)>,
+	track: &Track,
+) -> Vec<(&'a Path, Sim)> {
+	let duration_range = track.duration.map(
+		|duration| config.duration_range(duration)
+	);
+
+	let mut matches: Vec<_> = entries
+		.into_iter()
+		.filter(
+			|(_, entry)| duration_range
+				.as_ref()
+				.map(|range| range.contains(&entry.duration))
+				.unwrap_or(true)
+		)
+		.filter_map(
+			|(path, entry)| {
+				let id = config.id_cleaner.clean(&entry.id);
+
+				let similarity = sim::str(&id, track.id());
+
+				if similarity < config.sim_threshold {
+					None
+				}
+				else {
+					let closeness = track.duration.map(
+						|duration| rank::closeness(
+							entry.duration.as_seconds() as f32,
+							duration.as_seconds() as f32,
+							config.duration_tolerance as f32,
+						)
+					);
+
+					Some((path.as_path(), similarity, closeness.unwrap_or_default()))
+				}
+			}
+		)
+		.collect();
+
+	matches.sort_by(
+		|(_, sim1, closeness1), (_, sim2, closeness2)| sim2
+			.cmp(sim1)
+			.then(
+				closeness2
+					.partial_cmp(closeness1)
+					.unwrap_or(std::cmp::Ordering::Equal)
+			)
+	);
+
+	matches
+		.into_iter()
+		.take(MAX_MATCHES)
+		.map(|(path, sim, _)| (path, sim))
+		.collect()
+}
+
+
+/// Whether both paths are the same file. The destination may not exist yet.
+fn same_file(source: &Path, destination: &Path) -> io::Result<bool> {
+	let source = source.canonicalize()?;
+
+	match destination.canonicalize() {
+		Ok(destination) => Ok(source == destination),
+		Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+		Err(error) => Err(error),
+	}
+}
+
+
+/// Copy the library file to the output, never overwriting existing files. Yields `None`
+/// when the output is the library file itself, as when the output dir is in the library.
+fn copy(
+	track: &Track,
+	path: &Path,
+	output: &Output,
+) -> Result<Option<tracksource::Download>, io::Error> {
+	let stem = path
+		.file_stem()
+		.map(Path::new)
+		.unwrap_or_else(|| Path::new(track.id().as_ref()));
+
+	let extension = path.extension();
+
+	let destination = output.path(track.id(), stem, extension);
+
+	if same_file(path, &destination)? {
+		log::info!("library file is already in the output: {:#?}", path);
+		return Ok(None);
+	}
+
+	if let Some(parent) = destination.parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	// Reserve a unique name, as another file may have the same name.
+	let (mut target, destination) = file::create_unique_at(&destination, extension)?;
+
+	let result = File
+		::open(path)
+		.and_then(|mut source| io::copy(&mut source, &mut target));
+
+	if let Err(error) = result {
+		// Don't leave garbage behind.
+		let _ = fs::remove_file(&destination);
+		return Err(error);
+	}
+
+	Ok(
+		Some(
+			tracksource::Download {
+				source: tracksource::TrackSources::Library,
+				url: path.to_string_lossy().into(),
+				metadata: tracksource::inspect(&destination, None, 0)
+					.ok()
+					.flatten(),
+				path: destination,
+				flagged: false,
+			}
+		)
+	)
+}
+
+
+pub async fn fetch(
+	module: &super::Module,
+	track: &Track,
+	params: Params
+) -> Result<(), Error> {
+	let config = &module.config;
+	let progress = &params.progress;
+
+	if config.directories.is_empty() {
+		progress.finish(&Status::NoDirectories);
+		return Ok(())
+	}
+
+	progress.status(&Status::Indexing);
+
+	let index = shared_index(module)
+		.await
+		.map_err(
+			|error| report_wrapped!(
+				Status::Error(Error::Index(error)),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		)?;
+
+	progress.status(&Status::Searching);
+
+	let matches = find(config, &index.entries, track);
+
+	log::debug!("library matches: {:#?}", matches);
+
+	let (best, sim) = match matches.first() {
+		Some(&best) => best,
+		None => {
+			progress.finish(&Status::NotFound);
+			return Ok(())
+		}
+	};
+
+	progress.size_hint((matches.len(), Some(matches.len())));
+
+	for (id, (path, sim)) in matches.iter().enumerate() {
+		progress.item(id as u8, &path.to_string_lossy());
+		progress.item_status(id as u8, &ItemStatus::Match(*sim));
+	}
+
+	match config.on_match {
+		OnMatch::Report => progress.item_status(0, &ItemStatus::Owned(sim)),
+
+		OnMatch::Copy => match copy(track, best, &params.output) {
+			Ok(Some(download)) => {
+				params.downloads.push(download);
+				progress.item_status(0, &ItemStatus::Copied(sim));
+			},
+
+			// Already in the output, so it is reported as owned instead.
+			Ok(None) => progress.item_status(0, &ItemStatus::Owned(sim)),
+
+			Err(error) => {
+				let error = report_wrapped!(
+					ItemStatus::Error(error),
+					|status| progress.item_status(0, status),
+					ItemStatus::Error(error) => error
+				);
+
+				return Err(
+					report_wrapped!(
+						Status::Error(Error::Copy(error)),
+						|status| progress.finish(status),
+						Status::Error(error) => error
+					)
+				);
+			},
+		},
+	}
+
+	params.found.set(best.to_owned());
+
+	progress.finish(&Status::Done);
+
+	Ok(())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::*;
+	use crate::track::Duration;
+
+
+	fn config() -> super::super::Config {
+		crate::config
+			::read(
+				r#"
+					[slizzy]
+					duration-tolerance = 5
+					id-clean = [' ?\(?Original Mix\)?']
+
+					[library]
+					directories = ["/music"]
+					sim-threshold = 80
+				"#
+			)
+			.expect("failed to read config")
+	}
+
+
+	fn entry(id: &str, seconds: u16) -> Entry {
+		Entry {
+			id: id.into(),
+			duration: Duration::from_seconds(seconds),
+			modified: 0,
+		}
+	}
+
+
+	#[test]
+	fn test_find() {
+		let entries: HashMap<PathBuf, Entry> = vec![
+			("/music/a.mp3".into(), entry("Somne - Kinetic Rampage (Extended Mix)", 401)),
+			("/music/b.mp3".into(), entry("Somne - Kinetic Rampage (Extended Mix)", 330)),
+			("/music/c.mp3".into(), entry("Somne - Kinetic Rampage (Original Mix)", 400)),
+			("/music/d.mp3".into(), entry("Other - Track", 400)),
+		]
+			.into_iter()
+			.collect();
+
+		let mut track = Track
+			::new("Somne - Kinetic Rampage (Extended Mix)")
+			.expect("invalid track");
+
+		track.duration = Some(Duration::from_seconds(400));
+
+		let config = config();
+
+		let matches = find(&config, &entries, &track);
+
+		assert_eq!(
+			matches.first().map(|(path, _)| *path),
+			Some(Path::new("/music/a.mp3"))
+		);
+
+		assert!(!matches.iter().any(|(path, _)| *path == Path::new("/music/b.mp3")));
+		assert!(!matches.iter().any(|(path, _)| *path == Path::new("/music/d.mp3")));
+
+		track.duration = Some(Duration::from_seconds(200));
+
+		assert!(find(&config, &entries, &track).is_empty());
+	}
+
+
+	#[test]
+	fn test_copy() {
+		let dir = std::env::temp_dir().join(
+			format!("slizzy-library-copy-test-{}", std::process::id())
+		);
+		let library = dir.join("library");
+		fs::create_dir_all(&library).expect("failed to create test dir");
+
+		let path = library.join("Somne - Kinetic Rampage.mp3");
+		fs::write(&path, b"library file").expect("failed to write test file");
+
+		let track = Track
+			::new("Somne - Kinetic Rampage")
+			.expect("invalid track");
+
+		let output = |dir: &Path| Output {
+			dir: dir.to_owned(),
+			..Output::default()
+		};
+
+		// The output is the library itself, so the file must be left alone.
+		let download = copy(&track, &path, &output(&library)).expect("failed to copy");
+
+		assert!(download.is_none());
+		assert_eq!(fs::read(&path).expect("failed to read"), b"library file");
+
+		// Missing directories are created.
+		let out = dir.join("out/nested");
+
+		let download = copy(&track, &path, &output(&out))
+			.expect("failed to copy")
+			.expect("missing download");
+
+		assert_eq!(download.path, out.join("Somne - Kinetic Rampage.mp3"));
+
+		// Existing files aren't overwritten.
+		fs::write(&download.path, b"other file").expect("failed to write test file");
+
+		let download = copy(&track, &path, &output(&out))
+			.expect("failed to copy")
+			.expect("missing download");
+
+		assert_eq!(download.path, out.join("Somne - Kinetic Rampage (1).mp3"));
+		assert_eq!(fs::read(&download.path).expect("failed to read"), b"library file");
+		assert_eq!(
+			fs::read(out.join("Somne - Kinetic Rampage.mp3")).expect("failed to read"),
+			b"other file"
+		);
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
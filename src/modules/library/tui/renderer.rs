@@ -0,0 +1,135 @@
+use std::borrow::Cow;
+
+use tui::{
+	buffer::Buffer,
+	layout::{Rect, Corner},
+	style::{Color, Style},
+	widgets::{Block, Borders, Text, List, Widget},
+};
+
+use super::{ItemStatus, Status};
+
+
+pub struct Renderer<'a>(pub &'a super::Widget);
+
+
+impl<'a> Renderer<'a> {
+	fn render_title(&self) -> (Cow<'a, str>, Style) {
+		match &self.0.status {
+			Status::Initializing => (
+				" Library - initializing ".into(),
+				Style
+					::default()
+					.fg(Color::Gray)
+			),
+
+			Status::Indexing => (
+				" Library - indexing ".into(),
+				Style::default()
+			),
+
+			Status::Searching => (
+				" Library - searching ".into(),
+				Style::default()
+			),
+
+			Status::NoDirectories => (
+				" Library - no directories configured ".into(),
+				Style
+					::default()
+					.fg(Color::Gray)
+			),
+
+			Status::NotFound => (
+				" Library - not in the library ".into(),
+				Style::default()
+			),
+
+			Status::Error(error) => (
+				format!(" Library - {} ", error).into(),
+				Style
+					::default()
+					.fg(Color::Red)
+			),
+
+			Status::Done => (
+				" Library - done ".into(),
+				Style
+					::default()
+					.fg(Color::Green)
+			),
+		}
+	}
+
+
+	fn render_items(&self) -> impl Iterator<Item = Text<'a>> {
+		self.0.items
+			.iter()
+			.map(
+				|(label, status)| {
+					let (label, style) = match status {
+						ItemStatus::Searching => (
+							label.as_ref().into(),
+							Style::default()
+						),
+
+						ItemStatus::Error(error) => (
+							format!("{} | error: {}", label, error)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Red),
+						),
+
+						ItemStatus::Match(sim) => (
+							format!("{} | match: {}% similarity", label, sim.value())
+								.into(),
+							Style
+								::default()
+								.fg(Color::Yellow),
+						),
+
+						ItemStatus::Owned(sim) => (
+							format!("{} | already owned: {}% similarity", label, sim.value())
+								.into(),
+							Style
+								::default()
+								.fg(Color::Green),
+						),
+
+						ItemStatus::Copied(sim) => (
+							format!("{} | copied: {}% similarity", label, sim.value())
+								.into(),
+							Style
+								::default()
+								.fg(Color::Green),
+						),
+					};
+
+					Text::Styled(label, style)
+				}
+			)
+	}
+}
+
+
+impl<'a> Widget for Renderer<'a> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let (title, title_style) = self.render_title();
+
+		let items = self.render_items();
+
+		let widget = List
+			::new(items)
+			.block(
+				Block
+					::default()
+					.borders(Borders::ALL)
+					.title(&title)
+					.title_style(title_style)
+			)
+			.start_corner(Corner::TopLeft);
+
+		widget.render(area, buf);
+	}
+}
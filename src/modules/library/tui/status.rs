@@ -0,0 +1,73 @@
+use crate::sim::Sim;
+use super::{BackendStatus, BackendItemStatus};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+	Initializing,
+	Indexing,
+	Searching,
+	NoDirectories,
+	NotFound,
+	Error(Box<str>),
+	Done,
+}
+
+
+impl Default for Status {
+	fn default() -> Self { Status::Initializing }
+}
+
+
+impl From<&BackendStatus> for Status {
+	fn from(status: &BackendStatus) -> Self {
+		match status {
+			BackendStatus::Indexing => Status::Indexing,
+
+			BackendStatus::Searching => Status::Searching,
+
+			BackendStatus::NoDirectories => Status::NoDirectories,
+
+			BackendStatus::NotFound => Status::NotFound,
+
+			BackendStatus::Error(error) => Status::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+
+			BackendStatus::Done => Status::Done,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+	Searching,
+	Error(Box<str>),
+	Match(Sim),
+	Owned(Sim),
+	Copied(Sim),
+}
+
+
+impl Default for ItemStatus {
+	fn default() -> Self { ItemStatus::Searching }
+}
+
+
+impl From<&BackendItemStatus> for ItemStatus {
+	fn from(status: &BackendItemStatus) -> Self {
+		match status {
+			BackendItemStatus::Error(error) => ItemStatus::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+			BackendItemStatus::Match(sim) => ItemStatus::Match(*sim),
+			BackendItemStatus::Owned(sim) => ItemStatus::Owned(*sim),
+			BackendItemStatus::Copied(sim) => ItemStatus::Copied(*sim),
+		}
+	}
+}
@@ -1,3 +1,5 @@
+pub mod reconcile;
+
 use async_trait::async_trait;
 
 use enumset::EnumSetType;
@@ -23,4 +25,18 @@ pub trait Module: super::Module {
 pub enum MetaSources {
 	Bandcamp,
 	Beatport,
+	Discogs,
+	MusicBrainz,
+}
+
+
+impl std::fmt::Display for MetaSources {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			MetaSources::Bandcamp => write!(f, "bandcamp"),
+			MetaSources::Beatport => write!(f, "beatport"),
+			MetaSources::Discogs => write!(f, "discogs"),
+			MetaSources::MusicBrainz => write!(f, "musicbrainz"),
+		}
+	}
 }
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Deserializer};
+
+use super::Policy;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Config {
+	pub policy: Policy,
+	/// Durations within this many seconds are considered to agree.
+	pub duration_tolerance: u16,
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Config {
+				policy: config.slizzy.metasource_policy,
+				duration_tolerance: config.slizzy.duration_tolerance,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	slizzy: Slizzy,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+struct Slizzy {
+	#[serde(default)]
+	metasource_policy: Policy,
+	duration_tolerance: u16,
+}
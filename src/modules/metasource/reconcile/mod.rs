@@ -0,0 +1,221 @@
+pub mod tui;
+mod config;
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+use crate::track::{Duration, Metadata};
+use super::MetaSources;
+pub use config::Config;
+
+
+/// How to pick a duration when the metasources disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Policy {
+	/// The duration most metasources agree on. Ties are resolved by priority.
+	Majority,
+	/// The first metasource, in the order beatport, bandcamp, musicbrainz, discogs.
+	Priority,
+	/// The longest duration, favoring extended mixes.
+	Longest,
+	/// The shortest duration, favoring radio edits.
+	Shortest,
+}
+
+
+impl Default for Policy {
+	fn default() -> Self { Policy::Majority }
+}
+
+
+impl std::fmt::Display for Policy {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Policy::Majority => f.write_str("majority"),
+			Policy::Priority => f.write_str("priority"),
+			Policy::Longest => f.write_str("longest"),
+			Policy::Shortest => f.write_str("shortest"),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+	/// Many metasources agree on the duration.
+	High,
+	/// Only a single metasource found the track.
+	Low,
+	/// The metasources disagree, and the duration was picked by the policy.
+	Conflict,
+}
+
+
+impl std::fmt::Display for Confidence {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Confidence::High => f.write_str("high confidence"),
+			Confidence::Low => f.write_str("low confidence"),
+			Confidence::Conflict => f.write_str("conflict"),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Candidate {
+	pub source: MetaSources,
+	pub duration: Duration,
+	pub metadata: Metadata,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Status {
+	Waiting,
+	Skipped,
+	NoCandidates,
+	Done {
+		confidence: Confidence,
+		policy: Policy,
+		/// The metadata of the chosen candidate.
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		metadata: Metadata,
+	},
+}
+
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Waiting => f.write_str("waiting"),
+			Status::Skipped => f.write_str("skipped"),
+			Status::NoCandidates => f.write_str("no candidates"),
+			Status::Done { confidence: Confidence::Conflict, policy, .. } => write!(
+				f,
+				"conflict, resolved by {}",
+				policy
+			),
+			Status::Done { confidence, .. } => write!(f, "{}", confidence),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+	Chosen,
+	Agrees,
+	Conflicts,
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Chosen => f.write_str("chosen"),
+			ItemStatus::Agrees => f.write_str("agrees"),
+			ItemStatus::Conflicts => f.write_str("conflicts"),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reconciliation {
+	/// The index of the chosen candidate.
+	pub chosen: usize,
+	pub confidence: Confidence,
+	/// The status of each candidate.
+	pub statuses: Box<[ItemStatus]>,
+}
+
+
+/// The priority of a metasource, lower is preferred.
+pub fn priority(source: MetaSources) -> u8 {
+	match source {
+		MetaSources::Beatport => 0,
+		MetaSources::Bandcamp => 1,
+		MetaSources::MusicBrainz => 2,
+		MetaSources::Discogs => 3,
+	}
+}
+
+
+/// Pick a candidate according to the policy, and tell how much the candidates agree.
+pub fn reconcile(candidates: &[Candidate], config: Config) -> Option<Reconciliation> {
+	let agrees = |a: Duration, b: Duration| {
+		let (a, b) = (a.as_seconds(), b.as_seconds());
+
+		a.max(b) - a.min(b) <= config.duration_tolerance
+	};
+
+	let agreement = |candidate: &Candidate| candidates
+		.iter()
+		.filter(
+			|other| agrees(candidate.duration, other.duration)
+		)
+		.count();
+
+	let by_priority = |(_, candidate): &(usize, &Candidate)| priority(candidate.source);
+
+	let indexed = || candidates
+		.iter()
+		.enumerate();
+
+	// Ties are resolved by priority, so the keys are ordered such that min is preferred.
+	let chosen = match config.policy {
+		Policy::Majority => indexed().min_by_key(
+			|item| (std::cmp::Reverse(agreement(item.1)), by_priority(item))
+		),
+		Policy::Priority => indexed().min_by_key(by_priority),
+		Policy::Longest => indexed().min_by_key(
+			|item| (std::cmp::Reverse(item.1.duration), by_priority(item))
+		),
+		Policy::Shortest => indexed().min_by_key(
+			|item| (item.1.duration, by_priority(item))
+		),
+	};
+
+	let (chosen, chosen_candidate) = chosen?;
+
+	let statuses: Box<[ItemStatus]> = indexed()
+		.map(
+			|(ix, candidate)| {
+				if ix == chosen {
+					ItemStatus::Chosen
+				}
+				else if agrees(chosen_candidate.duration, candidate.duration) {
+					ItemStatus::Agrees
+				}
+				else {
+					ItemStatus::Conflicts
+				}
+			}
+		)
+		.collect();
+
+	let confidence = if statuses.contains(&ItemStatus::Conflicts) {
+		Confidence::Conflict
+	}
+	else if candidates.len() > 1 {
+		Confidence::High
+	}
+	else {
+		Confidence::Low
+	};
+
+	Some(
+		Reconciliation {
+			chosen,
+			confidence,
+			statuses,
+		}
+	)
+}
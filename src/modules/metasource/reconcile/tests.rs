@@ -0,0 +1,68 @@
+use super::*;
+
+
+fn candidate(source: MetaSources, seconds: u16) -> Candidate {
+	Candidate {
+		source,
+		duration: Duration::from_seconds(seconds),
+		metadata: Metadata::default(),
+	}
+}
+
+
+fn config(policy: Policy) -> Config {
+	Config {
+		policy,
+		duration_tolerance: 5,
+	}
+}
+
+
+#[test]
+fn test_agreement() {
+	let candidates = [
+		candidate(MetaSources::Bandcamp, 300),
+		candidate(MetaSources::Beatport, 303),
+	];
+
+	assert_eq!(
+		reconcile(&candidates, config(Policy::Majority)),
+		Some(
+			Reconciliation {
+				chosen: 1,
+				confidence: Confidence::High,
+				statuses: vec![ItemStatus::Agrees, ItemStatus::Chosen].into(),
+			}
+		)
+	);
+
+	assert_eq!(
+		reconcile(&candidates[..1], config(Policy::Majority)).map(|r| r.confidence),
+		Some(Confidence::Low)
+	);
+
+	assert_eq!(reconcile(&[], config(Policy::Majority)), None);
+}
+
+
+#[test]
+fn test_policies() {
+	let candidates = [
+		candidate(MetaSources::Discogs, 554),
+		candidate(MetaSources::Beatport, 210),
+		candidate(MetaSources::MusicBrainz, 552),
+	];
+
+	let test = |policy, chosen| {
+		let reconciliation = reconcile(&candidates, config(policy))
+			.expect("no candidate chosen");
+
+		assert_eq!(reconciliation.chosen, chosen, "policy: {}", policy);
+		assert_eq!(reconciliation.confidence, Confidence::Conflict);
+	};
+
+	test(Policy::Majority, 2);
+	test(Policy::Priority, 1);
+	test(Policy::Longest, 0);
+	test(Policy::Shortest, 1);
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+use tui::{
+	buffer::Buffer,
+	layout::{Rect, Corner},
+	style::{Color, Style},
+	widgets::{Block, Borders, Text, List, Widget},
+};
+
+use super::super::Confidence;
+use super::{ItemStatus, Status};
+
+
+pub struct Renderer<'a>(pub &'a super::Widget);
+
+
+impl<'a> Renderer<'a> {
+	fn render_title(&self) -> (Cow<'a, str>, Style) {
+		match &self.0.status {
+			Status::Waiting => (
+				" Durations - waiting ".into(),
+				Style::default()
+			),
+
+			Status::Skipped => (
+				" Durations - skipped ".into(),
+				Style
+					::default()
+					.fg(Color::Green)
+			),
+
+			Status::NoCandidates => (
+				" Durations - no candidates ".into(),
+				Style
+					::default()
+					.fg(Color::Red)
+			),
+
+			Status::Done(Confidence::High, _, _) => (
+				" Durations - high confidence ".into(),
+				Style
+					::default()
+					.fg(Color::Green)
+			),
+
+			Status::Done(Confidence::Low, _, _) => (
+				" Durations - low confidence ".into(),
+				Style::default()
+			),
+
+			Status::Done(Confidence::Conflict, policy, _) => (
+				format!(" Durations - conflict, resolved by {} ", policy).into(),
+				Style
+					::default()
+					.fg(Color::Yellow)
+			),
+		}
+	}
+
+
+	fn render_items(&self) -> impl Iterator<Item = Text<'a>> {
+		self.0.items
+			.iter()
+			.map(
+				|(label, status)| {
+					let (label, style) = match status {
+						ItemStatus::Pending => (
+							label.as_ref().into(),
+							Style::default()
+						),
+
+						ItemStatus::Chosen => (
+							format!("{} | chosen", label)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Green),
+						),
+
+						ItemStatus::Agrees => (
+							format!("{} | agrees", label)
+								.into(),
+							Style::default(),
+						),
+
+						ItemStatus::Conflicts => (
+							format!("{} | conflicts", label)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Yellow),
+						),
+					};
+
+					Text::Styled(label, style)
+				}
+			)
+	}
+}
+
+
+impl<'a> Widget for Renderer<'a> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let (title, title_style) = self.render_title();
+
+		let items = self.render_items();
+
+		let widget = List
+			::new(items)
+			.block(
+				Block
+					::default()
+					.borders(Borders::ALL)
+					.title(&title)
+					.title_style(title_style)
+			)
+			.start_corner(Corner::TopLeft);
+
+		widget.render(area, buf);
+	}
+}
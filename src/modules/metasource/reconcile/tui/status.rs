@@ -0,0 +1,58 @@
+use crate::track::Metadata;
+use super::super::{Confidence, Policy};
+use super::{BackendStatus, BackendItemStatus};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+	Waiting,
+	Skipped,
+	NoCandidates,
+	Done(Confidence, Policy, Metadata),
+}
+
+
+impl Default for Status {
+	fn default() -> Self { Status::Waiting }
+}
+
+
+impl From<&BackendStatus> for Status {
+	fn from(status: &BackendStatus) -> Self {
+		match status {
+			BackendStatus::Waiting => Status::Waiting,
+			BackendStatus::Skipped => Status::Skipped,
+			BackendStatus::NoCandidates => Status::NoCandidates,
+			BackendStatus::Done { confidence, policy, metadata } => Status::Done(
+				*confidence,
+				*policy,
+				metadata.clone()
+			),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+	Pending,
+	Chosen,
+	Agrees,
+	Conflicts,
+}
+
+
+impl Default for ItemStatus {
+	fn default() -> Self { ItemStatus::Pending }
+}
+
+
+impl From<&BackendItemStatus> for ItemStatus {
+	fn from(status: &BackendItemStatus) -> Self {
+		match *status {
+			BackendItemStatus::Chosen => ItemStatus::Chosen,
+			BackendItemStatus::Agrees => ItemStatus::Agrees,
+			BackendItemStatus::Conflicts => ItemStatus::Conflicts,
+		}
+	}
+}
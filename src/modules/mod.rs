@@ -3,11 +3,18 @@ pub mod metasource;
 pub mod tracksource;
 pub mod item;
 pub mod google;
+pub mod searxng;
+pub mod bing;
+pub mod duckduckgo;
 pub mod beatport;
 pub mod bandcamp;
+pub mod musicbrainz;
+pub mod discogs;
 pub mod slider;
 pub mod zippy;
 pub mod music2k;
+pub mod plugin;
+pub mod library;
 
 
 use serde::de::DeserializeOwned;
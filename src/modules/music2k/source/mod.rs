@@ -8,6 +8,8 @@ use std::{
 
 use thiserror::Error;
 
+use serde::Serialize;
+
 use derive_more::Deref;
 
 use lazy_static::lazy_static;
@@ -17,12 +19,14 @@ use futures::stream::StreamExt;
 use crate::{
 	report_wrapped,
 	track::{Track, Duration},
+	output::Output,
 	net::{http, url::{self, Url}},
 	sim::{self, Sim},
+	rank::{self, Score},
 	web::scraping,
-	util,
+	util::{self, bytes, mp3},
 };
-use super::super::item;
+use super::super::{item, tracksource};
 
 
 lazy_static! {
@@ -67,10 +71,14 @@ pub enum ItemError {
 
 	#[error("download error: {0}")]
 	Download(http::downloader::Error),
+
+	#[error("invalid download: {0}")]
+	Invalid(tracksource::Mismatch),
 }
 
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Filter {
 	Id(Sim),
 	Duration(Duration),
@@ -78,24 +86,88 @@ pub enum Filter {
 }
 
 
-#[derive(Debug)]
+impl std::fmt::Display for Filter {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Filter::Id(sim) => write!(
+				f,
+				"id mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			Filter::Duration(duration) => write!(f, "duration mismatch: {} out of range", duration),
+			Filter::Size(size) => write!(f, "file size mismatch: {} out of range", size),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ItemStatus {
-	Error(ItemError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
 	Filtered(Filter),
+	Candidate(Score),
+	Outranked(Score),
 	Downloading(util::io::Progress),
-	Done
+	/// The file was downloaded but doesn't match what was expected.
+	Flagged(tracksource::Mismatch),
+	/// The inspected file metadata, if available.
+	Done(Option<mp3::Metadata>),
 }
 
 
-#[derive(Debug)]
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+			ItemStatus::Filtered(filter) => write!(f, "{}", filter),
+			ItemStatus::Candidate(score) => write!(f, "candidate: {} score", score),
+			ItemStatus::Outranked(score) => write!(f, "outranked: {} score", score),
+			ItemStatus::Downloading(progress) => match progress.percentage() {
+				Some(percentage) => write!(f, "downloading: {:.0}%", percentage * 100.0),
+				None => write!(f, "downloading: {}", bytes::Mb::from(progress.completed)),
+			},
+			ItemStatus::Flagged(mismatch) => write!(f, "done, but {}", mismatch),
+			ItemStatus::Done(Some(metadata)) => write!(
+				f,
+				"done! {} at {} kbps",
+				metadata.duration,
+				metadata.bitrate
+			),
+			ItemStatus::Done(None) => f.write_str("done!"),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Status {
 	Fetching,
 	NoEntries,
-	Error(Error),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
 	Done,
 }
 
 
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Fetching => f.write_str("fetching"),
+			Status::NoEntries => f.write_str("no entries found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+			Status::Done => f.write_str("done"),
+		}
+	}
+}
+
+
 #[derive(Deref)]
 // TODO replace with type synonym when https://github.com/rust-lang/rust/issues/63033
 // gets solved.
@@ -111,6 +183,25 @@ pub struct Progress(
 );
 
 
+pub struct Params {
+	pub progress: Progress,
+	pub ranking: rank::Participant,
+	pub output: Output,
+	pub downloads: tracksource::Downloads,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.field("ranking", &self.ranking)
+			.field("output", &self.output)
+			.field("downloads", &self.downloads)
+			.finish()
+	}
+}
+
+
 async fn fetch_size(url: &Url) -> Result<usize, ItemError> {
 	let response = http::Request
 		::new(url)
@@ -185,10 +276,12 @@ async fn filter_entry(
 	config: &super::Config,
 	track: &Track,
 	entry: scraper::Entry,
-) -> Result<Option<Filter>, ItemError> {
+) -> Result<Result<Score, Filter>, ItemError> {
 	let id = entry.id
 		.map_err(ItemError::Scraping)?;
 
+	let hints = track.metadata.hints(&id);
+
 	let id = config.id_cleaner.clean(&id);
 
 	let similarity = sim::str(
@@ -198,12 +291,19 @@ async fn filter_entry(
 
 	if similarity < config.sim_threshold {
 		return Ok(
-			Some(
+			Err(
 				Filter::Id(similarity)
 			)
 		);
 	}
 
+	let mut metrics = rank::Metrics {
+		sim: Some(similarity),
+		duration: None,
+		quality: None,
+		hints,
+	};
+
 	if let Some(duration) = track.duration {
 		let entry_duration = entry.duration
 			.map_err(ItemError::Scraping)?;
@@ -212,7 +312,7 @@ async fn filter_entry(
 
 		if !duration_range.contains(&entry_duration) {
 			return Ok(
-				Some(
+				Err(
 					Filter::Duration(entry_duration)
 				)
 			);
@@ -227,23 +327,41 @@ async fn filter_entry(
 
 		if !size_range.contains(&size) {
 			return Ok(
-				Some(
+				Err(
 					Filter::Size(size)
 				)
 			);
 		}
+
+		metrics.duration = Some(
+			rank::closeness(
+				entry_duration.as_seconds() as f32,
+				duration.as_seconds() as f32,
+				config.duration_tolerance as f32,
+			)
+		);
+
+		metrics.quality = Some(
+			rank::closeness(
+				bytes::Mb::from(size).0,
+				config.expected_size(duration).0,
+				config.size_tolerance,
+			)
+		);
 	}
 
-	Ok(None)
+	Ok(
+		Ok(metrics.into())
+	)
 }
 
 
-async fn handle_item(
+async fn rank_item(
 	config: &super::Config,
 	track: &Track,
 	entry: scraper::Entry,
 	status: impl Fn(&ItemStatus),
-) -> Result<(), ItemError> {
+) -> Result<Option<(Url, String, Score)>, ItemError> {
 	let download_url = entry.download
 		.clone()
 		.map_err(
@@ -276,26 +394,52 @@ async fn handle_item(
 			)
 		)?;
 
-	if let Some(filter) = filter {
-		log::debug!("music2k filtered entry: {:?}", filter);
+	match filter {
+		Err(filter) => {
+			log::debug!("music2k filtered entry: {:?}", filter);
 
-		status(
-			&ItemStatus::Filtered(filter)
-		);
+			status(
+				&ItemStatus::Filtered(filter)
+			);
 
-		return Ok(())
+			Ok(None)
+		},
+
+		Ok(score) => {
+			status(
+				&ItemStatus::Candidate(score)
+			);
+
+			Ok(
+				Some((download_url, name, score))
+			)
+		},
 	}
+}
+
 
+async fn download_item(
+	config: &super::Config,
+	track: &Track,
+	download_url: Url,
+	name: String,
+	output: &Output,
+	status: impl Fn(&ItemStatus),
+) -> Result<tracksource::Download, ItemError> {
 	let default_path = Path::new(&name);
 
-	http::Downloader
+	let path = http::Downloader
 		::new()
 		.reporter(
 			|&progress| status(
 				&ItemStatus::Downloading(progress)
 			)
 		)
-		.download_file(&download_url, default_path)
+		.download_file(
+			&download_url,
+			default_path,
+			|stem, extension| output.path(track.id(), stem, extension)
+		)
 		.await
 		.map_err(
 			|error| report_wrapped!(
@@ -307,17 +451,72 @@ async fn handle_item(
 			)
 		)?;
 
-	status(&ItemStatus::Done);
+	let inspection = tracksource::inspect(
+		&path,
+		track.duration.map(
+			|duration| config.duration_range(duration)
+		),
+		config.min_bitrate,
+	);
+
+	match inspection {
+		Err(mismatch) if config.delete_invalid => {
+			if let Err(error) = std::fs::remove_file(&path) {
+				log::warn!("failed to delete {:#?}: {}", path, error);
+			}
+
+			Err(
+				report_wrapped!(
+					ItemStatus::Error(
+						ItemError::Invalid(mismatch)
+					),
+					status,
+					ItemStatus::Error(error) => error
+				)
+			)
+		},
+
+		Err(mismatch) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Flagged(mismatch));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Music2k,
+					url: download_url.to_string().into(),
+					path,
+					metadata: None,
+					flagged: true,
+				}
+			)
+		},
+
+		Ok(metadata) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Done(metadata));
 
-	Ok(())
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Music2k,
+					url: download_url.to_string().into(),
+					path,
+					metadata,
+					flagged: false,
+				}
+			)
+		},
+	}
 }
 
 
 pub async fn fetch(
 	module: &super::Module,
 	track: &Track,
-	progress: Progress
+	params: Params
 ) -> Result<(), Error> {
+	let progress = &params.progress;
+	let output = &params.output;
+
 	progress.status(&Status::Fetching);
 
 	let entries =
@@ -362,26 +561,69 @@ pub async fn fetch(
 						.unwrap_or("missing title")
 				);
 
-				let progress = &progress;
+				async move {
+					let result = rank_item(
+						&module.config,
+						track,
+						entry,
+						move |status| progress.item_status(id, status)
+					);
 
-				handle_item(
-					&module.config,
-					track,
-					entry,
-					move |status| progress.item_status(id, status)
-				)
+					(id, result.await)
+				}
 			}
 		)
 		.collect();
 
-	let errors: Vec<ItemError> = items
+	let mut errors: Vec<ItemError> = Vec::new();
+	let mut candidates = Vec::new();
+
+	for (id, result) in items.collect::<Vec<_>>().await {
+		match result {
+			Ok(Some((url, name, score))) => candidates.push((id, url, name, score)),
+			Ok(None) => (),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	let verdicts = params.ranking
+		.rank(
+			candidates
+				.iter()
+				.map(|(_, _, _, score)| *score)
+		)
+		.await;
+
+	let downloads: futures::stream::FuturesUnordered<_> = candidates
+		.into_iter()
+		.zip(verdicts.iter())
 		.filter_map(
-			|result| async move {
-				result.err()
+			|((id, url, name, score), verdict)| match verdict {
+				rank::Verdict::Selected => Some(
+					download_item(
+						&module.config,
+						track,
+						url,
+						name,
+						output,
+						move |status| progress.item_status(id, status)
+					)
+				),
+
+				rank::Verdict::Outranked => {
+					progress.item_status(id, &ItemStatus::Outranked(score));
+					None
+				},
 			}
 		)
-		.collect()
-		.await;
+		.collect();
+
+	for result in downloads.collect::<Vec<_>>().await {
+		match result {
+			Ok(download) => params.downloads.push(download),
+			Err(error) => errors.push(error),
+		}
+	}
 
 	if errors.is_empty() {
 		progress.finish(&Status::Done);
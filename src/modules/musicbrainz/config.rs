@@ -0,0 +1,71 @@
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+	net::url::Url,
+	sim::Sim,
+};
+
+
+const DEFAULT_BASE_URL: &str = "https://musicbrainz.org";
+
+
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The MusicBrainz server, which may be a local mirror.
+	pub base_url: Url,
+	pub sim_threshold: Sim,
+	/// How many recordings to request.
+	pub limit: u8,
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		let base_url = config.musicbrainz.base_url
+			.as_deref()
+			.unwrap_or(DEFAULT_BASE_URL)
+			.parse()
+			.map_err(serde::de::Error::custom)?;
+
+		Ok(
+			Config {
+				base_url,
+				sim_threshold: config.musicbrainz.sim_threshold,
+				limit: config.musicbrainz.limit,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	// Optional, so that older config files remain valid.
+	#[serde(default)]
+	musicbrainz: MusicBrainz,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+#[serde(default)]
+struct MusicBrainz {
+	base_url: Option<Box<str>>,
+	sim_threshold: Sim,
+	limit: u8,
+}
+
+
+impl Default for MusicBrainz {
+	fn default() -> Self {
+		Self {
+			base_url: None,
+			sim_threshold: Sim::try_from(70u8).expect("invalid default similarity"),
+			limit: 10,
+		}
+	}
+}
@@ -0,0 +1,400 @@
+mod recordings;
+
+use std::cmp::max;
+
+use thiserror::Error;
+
+use serde::Serialize;
+
+use lazy_static::lazy_static;
+
+use regex::Regex;
+
+use crate::{
+	report_wrapped,
+	net::http,
+	sim::{self, Sim},
+	track::{Track, Id, Duration, Metadata},
+};
+use super::super::item;
+use recordings::{Recordings, Recording};
+
+
+// MusicBrainz requires a meaningful user agent.
+const USER_AGENT: &str = concat!(
+	"slizzy/",
+	env!("CARGO_PKG_VERSION"),
+	" ( https://github.com/gahag/rslizzy )"
+);
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("http error: {0}")]
+	Http(http::Error),
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Status {
+	Skipped,
+	Searching,
+	Done,
+	MatchNotFound,
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
+}
+
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Skipped => f.write_str("skipped"),
+			Status::Searching => f.write_str("searching"),
+			Status::Done => f.write_str("done"),
+			Status::MatchNotFound => f.write_str("match not found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ItemStatus {
+	Selected,
+	Candidate(Sim),
+	MissingDuration,
+	ArtistsMismatch(Sim),
+	TitleMismatch(Sim),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Selected => f.write_str("selected"),
+			ItemStatus::Candidate(sim) => write!(f, "candidate: {}% similarity", sim.value()),
+			ItemStatus::MissingDuration => f.write_str("missing duration"),
+			ItemStatus::ArtistsMismatch(sim) => write!(
+				f,
+				"artists mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			ItemStatus::TitleMismatch(sim) => write!(
+				f,
+				"title mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+		}
+	}
+}
+
+
+pub struct Params {
+	pub progress: Box<
+		dyn item::progress::Progress<
+			Id = u8,
+			Item = str,
+			Status = Status,
+			ItemStatus = ItemStatus,
+		> + Send
+	>,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.finish()
+	}
+}
+
+
+/// Escape the Lucene special characters.
+fn escape(term: &str) -> String {
+	lazy_static! {
+		static ref SPECIAL_PATTERN: Regex = Regex
+			::new(r#"[+\-&|!(){}\[\]^"~*?:\\/]"#)
+			.expect("invalid regex");
+	}
+
+	SPECIAL_PATTERN
+		.replace_all(term, r"\$0")
+		.into_owned()
+}
+
+
+fn query(id: &Id) -> String {
+	format!(
+		"recording:({}) AND artist:({})",
+		escape(id.name()),
+		escape(id.artists()),
+	)
+}
+
+
+async fn fetch_recordings(config: &super::Config, id: &Id) -> Result<Box<[Recording]>, http::Error> {
+	let url = config.base_url
+		.dissect()
+		.push_path("ws/2/recording")
+		.expect("invalid url")
+		.append_query("query", &query(id))
+		.append_query("limit", &config.limit.to_string())
+		.append_query("fmt", "json")
+		.assemble();
+
+	log::debug!("musicbrainz url: {}", url);
+
+	let mut request = http::Request::new(&url);
+
+	request
+		.headers_mut()
+		.insert(
+			http::headers::USER_AGENT,
+			http::headers::Value::from_static(USER_AGENT),
+		);
+
+	let recordings = request
+		.send()
+		.await?
+		.body_json::<Recordings>()
+		.await?
+		.recordings;
+
+	log::trace!("musicbrainz recordings: {:#?}", recordings);
+
+	Ok(recordings)
+}
+
+
+/// Whether the mix is the original one, which MusicBrainz usually omits.
+fn is_original(mix: &str) -> bool {
+	mix
+		.to_lowercase()
+		.contains("original")
+}
+
+
+/// The similarity of the recording's artists and title to the track id.
+fn similarity(recording: &Recording, id: &Id) -> (Sim, Sim) {
+	let artists = sim::str(&recording.artists(), id.artists());
+
+	let title = match id.mix() {
+		Some(mix) if !is_original(mix) => sim::str(&recording.title, id.title()),
+		_ => max(
+			sim::str(&recording.title, id.name()),
+			sim::str(&recording.title, id.title()),
+		),
+	};
+
+	(artists, title)
+}
+
+
+fn duration(length: u64) -> Duration {
+	Duration::from_seconds(
+		((length + 500) / 1000) as u16
+	)
+}
+
+
+/// Filter the recording, yielding its lowest similarity and duration when it matches the
+/// track.
+fn select(
+	recording: &Recording,
+	id: &Id,
+	sim_threshold: Sim,
+) -> Result<(Sim, Duration), ItemStatus> {
+	let length = recording.length.ok_or(ItemStatus::MissingDuration)?;
+
+	let (artists, title) = similarity(recording, id);
+
+	if artists < sim_threshold {
+		return Err(ItemStatus::ArtistsMismatch(artists));
+	}
+
+	if title < sim_threshold {
+		return Err(ItemStatus::TitleMismatch(title));
+	}
+
+	Ok((artists.min(title), duration(length)))
+}
+
+
+pub async fn fill_metadata(
+	module: &super::Module,
+	track: &mut Track,
+	params: Params,
+) -> Result<bool, Error> {
+	let progress = params.progress.as_ref();
+
+	if track.duration.is_none() {
+		progress.status(&Status::Searching);
+	}
+	else {
+		progress.finish(&Status::Skipped);
+		return Ok(true);
+	}
+
+	let recordings = fetch_recordings(&module.config, track.id())
+		.await
+		.map_err(
+			|error| report_wrapped!(
+				Status::Error(Error::Http(error)),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		)?;
+
+	progress.size_hint(
+		(recordings.len(), Some(recordings.len()))
+	);
+
+	let mut best: Option<(u8, Sim, Duration, &Recording)> = None;
+
+	for (id, recording) in recordings.iter().enumerate() {
+		let id = id as u8;
+
+		let length = recording.length
+			.map(
+				|length| duration(length).to_string()
+			)
+			.unwrap_or_else(|| "?".into());
+
+		progress.item(
+			id,
+			&format!("{} - {} [{}]", recording.artists(), recording.title, length)
+		);
+
+		match select(recording, track.id(), module.config.sim_threshold) {
+			Ok((sim, duration)) => {
+				progress.item_status(id, &ItemStatus::Candidate(sim));
+
+				let is_best = match best {
+					Some((_, best_sim, _, _)) => sim > best_sim,
+					None => true,
+				};
+
+				if is_best {
+					best = Some((id, sim, duration, recording));
+				}
+			},
+
+			Err(status) => progress.item_status(id, &status),
+		}
+	}
+
+	match best {
+		Some((id, _, duration, recording)) => {
+			progress.item_status(id, &ItemStatus::Selected);
+
+			log::debug!("musicbrainz selected recording: {}", recording.id);
+
+			track.duration = Some(duration);
+			track.metadata = Metadata {
+				release_date: recording.release_date().map(Into::into),
+				..Default::default()
+			};
+
+			progress.finish(&Status::Done);
+
+			Ok(true)
+		},
+
+		None => {
+			progress.finish(&Status::MatchNotFound);
+
+			Ok(false)
+		},
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryFrom;
+
+	use super::*;
+
+
+	const RECORDINGS: &str = r#"{
+		"created": "2021-05-17T00:00:00.000Z",
+		"count": 2,
+		"offset": 0,
+		"recordings": [
+			{
+				"id": "0c4e2e6a-0000-0000-0000-000000000000",
+				"score": 100,
+				"title": "Vertere",
+				"length": 553826,
+				"artist-credit": [
+					{ "name": "Somne", "joinphrase": " & ", "artist": { "name": "Somne" } },
+					{ "name": "Mind Against", "artist": { "name": "Mind Against" } }
+				],
+				"releases": [
+					{ "title": "Vertere", "date": "2021-05-17" },
+					{ "title": "Compilation", "date": "2022" }
+				]
+			},
+			{
+				"id": "0c4e2e6a-0000-0000-0000-000000000001",
+				"score": 80,
+				"title": "Another Track",
+				"artist-credit": [{ "name": "Somne" }]
+			}
+		]
+	}"#;
+
+
+	#[test]
+	fn test_select() {
+		let recordings: Recordings = serde_json
+			::from_str(RECORDINGS)
+			.expect("failed to parse recordings");
+
+		let recordings = recordings.recordings;
+
+		let id: Id = "Somne & Mind Against - Vertere (Original Mix)"
+			.parse()
+			.expect("invalid track id");
+
+		assert_eq!(recordings[0].artists(), "Somne & Mind Against");
+		assert_eq!(recordings[0].release_date(), Some("2021-05-17"));
+
+		let (_, duration) = select(&recordings[0], &id, Sim::try_from(70u8).unwrap())
+			.expect("recording should match");
+
+		assert_eq!(duration, Duration::new(9, 14));
+
+		assert_eq!(
+			select(&recordings[1], &id, Sim::try_from(70u8).unwrap()),
+			Err(ItemStatus::MissingDuration)
+		);
+
+		let remix: Id = "Somne & Mind Against - Vertere (Someone Remix)"
+			.parse()
+			.expect("invalid track id");
+
+		assert!(
+			matches!(
+				select(&recordings[0], &remix, Sim::try_from(70u8).unwrap()),
+				Err(ItemStatus::TitleMismatch(_))
+			)
+		);
+	}
+
+
+	#[test]
+	fn test_query() {
+		let id: Id = "AC/DC - Who Made Who (Live)"
+			.parse()
+			.expect("invalid track id");
+
+		assert_eq!(query(&id), r"recording:(Who Made Who) AND artist:(AC\/DC)");
+	}
+}
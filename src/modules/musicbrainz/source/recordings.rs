@@ -0,0 +1,66 @@
+use serde::Deserialize;
+
+
+/// The response of the recording search.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Recordings {
+	pub recordings: Box<[Recording]>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Recording {
+	pub id: Box<str>,
+	pub title: Box<str>,
+	/// In milliseconds.
+	pub length: Option<u64>,
+	#[serde(rename = "artist-credit", default)]
+	pub artist_credit: Box<[ArtistCredit]>,
+	#[serde(default)]
+	pub releases: Box<[Release]>,
+}
+
+
+impl Recording {
+	/// The credited artists, as displayed by MusicBrainz.
+	pub fn artists(&self) -> String {
+		self.artist_credit
+			.iter()
+			.map(
+				|credit| format!(
+					"{}{}",
+					credit.name,
+					credit.joinphrase.as_deref().unwrap_or("")
+				)
+			)
+			.collect()
+	}
+
+
+	/// The earliest known release date.
+	pub fn release_date(&self) -> Option<&str> {
+		self.releases
+			.iter()
+			.filter_map(
+				|release| release.date.as_deref()
+			)
+			.filter(
+				|date| !date.is_empty()
+			)
+			.min()
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ArtistCredit {
+	pub name: Box<str>,
+	pub joinphrase: Option<Box<str>>,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+	pub title: Box<str>,
+	pub date: Option<Box<str>>,
+}
@@ -0,0 +1,64 @@
+use crate::sim::Sim;
+use super::{BackendStatus, BackendItemStatus};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+	Initializing,
+	Skipped,
+	Searching,
+	Done,
+	MatchNotFound,
+	Error(Box<str>),
+}
+
+
+impl Default for Status {
+	fn default() -> Self { Status::Initializing }
+}
+
+
+impl From<&BackendStatus> for Status {
+	fn from(status: &BackendStatus) -> Self {
+		match status {
+			BackendStatus::Skipped => Status::Skipped,
+			BackendStatus::Searching => Status::Searching,
+			BackendStatus::Done => Status::Done,
+			BackendStatus::MatchNotFound => Status::MatchNotFound,
+			BackendStatus::Error(error) => Status::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+	Pending,
+	Selected,
+	Candidate(Sim),
+	MissingDuration,
+	ArtistsMismatch(Sim),
+	TitleMismatch(Sim),
+}
+
+
+impl Default for ItemStatus {
+	fn default() -> Self { ItemStatus::Pending }
+}
+
+
+impl From<&BackendItemStatus> for ItemStatus {
+	fn from(status: &BackendItemStatus) -> Self {
+		match *status {
+			BackendItemStatus::Selected => ItemStatus::Selected,
+			BackendItemStatus::Candidate(sim) => ItemStatus::Candidate(sim),
+			BackendItemStatus::MissingDuration => ItemStatus::MissingDuration,
+			BackendItemStatus::ArtistsMismatch(sim) => ItemStatus::ArtistsMismatch(sim),
+			BackendItemStatus::TitleMismatch(sim) => ItemStatus::TitleMismatch(sim),
+		}
+	}
+}
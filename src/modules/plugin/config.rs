@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+	sim::Sim,
+	track::{IdCleaner, Duration},
+	util::bytes,
+};
+
+
+#[derive(Debug, Clone)]
+pub struct Config {
+	pub plugins: Box<[Plugin]>,
+	pub duration_tolerance: u16,
+	pub size_factor: f32,
+	pub size_tolerance: f32,
+	pub min_bitrate: u16,
+	pub delete_invalid: bool,
+	pub id_cleaner: IdCleaner,
+}
+
+
+/// An external command that prints the candidates for a track.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct Plugin {
+	pub name: Box<str>,
+	pub command: PathBuf,
+	/// Passed before the track id, query and duration.
+	#[serde(default)]
+	pub args: Box<[Box<str>]>,
+	pub sim_threshold: Sim,
+	/// In seconds. The command is killed when it takes longer.
+	#[serde(default = "default_timeout")]
+	pub timeout: u64,
+}
+
+
+fn default_timeout() -> u64 {
+	60
+}
+
+
+impl Config {
+	pub fn duration_range(&self, duration: Duration) -> std::ops::Range<Duration> {
+		let seconds = duration.as_seconds();
+
+		std::ops::Range {
+			start: Duration::from_seconds(
+				seconds.saturating_sub(self.duration_tolerance)
+			),
+			end: Duration::from_seconds(
+				seconds.saturating_add(self.duration_tolerance)
+			),
+		}
+	}
+
+
+	pub fn expected_size(&self, duration: Duration) -> bytes::Mb {
+		let seconds = duration.as_seconds();
+
+		let minutes = seconds as f32 / 60.0;
+
+		bytes::Mb(minutes * self.size_factor)
+	}
+
+
+	pub fn size_range(&self, duration: Duration) -> std::ops::Range<usize> {
+		let bytes::Mb(expected_size) = self.expected_size(duration);
+
+		std::ops::Range {
+			start: bytes::Mb(expected_size - self.size_tolerance).into(),
+			end:   bytes::Mb(expected_size + self.size_tolerance).into(),
+		}
+	}
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Config {
+				duration_tolerance : config.slizzy.duration_tolerance,
+				size_factor        : config.slizzy.size_factor,
+				size_tolerance     : config.slizzy.size_tolerance,
+				min_bitrate        : config.slizzy.min_bitrate,
+				delete_invalid     : config.slizzy.delete_invalid,
+				id_cleaner         : config.slizzy.id_clean,
+				plugins            : config.plugin,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	slizzy: Slizzy,
+	// Optional, so that older config files remain valid.
+	#[serde(default)]
+	plugin: Box<[Plugin]>,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+struct Slizzy {
+	duration_tolerance: u16,
+	size_factor: f32,
+	size_tolerance: f32,
+	min_bitrate: u16,
+	#[serde(default)]
+	delete_invalid: bool,
+	id_clean: IdCleaner,
+}
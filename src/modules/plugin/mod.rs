@@ -0,0 +1,49 @@
+mod config;
+mod source;
+pub mod tui;
+
+use async_trait::async_trait;
+
+use crate::track::Track;
+use super::tracksource;
+pub use config::{Config, Plugin};
+pub use source::{
+	Error as SourceError,
+	Params as SourceParams,
+	Status,
+	ItemStatus,
+	Filter
+};
+
+
+/// Runs external commands as track sources, so that private sources needn't be built in.
+#[derive(Debug, Clone)]
+pub struct Module {
+	config: Config,
+}
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(config: Config) -> Self {
+		Module { config }
+	}
+}
+
+
+#[async_trait(?Send)]
+impl tracksource::Module for Module {
+	type Params = SourceParams;
+	type Error = SourceError;
+
+	async fn fetch(
+		&self,
+		track: &Track,
+		params: Self::Params
+	) -> Result<(), Self::Error> {
+		source
+			::fetch(self, track, params)
+			.await
+	}
+}
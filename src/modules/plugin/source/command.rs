@@ -0,0 +1,283 @@
+use std::{
+	io::{self, Read},
+	process::{Child, Command, ExitStatus, Output, Stdio},
+	thread,
+	time::{Duration as StdDuration, Instant},
+};
+
+use futures::channel::oneshot;
+
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::track::{Duration, Track};
+use super::super::Plugin;
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("failed to run command: {0}")]
+	Spawn(io::Error),
+
+	#[error("command timed out after {0} seconds")]
+	Timeout(u64),
+
+	#[error("command failed with {status}: {stderr}")]
+	Exit {
+		status: ExitStatus,
+		stderr: Box<str>,
+	},
+
+	#[error("invalid output: {0}")]
+	Output(serde_json::Error),
+}
+
+
+/// A download candidate, as printed by the command.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct Candidate {
+	pub id: Box<str>,
+	#[serde(default)]
+	pub duration: Option<Duration>,
+	/// In bytes.
+	#[serde(default)]
+	pub size: Option<usize>,
+	/// In kbps.
+	#[serde(default)]
+	pub bitrate: Option<u16>,
+	// We won't parse the URL here, so that a single malformed URL fails only its candidate.
+	pub download_url: Box<str>,
+}
+
+
+/// How often to check whether the command has exited.
+const POLL_INTERVAL: StdDuration = StdDuration::from_millis(50);
+
+
+/// Run the plugin with the track id, query and duration in seconds, when known. The command
+/// runs in its own thread, so that other sources aren't blocked meanwhile, and is killed if
+/// it doesn't finish within the plugin's timeout.
+pub async fn run(plugin: &Plugin, track: &Track) -> Result<Box<[Candidate]>, Error> {
+	let mut command = Command::new(&plugin.command);
+
+	command
+		.args(
+			plugin.args
+				.iter()
+				.map(AsRef::<str>::as_ref)
+		)
+		.arg(track.id().as_ref())
+		.arg(track.query_str())
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped());
+
+	if let Some(duration) = track.duration {
+		command.arg(
+			duration
+				.as_seconds()
+				.to_string()
+		);
+	}
+
+	log::debug!("plugin {} command: {:?}", plugin.name, command);
+
+	let timeout = StdDuration::from_secs(plugin.timeout);
+
+	let (tx, rx) = oneshot::channel();
+
+	thread::spawn(
+		move || {
+			let result = command
+				.spawn()
+				.map_err(Error::Spawn)
+				.and_then(|child| wait(child, timeout));
+
+			// The receiver is only dropped if the fetch is aborted.
+			let _ = tx.send(result);
+		}
+	);
+
+	let output = rx
+		.await
+		.expect("plugin thread dropped the channel")?;
+
+	if !output.status.success() {
+		return Err(
+			Error::Exit {
+				status: output.status,
+				stderr: String
+					::from_utf8_lossy(&output.stderr)
+					.trim()
+					.into(),
+			}
+		);
+	}
+
+	log::trace!(
+		"plugin {} output: {}",
+		plugin.name,
+		String::from_utf8_lossy(&output.stdout)
+	);
+
+	parse(&output.stdout)
+}
+
+
+/// Wait for the child to exit, killing it after the timeout. The output is read in separate
+/// threads, as the child would block on a full pipe otherwise.
+fn wait(mut child: Child, timeout: StdDuration) -> Result<Output, Error> {
+	fn read_all(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+		thread::spawn(
+			move || {
+				let mut data = Vec::new();
+
+				if let Some(mut pipe) = pipe {
+					// A read error leaves the output truncated, which fails the parsing anyway.
+					let _ = pipe.read_to_end(&mut data);
+				}
+
+				data
+			}
+		)
+	}
+
+	let stdout = read_all(child.stdout.take());
+	let stderr = read_all(child.stderr.take());
+
+	let deadline = Instant::now() + timeout;
+
+	let status = loop {
+		if let Some(status) = child.try_wait().map_err(Error::Spawn)? {
+			break status;
+		}
+
+		if Instant::now() >= deadline {
+			// The child may have exited meanwhile, which is fine.
+			let _ = child.kill();
+			let _ = child.wait();
+
+			return Err(Error::Timeout(timeout.as_secs()));
+		}
+
+		thread::sleep(POLL_INTERVAL);
+	};
+
+	Ok(
+		Output {
+			status,
+			stdout: stdout.join().unwrap_or_default(),
+			stderr: stderr.join().unwrap_or_default(),
+		}
+	)
+}
+
+
+/// Parse a JSON array of candidates.
+pub fn parse(output: &[u8]) -> Result<Box<[Candidate]>, Error> {
+	serde_json
+		::from_slice(output)
+		.map_err(Error::Output)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+
+	fn plugin(script: &str) -> Plugin {
+		Plugin {
+			name: "test".into(),
+			command: "sh".into(),
+			args: Box::new(["-c".into(), script.into(), "sh".into()]),
+			sim_threshold: std::convert::TryFrom::try_from(60_u8).expect("invalid sim"),
+			timeout: 1,
+		}
+	}
+
+
+	#[test]
+	fn test_parse() {
+		let candidates = parse(
+			br#"[
+				{
+					"id": "Test - track (Original Mix)",
+					"duration": 320,
+					"size": 12800000,
+					"download_url": "https://example.com/track.mp3"
+				},
+				{
+					"id": "Test - track (Remix)",
+					"bitrate": 320,
+					"download_url": "https://example.com/remix.mp3"
+				}
+			]"#
+		)
+			.expect("failed to parse candidates");
+
+		assert_eq!(
+			candidates.as_ref(),
+			[
+				Candidate {
+					id: "Test - track (Original Mix)".into(),
+					duration: Some(Duration::from_seconds(320)),
+					size: Some(12800000),
+					bitrate: None,
+					download_url: "https://example.com/track.mp3".into(),
+				},
+				Candidate {
+					id: "Test - track (Remix)".into(),
+					duration: None,
+					size: None,
+					bitrate: Some(320),
+					download_url: "https://example.com/remix.mp3".into(),
+				},
+			]
+		);
+
+		assert!(parse(b"not json").is_err());
+	}
+
+
+	#[test]
+	fn test_run() {
+		let mut track = Track
+			::new("Test - track")
+			.expect("invalid track");
+
+		track.duration = Some(Duration::from_seconds(320));
+
+		// Echo the arguments back as a candidate.
+		let echo = plugin(
+			r#"printf '[{"id": "%s", "duration": %s, "download_url": "https://example.com/%s"}]' "$1" "$3" "$2""#
+		);
+
+		let candidates = futures::executor::block_on(run(&echo, &track))
+			.expect("failed to run plugin");
+
+		assert_eq!(candidates.len(), 1);
+		assert_eq!(candidates[0].id.as_ref(), track.id().as_ref());
+		assert_eq!(candidates[0].duration, track.duration);
+
+		let failing = plugin("echo 'no access' >&2; exit 3");
+
+		assert!(
+			matches!(
+				futures::executor::block_on(run(&failing, &track)),
+				Err(Error::Exit { .. })
+			)
+		);
+
+		let hanging = plugin("sleep 10");
+
+		assert!(
+			matches!(
+				futures::executor::block_on(run(&hanging, &track)),
+				Err(Error::Timeout(1))
+			)
+		);
+	}
+}
@@ -0,0 +1,651 @@
+mod command;
+
+use std::path::Path;
+
+use thiserror::Error;
+
+use serde::Serialize;
+
+use futures::stream::StreamExt;
+
+use crate::{
+	report_wrapped,
+	track::{Track, Duration},
+	output::Output,
+	net::{http, url::Url},
+	sim::{self, Sim},
+	rank::{self, Score},
+	util::{self, bytes, mp3},
+};
+use super::super::{item, tracksource};
+use super::Plugin;
+use command::Candidate;
+
+
+/// The bitrate of the best quality mp3 files, in kbps.
+const MAX_BITRATE: u16 = 320;
+
+/// Entries are identified by a u8 in the progress reports.
+const MAX_ENTRIES: usize = u8::MAX as usize;
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	Plugins(Box<[PluginError]>),
+	Items(Box<[ItemError]>),
+}
+
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Error::Plugins(errors) => {
+				f.write_str("plugins errors:\n")?;
+
+				for error in errors.iter() {
+					writeln!(f, "  {}", error)?
+				}
+
+				Ok(())
+			},
+			Error::Items(errors) => {
+				f.write_str("items errors:\n")?;
+
+				for error in errors.iter() {
+					writeln!(f, "  {}", error)?
+				}
+
+				Ok(())
+			}
+		}
+	}
+}
+
+
+#[derive(Debug, Error)]
+#[error("{name}: {error}")]
+pub struct PluginError {
+	pub name: Box<str>,
+	pub error: command::Error,
+}
+
+
+#[derive(Debug, Error)]
+pub enum ItemError {
+	#[error("invalid download url: {0}")]
+	Url(url::ParseError),
+
+	#[error("http error: {0}")]
+	Http(http::Error),
+
+	#[error("download error: {0}")]
+	Download(http::downloader::Error),
+
+	#[error("invalid download: {0}")]
+	Invalid(tracksource::Mismatch),
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Filter {
+	Id(Sim),
+	Duration(Duration),
+	Size(usize),
+	Bitrate(u16),
+}
+
+
+impl std::fmt::Display for Filter {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Filter::Id(sim) => write!(
+				f,
+				"id mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			Filter::Duration(duration) => write!(f, "duration mismatch: {} out of range", duration),
+			Filter::Size(size) => write!(f, "file size mismatch: {} out of range", size),
+			Filter::Bitrate(bitrate) => write!(f, "bitrate mismatch: {} kbps below minimum", bitrate),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ItemStatus {
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
+	Filtered(Filter),
+	Candidate(Score),
+	Outranked(Score),
+	Downloading(util::io::Progress),
+	/// The file was downloaded but doesn't match what was expected.
+	Flagged(tracksource::Mismatch),
+	/// The inspected file metadata, if available.
+	Done(Option<mp3::Metadata>),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+			ItemStatus::Filtered(filter) => write!(f, "{}", filter),
+			ItemStatus::Candidate(score) => write!(f, "candidate: {} score", score),
+			ItemStatus::Outranked(score) => write!(f, "outranked: {} score", score),
+			ItemStatus::Downloading(progress) => match progress.percentage() {
+				Some(percentage) => write!(f, "downloading: {:.0}%", percentage * 100.0),
+				None => write!(f, "downloading: {}", bytes::Mb::from(progress.completed)),
+			},
+			ItemStatus::Flagged(mismatch) => write!(f, "done, but {}", mismatch),
+			ItemStatus::Done(Some(metadata)) => write!(
+				f,
+				"done! {} at {} kbps",
+				metadata.duration,
+				metadata.bitrate
+			),
+			ItemStatus::Done(None) => f.write_str("done!"),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Status {
+	Running,
+	NoPlugins,
+	NoEntries,
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
+	Done,
+}
+
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Running => f.write_str("running"),
+			Status::NoPlugins => f.write_str("no plugins configured"),
+			Status::NoEntries => f.write_str("no entries found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+			Status::Done => f.write_str("done"),
+		}
+	}
+}
+
+
+pub struct Params {
+	pub progress: Box<
+		dyn item::progress::Progress<
+			Id = u8,
+			Item = str,
+			Status = Status,
+			ItemStatus = ItemStatus,
+		> + Send
+	>,
+	pub ranking: rank::Participant,
+	pub output: Output,
+	pub downloads: tracksource::Downloads,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.field("ranking", &self.ranking)
+			.field("output", &self.output)
+			.field("downloads", &self.downloads)
+			.finish()
+	}
+}
+
+
+async fn fetch_size(url: &Url) -> Result<Option<usize>, ItemError> {
+	let response = http::Request
+		::new(url)
+		.set_method(http::request::Method::HEAD)
+		.send()
+		.await
+		.map_err(
+			|error| ItemError::Http(
+				http::Error::Request(error)
+			)
+		)?;
+
+	Ok(response.content_length())
+}
+
+
+async fn filter_candidate(
+	config: &super::Config,
+	plugin: &Plugin,
+	track: &Track,
+	candidate: &Candidate,
+	download_url: &Url,
+) -> Result<Result<Score, Filter>, ItemError> {
+	let id = config.id_cleaner.clean(&candidate.id);
+
+	let similarity = sim::str(
+		&id,
+		&track.id()
+	);
+
+	if similarity < plugin.sim_threshold {
+		return Ok(
+			Err(
+				Filter::Id(similarity)
+			)
+		);
+	}
+
+	let mut metrics = rank::Metrics {
+		sim: Some(similarity),
+		duration: None,
+		quality: None,
+		hints: track.metadata.hints(&candidate.id),
+	};
+
+	if let Some(bitrate) = candidate.bitrate {
+		if bitrate < config.min_bitrate {
+			return Ok(
+				Err(
+					Filter::Bitrate(bitrate)
+				)
+			);
+		}
+
+		metrics.quality = Some(
+			rank::closeness(
+				bitrate.min(MAX_BITRATE) as f32,
+				MAX_BITRATE as f32,
+				MAX_BITRATE.saturating_sub(config.min_bitrate) as f32,
+			)
+		);
+	}
+
+	if let Some(duration) = track.duration {
+		if let Some(candidate_duration) = candidate.duration {
+			let duration_range = config.duration_range(duration);
+
+			if !duration_range.contains(&candidate_duration) {
+				return Ok(
+					Err(
+						Filter::Duration(candidate_duration)
+					)
+				);
+			}
+
+			metrics.duration = Some(
+				rank::closeness(
+					candidate_duration.as_seconds() as f32,
+					duration.as_seconds() as f32,
+					config.duration_tolerance as f32,
+				)
+			);
+		}
+
+		// The bitrate is a better quality measure, so only fetch the size without it.
+		let size = match (candidate.size, candidate.bitrate) {
+			(Some(size), _) => Some(size),
+			(None, None) => fetch_size(download_url).await?,
+			(None, Some(_)) => None,
+		};
+
+		if let Some(size) = size {
+			let size_range = config.size_range(duration);
+
+			if !size_range.contains(&size) {
+				return Ok(
+					Err(
+						Filter::Size(size)
+					)
+				);
+			}
+
+			metrics.quality = metrics.quality.or(
+				Some(
+					rank::closeness(
+						bytes::Mb::from(size).0,
+						config.expected_size(duration).0,
+						config.size_tolerance,
+					)
+				)
+			);
+		}
+	}
+
+	Ok(
+		Ok(metrics.into())
+	)
+}
+
+
+async fn rank_item(
+	config: &super::Config,
+	plugin: &Plugin,
+	track: &Track,
+	candidate: Candidate,
+	status: impl Fn(&ItemStatus),
+) -> Result<Option<(Url, Box<str>, Score)>, ItemError> {
+	let download_url: Url = candidate.download_url
+		.parse()
+		.map_err(
+			|error| report_wrapped!(
+				ItemStatus::Error(
+					ItemError::Url(error)
+				),
+				status,
+				ItemStatus::Error(error) => error
+			)
+		)?;
+
+	let filter = filter_candidate(config, plugin, track, &candidate, &download_url)
+		.await
+		.map_err(
+			|error| report_wrapped!(
+				ItemStatus::Error(error),
+				status,
+				ItemStatus::Error(error) => error
+			)
+		)?;
+
+	match filter {
+		Err(filter) => {
+			log::debug!("plugin {} filtered entry: {:?}", plugin.name, filter);
+
+			status(
+				&ItemStatus::Filtered(filter)
+			);
+
+			Ok(None)
+		},
+
+		Ok(score) => {
+			status(
+				&ItemStatus::Candidate(score)
+			);
+
+			Ok(
+				Some((download_url, candidate.id, score))
+			)
+		},
+	}
+}
+
+
+async fn download_item(
+	config: &super::Config,
+	track: &Track,
+	download_url: Url,
+	name: Box<str>,
+	output: &Output,
+	status: impl Fn(&ItemStatus),
+) -> Result<tracksource::Download, ItemError> {
+	let default_path = Path::new(name.as_ref());
+
+	let path = http::Downloader
+		::new()
+		.reporter(
+			|&progress| status(
+				&ItemStatus::Downloading(progress)
+			)
+		)
+		.download_file(
+			&download_url,
+			default_path,
+			|stem, extension| output.path(track.id(), stem, extension)
+		)
+		.await
+		.map_err(
+			|error| report_wrapped!(
+				ItemStatus::Error(
+					ItemError::Download(error)
+				),
+				status,
+				ItemStatus::Error(error) => error
+			)
+		)?;
+
+	let inspection = tracksource::inspect(
+		&path,
+		track.duration.map(
+			|duration| config.duration_range(duration)
+		),
+		config.min_bitrate,
+	);
+
+	match inspection {
+		Err(mismatch) if config.delete_invalid => {
+			if let Err(error) = std::fs::remove_file(&path) {
+				log::warn!("failed to delete {:#?}: {}", path, error);
+			}
+
+			Err(
+				report_wrapped!(
+					ItemStatus::Error(
+						ItemError::Invalid(mismatch)
+					),
+					status,
+					ItemStatus::Error(error) => error
+				)
+			)
+		},
+
+		Err(mismatch) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Flagged(mismatch));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Plugin,
+					url: download_url.to_string().into(),
+					path,
+					metadata: None,
+					flagged: true,
+				}
+			)
+		},
+
+		Ok(metadata) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Done(metadata));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Plugin,
+					url: download_url.to_string().into(),
+					path,
+					metadata,
+					flagged: false,
+				}
+			)
+		},
+	}
+}
+
+
+pub async fn fetch(
+	module: &super::Module,
+	track: &Track,
+	params: Params
+) -> Result<(), Error> {
+	let progress = &params.progress;
+	let output = &params.output;
+	let plugins = &module.config.plugins;
+
+	if plugins.is_empty() {
+		progress.finish(&Status::NoPlugins);
+		return Ok(())
+	}
+
+	progress.status(&Status::Running);
+
+	let runs: futures::stream::FuturesUnordered<_> = plugins
+		.iter()
+		.map(
+			|plugin| async move {
+				(plugin, command::run(plugin, track).await)
+			}
+		)
+		.collect();
+
+	let mut entries = Vec::new();
+	let mut plugin_errors = Vec::new();
+
+	for (plugin, result) in runs.collect::<Vec<_>>().await {
+		match result {
+			Ok(candidates) => entries.extend(
+				candidates
+					.into_vec()
+					.into_iter()
+					.map(|candidate| (plugin, candidate))
+			),
+			Err(error) => {
+				log::warn!("plugin {} failed: {}", plugin.name, error);
+
+				plugin_errors.push(
+					PluginError {
+						name: plugin.name.clone(),
+						error,
+					}
+				);
+			},
+		}
+	}
+
+	log::debug!("plugin entries: {:#?}", entries);
+
+	// Only fatal when no plugin succeeded.
+	if plugin_errors.len() == plugins.len() {
+		let error = Error::Plugins(
+			plugin_errors.into_boxed_slice()
+		);
+
+		return Err(
+			report_wrapped!(
+				Status::Error(error),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		);
+	}
+
+	if entries.is_empty() {
+		progress.finish(&Status::NoEntries);
+		return Ok(())
+	}
+
+	if entries.len() > MAX_ENTRIES {
+		log::warn!("plugins printed {} entries, keeping the first {}", entries.len(), MAX_ENTRIES);
+		entries.truncate(MAX_ENTRIES);
+	}
+
+	let it = entries.into_iter();
+
+	progress.size_hint(
+		it.size_hint()
+	);
+
+	let items: futures::stream::FuturesUnordered<_> = it
+		.enumerate()
+		.map(
+			|(id, (plugin, candidate))| {
+				let id = id as u8;
+
+				progress.item(
+					id,
+					&format!("{}: {}", plugin.name, candidate.id)
+				);
+
+				async move {
+					let result = rank_item(
+						&module.config,
+						plugin,
+						track,
+						candidate,
+						move |status| progress.item_status(id, status)
+					);
+
+					(id, result.await)
+				}
+			}
+		)
+		.collect();
+
+	let mut errors: Vec<ItemError> = Vec::new();
+	let mut candidates = Vec::new();
+
+	for (id, result) in items.collect::<Vec<_>>().await {
+		match result {
+			Ok(Some((url, name, score))) => candidates.push((id, url, name, score)),
+			Ok(None) => (),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	let verdicts = params.ranking
+		.rank(
+			candidates
+				.iter()
+				.map(|(_, _, _, score)| *score)
+		)
+		.await;
+
+	let downloads: futures::stream::FuturesUnordered<_> = candidates
+		.into_iter()
+		.zip(verdicts.iter())
+		.filter_map(
+			|((id, url, name, score), verdict)| match verdict {
+				rank::Verdict::Selected => Some(
+					download_item(
+						&module.config,
+						track,
+						url,
+						name,
+						output,
+						move |status| progress.item_status(id, status)
+					)
+				),
+
+				rank::Verdict::Outranked => {
+					progress.item_status(id, &ItemStatus::Outranked(score));
+					None
+				},
+			}
+		)
+		.collect();
+
+	for result in downloads.collect::<Vec<_>>().await {
+		match result {
+			Ok(download) => params.downloads.push(download),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	if errors.is_empty() {
+		progress.finish(&Status::Done);
+		Ok(())
+	}
+	else {
+		let error = Error::Items(
+			errors.into_boxed_slice()
+		);
+
+		log::error!("plugin errors: {}", error);
+
+		Err(
+			report_wrapped!(
+				Status::Error(error),
+				|status| progress.finish(status),
+				Status::Error(error) => error
+			)
+		)
+	}
+}
@@ -0,0 +1,90 @@
+use crate::{
+	modules::tracksource,
+	rank::Score,
+	util::{self, mp3},
+};
+use super::{BackendError, BackendStatus, BackendItemStatus};
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+	Initializing,
+	Running,
+	NoPlugins,
+	NoEntries,
+	Error(Box<str>),
+	Done,
+}
+
+
+impl Default for Status {
+	fn default() -> Self { Status::Initializing }
+}
+
+
+impl From<&BackendStatus> for Status {
+	fn from(status: &BackendStatus) -> Self {
+		match status {
+			BackendStatus::Running => Status::Running,
+
+			BackendStatus::NoPlugins => Status::NoPlugins,
+
+			BackendStatus::NoEntries => Status::NoEntries,
+
+			BackendStatus::Error(BackendError::Plugins(errors)) if errors.len() == 1 => Status::Error(
+				errors[0]
+					.to_string()
+					.into_boxed_str()
+			),
+
+			BackendStatus::Error(BackendError::Plugins(_)) => Status::Error(
+				"all plugins failed, check the log for details".into()
+			),
+
+			BackendStatus::Error(BackendError::Items(_)) => Status::Error(
+				"some items failed, check the log for details".into()
+			),
+
+			BackendStatus::Done => Status::Done,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ItemStatus {
+	Fetching,
+	Error(Box<str>),
+	Filtered(super::Filter),
+	Candidate(Score),
+	Outranked(Score),
+	Downloading(util::io::Progress),
+	Flagged(tracksource::Mismatch),
+	Done(Option<mp3::Metadata>),
+}
+
+
+impl Default for ItemStatus {
+	fn default() -> Self { ItemStatus::Fetching }
+}
+
+
+impl From<&BackendItemStatus> for ItemStatus {
+	fn from(status: &BackendItemStatus) -> Self {
+		match status {
+			BackendItemStatus::Error(error) => ItemStatus::Error(
+				error
+					.to_string()
+					.into_boxed_str()
+			),
+			BackendItemStatus::Filtered(filter) => ItemStatus::Filtered(
+				filter.clone() // unfortunately, we have to clone here.
+			),
+			BackendItemStatus::Candidate(score) => ItemStatus::Candidate(*score),
+			BackendItemStatus::Outranked(score) => ItemStatus::Outranked(*score),
+			BackendItemStatus::Downloading(progress) => ItemStatus::Downloading(*progress),
+			BackendItemStatus::Flagged(mismatch) => ItemStatus::Flagged(*mismatch),
+			BackendItemStatus::Done(metadata) => ItemStatus::Done(*metadata),
+		}
+	}
+}
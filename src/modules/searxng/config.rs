@@ -0,0 +1,38 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::net::url::Url;
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Config {
+	/// The SearXNG instance, which must have the JSON format enabled.
+	pub base_url: Url,
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Config {
+				base_url: config.searxng.base_url
+					.parse()
+					.map_err(serde::de::Error::custom)?,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	searxng: Searxng,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+struct Searxng {
+	base_url: Box<str>,
+}
@@ -0,0 +1,153 @@
+mod config;
+
+use async_trait::async_trait;
+
+use serde::Deserialize;
+
+use super::websearch;
+use crate::net::{http, url::Url};
+pub use config::Config;
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Module {
+	config: Config,
+}
+
+
+impl super::Module for Module {
+	type Config = Config;
+
+	fn new(config: Config) -> Self {
+		Module { config }
+	}
+}
+
+
+pub type SearchConfig = websearch::SiteConfig;
+
+
+pub type SearchError = http::Error;
+
+
+#[derive(Debug, Deserialize)]
+struct Results {
+	#[serde(default)]
+	results: Vec<Item>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Item {
+	url: Box<str>,
+}
+
+
+impl Module {
+	/// The instance may be served under a path, so the search path is relative to it.
+	fn search_url(&self, query: &str, search_config: &SearchConfig) -> Url {
+		self.config.base_url
+			.dissect()
+			.push_path("search")
+			.expect("invalid url")
+			.append_query("q", &search_config.query(query))
+			.append_query("format", "json")
+			.assemble()
+	}
+}
+
+
+fn parse_results(results: Results) -> Box<[Url]> {
+	websearch::parse_urls(
+		"searxng",
+		results.results
+			.into_iter()
+			.map(|item| item.url)
+	)
+}
+
+
+#[async_trait]
+impl websearch::Module for Module {
+	type SearchConfig = SearchConfig;
+	type Error = SearchError;
+
+	async fn search(
+		&self,
+		query: &str,
+		search_config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error> {
+		let url = self.search_url(query, search_config);
+
+		log::debug!("searxng query url: {}", url);
+
+		let results = http::Request
+			::new(&url)
+			.send()
+			.await?
+			.body_json::<Results>()
+			.await?;
+
+		Ok(parse_results(results))
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_search_url() {
+		let module = Module {
+			config: Config {
+				base_url: "http://localhost:8888/searx".parse().expect("invalid url"),
+			},
+		};
+
+		let search_config = SearchConfig {
+			site: Some("beatport.com".into()),
+		};
+
+		assert_eq!(
+			module.search_url("Somne Kinetic Rampage", &search_config).as_ref(),
+			"http://localhost:8888/searx/search?q=site%3Abeatport.com+Somne+Kinetic+Rampage&format=json"
+		);
+	}
+
+
+	#[test]
+	fn test_parse_results() {
+		let results: Results = serde_json
+			::from_str(
+				r#"{
+					"query": "Somne Kinetic Rampage",
+					"number_of_results": 0,
+					"results": [
+						{
+							"url": "https://www.beatport.com/track/kinetic-rampage/15161234",
+							"title": "Kinetic Rampage",
+							"engine": "google"
+						},
+						{ "url": "not a url", "title": "Broken" },
+						{ "url": "https://skryptom.bandcamp.com/track/kinetic-rampage" }
+					],
+					"answers": []
+				}"#
+			)
+			.expect("failed to parse results");
+
+		assert_eq!(
+			parse_results(results).as_ref(),
+			[
+				"https://www.beatport.com/track/kinetic-rampage/15161234".parse().expect("invalid url"),
+				"https://skryptom.bandcamp.com/track/kinetic-rampage".parse().expect("invalid url"),
+			] as [Url; 2]
+		);
+
+		let empty: Results = serde_json::from_str("{}").expect("failed to parse results");
+
+		assert!(parse_results(empty).is_empty());
+	}
+}
@@ -13,6 +13,7 @@ pub struct Config {
 	pub sim_threshold: Sim,
 	pub duration_tolerance: u16,
 	pub bitrate_range: RangeFrom<u16>,
+	pub delete_invalid: bool,
 	pub id_cleaner: IdCleaner,
 }
 
@@ -42,6 +43,7 @@ impl<'de> Deserialize<'de> for Config {
 				sim_threshold: config.slider.sim_threshold,
 				duration_tolerance: config.slizzy.duration_tolerance,
 				bitrate_range: RangeFrom { start: config.slizzy.min_bitrate },
+				delete_invalid: config.slizzy.delete_invalid,
 				id_cleaner: config.slizzy.id_clean,
 			}
 		)
@@ -61,6 +63,8 @@ struct ConfigFile {
 struct Slizzy {
 	duration_tolerance: u16,
 	min_bitrate: u16,
+	#[serde(default)]
+	delete_invalid: bool,
 	id_clean: IdCleaner,
 }
 
@@ -9,6 +9,7 @@ use super::tracksource;
 pub use config::Config;
 pub use source::{
 	Error as SourceError,
+	Params as SourceParams,
 	Progress as SourceProgress,
 	Status,
 	ItemStatus,
@@ -33,16 +34,16 @@ impl super::Module for Module {
 
 #[async_trait(?Send)]
 impl tracksource::Module for Module {
-	type Params = SourceProgress;
+	type Params = SourceParams;
 	type Error = SourceError;
 
 	async fn fetch(
 		&self,
 		track: &Track,
-		progress: Self::Params
+		params: Self::Params
 	) -> Result<(), Self::Error> {
 		source
-			::fetch(self, track, progress)
+			::fetch(self, track, params)
 			.await
 	}
 }
@@ -8,6 +8,8 @@ use std::{
 
 use thiserror::Error;
 
+use serde::Serialize;
+
 use derive_more::Deref;
 
 use lazy_static::lazy_static;
@@ -16,19 +18,25 @@ use futures::stream::StreamExt;
 
 use crate::{
 	track::{Track, Duration},
+	output::Output,
 	net::{
 		http,
 		url::{self, Url}
 	},
 	sim::{self, Sim},
-	util,
+	rank::{self, Score},
+	util::{self, bytes, mp3},
 	report_wrapped,
 };
-use super::super::item;
+use super::super::{item, tracksource};
 use audios::{Audios, Entry};
 pub use self::scraper::Data;
 
 
+// Bitrate considered to be the best quality for ranking.
+const MAX_BITRATE: f32 = 320.0;
+
+
 lazy_static! {
 	static ref BASE_URL: url::Dissected<'static> = "https://slider.kz"
 		.try_into()
@@ -71,10 +79,14 @@ pub enum ItemError {
 
 	#[error("download error: {0}")]
 	Download(http::downloader::Error),
+
+	#[error("invalid download: {0}")]
+	Invalid(tracksource::Mismatch),
 }
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Filter {
 	Id(Sim),
 	Duration(Duration),
@@ -82,31 +94,98 @@ pub enum Filter {
 }
 
 
-#[derive(Debug)]
+impl std::fmt::Display for Filter {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Filter::Id(sim) => write!(
+				f,
+				"id mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			Filter::Bitrate(bitrate) => write!(f, "bitrate mismatch: {} out of range", bitrate),
+			Filter::Duration(duration) => write!(f, "duration mismatch: {} out of range", duration),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ItemStatus {
 	Attempt {
 		number: usize,
+		#[serde(serialize_with = "crate::util::serialize::display")]
 		error: ItemError,
 	},
-	Error(ItemError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
 	Filtered(Filter),
+	Candidate(Score),
+	Outranked(Score),
 	Downloading(util::io::Progress),
-	Done
+	/// The file was downloaded but doesn't match what was expected.
+	Flagged(tracksource::Mismatch),
+	/// The inspected file metadata, if available.
+	Done(Option<mp3::Metadata>),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Attempt { number, error } => write!(f, "attempt #{}: {}", number, error),
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+			ItemStatus::Filtered(filter) => write!(f, "{}", filter),
+			ItemStatus::Candidate(score) => write!(f, "candidate: {} score", score),
+			ItemStatus::Outranked(score) => write!(f, "outranked: {} score", score),
+			ItemStatus::Downloading(progress) => match progress.percentage() {
+				Some(percentage) => write!(f, "downloading: {:.0}%", percentage * 100.0),
+				None => write!(f, "downloading: {}", bytes::Mb::from(progress.completed)),
+			},
+			ItemStatus::Flagged(mismatch) => write!(f, "done, but {}", mismatch),
+			ItemStatus::Done(Some(metadata)) => write!(
+				f,
+				"done! {} at {} kbps",
+				metadata.duration,
+				metadata.bitrate
+			),
+			ItemStatus::Done(None) => f.write_str("done!"),
+		}
+	}
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Status {
 	Fetching,
 	Attempt {
 		number: usize,
+		#[serde(serialize_with = "crate::util::serialize::display")]
 		error: http::Error,
 	},
-	Error(Error),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error
+	),
 	Done,
 }
 
 
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Fetching => f.write_str("fetching"),
+			Status::Attempt { number, error } => write!(f, "attempt #{}: {}", number, error),
+			Status::Error(error) => write!(f, "error: {}", error),
+			Status::Done => f.write_str("done"),
+		}
+	}
+}
+
+
 #[derive(Deref)]
 // TODO replace with type synonym when https://github.com/rust-lang/rust/issues/63033
 // gets solved.
@@ -122,6 +201,25 @@ pub struct Progress(
 );
 
 
+pub struct Params {
+	pub progress: Progress,
+	pub ranking: rank::Participant,
+	pub output: Output,
+	pub downloads: tracksource::Downloads,
+}
+
+
+impl std::fmt::Debug for Params {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Params")
+			.field("ranking", &self.ranking)
+			.field("output", &self.output)
+			.field("downloads", &self.downloads)
+			.finish()
+	}
+}
+
+
 async fn fetch_info(entry: &Entry) -> Result<Data, ItemError> {
 	let url = BASE_URL
 		.clone()
@@ -201,13 +299,13 @@ async fn filter_entry(
 	track: &Track,
 	entry: &Entry,
 	status: &impl Fn(&ItemStatus),
-) -> Result<Option<Filter>, ItemError> {
+) -> Result<Result<Score, Filter>, ItemError> {
 	if let Some(duration) = track.duration {
 		let duration_range = config.duration_range(duration);
 
 		if !duration_range.contains(&entry.duration) {
 			return Ok(
-				Some(
+				Err(
 					Filter::Duration(entry.duration)
 				)
 			);
@@ -223,7 +321,7 @@ async fn filter_entry(
 
 	if similarity < config.sim_threshold {
 		return Ok(
-			Some(
+			Err(
 				Filter::Id(similarity)
 			)
 		);
@@ -256,32 +354,66 @@ async fn filter_entry(
 
 	if !config.bitrate_range.contains(&info.bitrate) {
 		return Ok(
-			Some(
+			Err(
 				Filter::Bitrate(info.bitrate)
 			)
 		);
 	}
 
-	Ok(None)
+	let metrics = rank::Metrics {
+		sim: Some(similarity),
+		duration: track.duration.map(
+			|duration| rank::closeness(
+				entry.duration.as_seconds() as f32,
+				duration.as_seconds() as f32,
+				config.duration_tolerance as f32,
+			)
+		),
+		quality: Some(info.bitrate as f32 / MAX_BITRATE),
+		hints: track.metadata.hints(&entry.track_id),
+	};
+
+	Ok(
+		Ok(metrics.into())
+	)
 }
 
 
-async fn handle_item(
+async fn rank_item(
 	config: &super::Config,
 	track: &Track,
 	entry: Entry,
 	status: impl Fn(&ItemStatus),
-) -> Result<(), ItemError> {
-	let filter = filter_entry(config, track, &entry, &status).await?;
+) -> Result<Option<(Entry, Score)>, ItemError> {
+	match filter_entry(config, track, &entry, &status).await? {
+		Err(filter) => {
+			status(
+				&ItemStatus::Filtered(filter)
+			);
 
-	if let Some(filter) = filter {
-		status(
-			&ItemStatus::Filtered(filter)
-		);
+			Ok(None)
+		},
+
+		Ok(score) => {
+			status(
+				&ItemStatus::Candidate(score)
+			);
 
-		return Ok(())
+			Ok(
+				Some((entry, score))
+			)
+		},
 	}
+}
 
+
+async fn download_item(
+	config: &super::Config,
+	track: &Track,
+	entry: Entry,
+	output: &Output,
+	status: impl Fn(&ItemStatus),
+) -> Result<tracksource::Download, ItemError> {
 	let build_url = || -> Result<Url, url::PathError> {
 		Ok(
 			BASE_URL
@@ -316,14 +448,18 @@ async fn handle_item(
 		entry.track_id.as_ref()
 	);
 
-	http::Downloader
+	let path = http::Downloader
 		::new()
 		.reporter(
 			|&progress| status(
 				&ItemStatus::Downloading(progress)
 			)
 		)
-		.download_file(&url, default_path)
+		.download_file(
+			&url,
+			default_path,
+			|stem, extension| output.path(track.id(), stem, extension)
+		)
 		.await
 		.map_err(
 			|error| report_wrapped!(
@@ -335,17 +471,72 @@ async fn handle_item(
 			)
 		)?;
 
-	status(&ItemStatus::Done);
+	let inspection = tracksource::inspect(
+		&path,
+		track.duration.map(
+			|duration| config.duration_range(duration)
+		),
+		config.bitrate_range.start,
+	);
 
-	Ok(())
+	match inspection {
+		Err(mismatch) if config.delete_invalid => {
+			if let Err(error) = std::fs::remove_file(&path) {
+				log::warn!("failed to delete {:#?}: {}", path, error);
+			}
+
+			Err(
+				report_wrapped!(
+					ItemStatus::Error(
+						ItemError::Invalid(mismatch)
+					),
+					status,
+					ItemStatus::Error(error) => error
+				)
+			)
+		},
+
+		Err(mismatch) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Flagged(mismatch));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Slider,
+					url: url.to_string().into(),
+					path,
+					metadata: None,
+					flagged: true,
+				}
+			)
+		},
+
+		Ok(metadata) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Done(metadata));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Slider,
+					url: url.to_string().into(),
+					path,
+					metadata,
+					flagged: false,
+				}
+			)
+		},
+	}
 }
 
 
 pub async fn fetch(
 	module: &super::Module,
 	track: &Track,
-	progress: Progress
+	params: Params
 ) -> Result<(), Error> {
+	let progress = &params.progress;
+	let output = &params.output;
+
 	progress.status(&Status::Fetching);
 
 	let query_str = track.query_str();
@@ -393,26 +584,68 @@ pub async fn fetch(
 
 				progress.item(id, &entry.track_id);
 
-				let progress = &progress;
+				async move {
+					let result = rank_item(
+						&module.config,
+						track,
+						entry,
+						move |status| progress.item_status(id, status)
+					);
 
-				handle_item(
-					&module.config,
-					track,
-					entry,
-					move |status| progress.item_status(id, status)
-				)
+					(id, result.await)
+				}
 			}
 		)
 		.collect();
 
-	let errors: Vec<ItemError> = items
+	let mut errors: Vec<ItemError> = Vec::new();
+	let mut candidates = Vec::new();
+
+	for (id, result) in items.collect::<Vec<_>>().await {
+		match result {
+			Ok(Some((entry, score))) => candidates.push((id, entry, score)),
+			Ok(None) => (),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	let verdicts = params.ranking
+		.rank(
+			candidates
+				.iter()
+				.map(|(_, _, score)| *score)
+		)
+		.await;
+
+	let downloads: futures::stream::FuturesUnordered<_> = candidates
+		.into_iter()
+		.zip(verdicts.iter())
 		.filter_map(
-			|result| async move {
-				result.err()
+			|((id, entry, score), verdict)| match verdict {
+				rank::Verdict::Selected => Some(
+					download_item(
+						&module.config,
+						track,
+						entry,
+						output,
+						move |status| progress.item_status(id, status)
+					)
+				),
+
+				rank::Verdict::Outranked => {
+					progress.item_status(id, &ItemStatus::Outranked(score));
+					None
+				},
 			}
 		)
-		.collect()
-		.await;
+		.collect();
+
+	for result in downloads.collect::<Vec<_>>().await {
+		match result {
+			Ok(download) => params.downloads.push(download),
+			Err(error) => errors.push(error),
+		}
+	}
 
 	if errors.is_empty() {
 		progress.finish(&Status::Done);
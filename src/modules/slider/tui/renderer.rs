@@ -109,6 +109,20 @@ impl<'a> Renderer<'a> {
 							)
 						},
 
+						ItemStatus::Candidate(score) => (
+							format!("{} | candidate: {} score", label, score)
+								.into(),
+							Style::default(),
+						),
+
+						ItemStatus::Outranked(score) => (
+							format!("{} | outranked: {} score", label, score)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Yellow),
+						),
+
 						ItemStatus::Downloading(progress) => {
 							let text =
 								if let Some(percentage) = progress.percentage() {
@@ -132,7 +146,28 @@ impl<'a> Renderer<'a> {
 							)
 						},
 
-						ItemStatus::Done => (
+						ItemStatus::Flagged(mismatch) => (
+							format!("{} | done, but {}", label, mismatch)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Yellow),
+						),
+
+						ItemStatus::Done(Some(metadata)) => (
+							format!(
+								"{} | done! {} at {} kbps",
+								label,
+								metadata.duration,
+								metadata.bitrate
+							)
+								.into(),
+							Style
+								::default()
+								.fg(Color::Green),
+						),
+
+						ItemStatus::Done(None) => (
 							format!("{} | done!", label)
 								.into(),
 							Style
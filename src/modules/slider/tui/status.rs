@@ -1,4 +1,8 @@
-use crate::util;
+use crate::{
+	modules::tracksource,
+	rank::Score,
+	util::{self, mp3},
+};
 use super::{BackendError, BackendStatus, BackendItemStatus};
 
 
@@ -58,8 +62,11 @@ pub enum ItemStatus {
 	},
 	Error(Box<str>),
 	Filtered(super::Filter),
+	Candidate(Score),
+	Outranked(Score),
 	Downloading(util::io::Progress),
-	Done
+	Flagged(tracksource::Mismatch),
+	Done(Option<mp3::Metadata>),
 }
 
 
@@ -83,8 +90,11 @@ impl From<&BackendItemStatus> for ItemStatus {
 					.into_boxed_str()
 			),
 			BackendItemStatus::Filtered(filter) => ItemStatus::Filtered(*filter),
+			BackendItemStatus::Candidate(score) => ItemStatus::Candidate(*score),
+			BackendItemStatus::Outranked(score) => ItemStatus::Outranked(*score),
 			BackendItemStatus::Downloading(progress) => ItemStatus::Downloading(*progress),
-			BackendItemStatus::Done => ItemStatus::Done,
+			BackendItemStatus::Flagged(mismatch) => ItemStatus::Flagged(*mismatch),
+			BackendItemStatus::Done(metadata) => ItemStatus::Done(*metadata),
 		}
 	}
 }
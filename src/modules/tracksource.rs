@@ -1,8 +1,22 @@
+use std::{
+	cell::RefCell,
+	fs::File,
+	io::BufReader,
+	ops::Range,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
+
 use async_trait::async_trait;
 
 use enumset::EnumSetType;
 
-use crate::track::Track;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	track::{Duration, Track},
+	util::{id3, mp3},
+};
 
 
 #[async_trait(?Send)]
@@ -18,10 +32,152 @@ pub trait Module: super::Module {
 }
 
 
-#[derive(Debug, Hash, EnumSetType)]
+#[derive(Debug, Hash, EnumSetType, Deserialize, Serialize)]
 #[enumset(no_ops)]
+#[serde(rename_all = "lowercase")]
 pub enum TrackSources {
 	Music2k,
 	Slider,
 	Zippy,
+	Plugin,
+	Library,
+}
+
+
+impl std::fmt::Display for TrackSources {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			TrackSources::Music2k => write!(f, "music2k"),
+			TrackSources::Slider => write!(f, "slider"),
+			TrackSources::Zippy => write!(f, "zippy"),
+			TrackSources::Plugin => write!(f, "plugin"),
+			TrackSources::Library => write!(f, "library"),
+		}
+	}
+}
+
+
+/// A file downloaded by a track source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Download {
+	pub source: TrackSources,
+	pub url: Box<str>,
+	pub path: PathBuf,
+	/// The inspected file metadata, if available.
+	pub metadata: Option<mp3::Metadata>,
+	/// Whether the file failed the inspection, but was kept.
+	pub flagged: bool,
+}
+
+
+/// Collects the downloads of all track sources. Clones share the same collection.
+#[derive(Debug, Clone, Default)]
+pub struct Downloads(Rc<RefCell<Vec<Download>>>);
+
+
+impl Downloads {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+
+	pub fn is_empty(&self) -> bool {
+		self.0
+			.borrow()
+			.is_empty()
+	}
+
+
+	pub fn push(&self, download: Download) {
+		log::info!("downloaded: {:#?}", download.path);
+
+		self.0
+			.borrow_mut()
+			.push(download);
+	}
+
+
+	pub fn take(&self) -> Box<[Download]> {
+		self.0
+			.take()
+			.into_boxed_slice()
+	}
+}
+
+
+/// A downloaded file that doesn't match the expected duration or bitrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Mismatch {
+	Duration(Duration),
+	Bitrate(u16),
+}
+
+
+impl std::fmt::Display for Mismatch {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Mismatch::Duration(duration) => write!(f, "duration mismatch: {}", duration),
+			Mismatch::Bitrate(bitrate) => write!(f, "bitrate mismatch: {} kbps", bitrate),
+		}
+	}
+}
+
+
+/// Inspect a downloaded mp3 file, checking its actual duration and bitrate. Files that
+/// can't be inspected, like other formats, yield `Ok(None)`.
+pub fn inspect(
+	path: &Path,
+	duration_range: Option<Range<Duration>>,
+	min_bitrate: u16,
+) -> Result<Option<mp3::Metadata>, Mismatch> {
+	if !is_mp3(path) {
+		return Ok(None);
+	}
+
+	let metadata = File
+		::open(path)
+		.map(BufReader::new)
+		.and_then(mp3::extract_metadata);
+
+	let metadata = match metadata {
+		Ok(metadata) => metadata,
+		Err(error) => {
+			log::warn!("failed to inspect {:#?}: {}", path, error);
+			return Ok(None);
+		}
+	};
+
+	if let Some(range) = duration_range {
+		if !range.contains(&metadata.duration) {
+			return Err(Mismatch::Duration(metadata.duration));
+		}
+	}
+
+	if metadata.bitrate < min_bitrate {
+		return Err(Mismatch::Bitrate(metadata.bitrate));
+	}
+
+	Ok(Some(metadata))
+}
+
+
+/// Replace the tags of a downloaded mp3 file with the track's metadata. Failing to tag is
+/// not fatal, so errors are only logged.
+pub fn tag(path: &Path, track: &Track) {
+	if !is_mp3(path) {
+		return;
+	}
+
+	if let Err(error) = id3::write(path, &id3::Tag::from(track)) {
+		log::warn!("failed to tag {:#?}: {}", path, error);
+	}
+}
+
+
+fn is_mp3(path: &Path) -> bool {
+	matches!(
+		path.extension(),
+		Some(ext) if ext.eq_ignore_ascii_case("mp3")
+	)
 }
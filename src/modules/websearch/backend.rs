@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+
+use serde::{Deserialize, Deserializer};
+
+use thiserror::Error;
+
+use crate::net::url::Url;
+use super::SiteConfig;
+use super::super::{google, searxng, bing, duckduckgo};
+
+
+/// The available web search backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backends {
+	Google,
+	Searxng,
+	Bing,
+	DuckDuckGo,
+}
+
+
+impl Default for Backends {
+	fn default() -> Self { Backends::Google }
+}
+
+
+/// The configuration of the selected backend. Only that backend's section is required.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Config {
+	Google(google::Config),
+	Searxng(searxng::Config),
+	Bing(bing::Config),
+	DuckDuckGo(duckduckgo::Config),
+}
+
+
+impl<'de> Deserialize<'de> for Config {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+
+		// Buffered, as the backend's config is read from the same file.
+		let file = toml::Value::deserialize(deserializer)?;
+
+		let config: ConfigFile = file
+			.clone()
+			.try_into()
+			.map_err(D::Error::custom)?;
+
+		let config = match config.websearch.backend {
+			Backends::Google => file.try_into().map(Config::Google),
+			Backends::Searxng => file.try_into().map(Config::Searxng),
+			Backends::Bing => file.try_into().map(Config::Bing),
+			Backends::DuckDuckGo => file.try_into().map(Config::DuckDuckGo),
+		};
+
+		config.map_err(D::Error::custom)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	// Optional, so that older config files remain valid.
+	#[serde(default)]
+	websearch: WebSearch,
+}
+
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WebSearch {
+	backend: Backends,
+}
+
+
+/// The per module search config, which holds the fields of all backends.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct SearchConfig {
+	/// Google's custom search engine id.
+	#[serde(default)]
+	pub custom_search: Option<Box<str>>,
+
+	#[serde(flatten)]
+	pub site: SiteConfig,
+}
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("google: {0}")]
+	Google(google::SearchError),
+
+	#[error("searxng: {0}")]
+	Searxng(searxng::SearchError),
+
+	#[error("bing: {0}")]
+	Bing(bing::SearchError),
+
+	#[error("duckduckgo: {0}")]
+	DuckDuckGo(duckduckgo::SearchError),
+
+	#[error("google: custom-search is configured neither for this module nor globally")]
+	MissingCustomSearch,
+}
+
+
+/// Dispatches to the configured backend, so that the modules needn't know which one.
+#[derive(Debug, Clone)]
+pub enum Backend {
+	Google(google::Module),
+	Searxng(searxng::Module),
+	Bing(bing::Module),
+	DuckDuckGo(duckduckgo::Module),
+}
+
+
+impl super::super::Module for Backend {
+	type Config = Config;
+
+	fn new(config: Config) -> Self {
+		match config {
+			Config::Google(config) => Backend::Google(google::Module::new(config)),
+			Config::Searxng(config) => Backend::Searxng(searxng::Module::new(config)),
+			Config::Bing(config) => Backend::Bing(bing::Module::new(config)),
+			Config::DuckDuckGo(config) => Backend::DuckDuckGo(duckduckgo::Module::new(config)),
+		}
+	}
+}
+
+
+#[async_trait]
+impl super::Module for Backend {
+	type SearchConfig = SearchConfig;
+	type Error = Error;
+
+	async fn search(
+		&self,
+		query: &str,
+		search_config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error> {
+		match self {
+			Backend::Google(google) => {
+				let custom_search = search_config.custom_search
+					.as_deref()
+					.or_else(|| google.custom_search())
+					.ok_or(Error::MissingCustomSearch)?
+					.into();
+
+				let google_config = google::SearchConfig {
+					custom_search,
+					site_search: search_config.site.site.clone(),
+				};
+
+				google
+					.search(query, &google_config)
+					.await
+					.map_err(Error::Google)
+			},
+
+			Backend::Searxng(searxng) => searxng
+				.search(query, &search_config.site)
+				.await
+				.map_err(Error::Searxng),
+
+			Backend::Bing(bing) => bing
+				.search(query, &search_config.site)
+				.await
+				.map_err(Error::Bing),
+
+			Backend::DuckDuckGo(duckduckgo) => duckduckgo
+				.search(query, &search_config.site)
+				.await
+				.map_err(Error::DuckDuckGo),
+		}
+	}
+
+
+	/// Google requires a custom search engine, the other backends need nothing per module.
+	fn can_search(&self, search_config: &Self::SearchConfig) -> bool {
+		match self {
+			Backend::Google(google) => search_config.custom_search.is_some()
+				|| google.custom_search().is_some(),
+			_ => true,
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_config() {
+		let config: Config = crate::config
+			::read(
+				r#"
+					[google]
+					key = "key"
+				"#
+			)
+			.expect("failed to read config");
+
+		assert!(matches!(config, Config::Google(_)));
+
+		let config: Config = crate::config
+			::read(
+				r#"
+					[websearch]
+					backend = "searxng"
+
+					[searxng]
+					base-url = "http://localhost:8888"
+				"#
+			)
+			.expect("failed to read config");
+
+		assert!(matches!(config, Config::Searxng(_)));
+
+		let config: Config = crate::config
+			::read(
+				r#"
+					[websearch]
+					backend = "duckduckgo"
+				"#
+			)
+			.expect("failed to read config");
+
+		assert_eq!(config, Config::DuckDuckGo(duckduckgo::Config { }));
+	}
+}
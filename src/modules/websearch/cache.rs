@@ -0,0 +1,381 @@
+use std::{
+	fs,
+	hash::{Hash, Hasher},
+	path::PathBuf,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+	net::url::Url,
+	util::hash::Fnv1a,
+};
+
+
+/// The configuration of the wrapped backend, along with the cache's.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Config<C> {
+	pub backend: C,
+	/// Zero disables the cache.
+	pub ttl: Duration,
+}
+
+
+impl<'de, C> Deserialize<'de> for Config<C>
+where
+	C: serde::de::DeserializeOwned,
+{
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+
+		// Buffered, as the backend's config is read from the same file.
+		let file = toml::Value::deserialize(deserializer)?;
+
+		let config: ConfigFile = file
+			.clone()
+			.try_into()
+			.map_err(D::Error::custom)?;
+
+		let backend = file
+			.try_into()
+			.map_err(D::Error::custom)?;
+
+		Ok(
+			Config {
+				backend,
+				ttl: Duration::from_secs(config.websearch.cache_ttl),
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	#[serde(default)]
+	websearch: WebSearch,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all(deserialize = "kebab-case"))]
+struct WebSearch {
+	/// In seconds.
+	cache_ttl: u64,
+}
+
+
+impl Default for WebSearch {
+	fn default() -> Self {
+		Self {
+			cache_ttl: 24 * 60 * 60,
+		}
+	}
+}
+
+
+/// Search results stored as a JSON file per query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cache {
+	dir: PathBuf,
+	ttl: Duration,
+}
+
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Entry {
+	/// Seconds since the unix epoch.
+	time: u64,
+	query: Box<str>,
+	urls: Box<[Box<str>]>,
+}
+
+
+impl Cache {
+	/// Use the XDG cache directory.
+	pub fn open(ttl: Duration) -> anyhow::Result<Self> {
+		let dir = xdg::BaseDirectories
+			::with_prefix("slizzy")?
+			.create_cache_directory("websearch")?;
+
+		log::debug!("websearch cache dir: {:#?}", dir);
+
+		let cache = Self::at(dir, ttl);
+
+		cache.sweep();
+
+		Ok(cache)
+	}
+
+
+	pub fn at<P: Into<PathBuf>>(dir: P, ttl: Duration) -> Self {
+		Self {
+			dir: dir.into(),
+			ttl,
+		}
+	}
+
+
+	/// The fresh results of the query, if any. Stale or invalid entries are deleted.
+	pub fn get(&self, key: u64, query: &str) -> Option<Box<[Url]>> {
+		let path = self.path(key);
+
+		let data = fs::read(&path).ok()?;
+
+		let entry: Option<Entry> = serde_json
+			::from_slice(&data)
+			.map_err(|error| log::warn!("invalid websearch cache entry: {}", error))
+			.ok();
+
+		let entry = match entry {
+			Some(entry) if entry.query.as_ref() == query && !self.expired(entry.time) => entry,
+			_ => {
+				if let Err(error) = fs::remove_file(&path) {
+					log::warn!("failed to delete websearch cache entry: {}", error);
+				}
+
+				return None;
+			}
+		};
+
+		let urls = entry.urls
+			.iter()
+			.filter_map(|url| url.parse().ok())
+			.collect();
+
+		Some(urls)
+	}
+
+
+	/// Failures are logged, as the cache is merely an optimization.
+	pub fn put(&self, key: u64, query: &str, urls: &[Url]) {
+		let entry = Entry {
+			time: now(),
+			query: query.into(),
+			urls: urls
+				.iter()
+				.map(|url| url.as_ref().into())
+				.collect(),
+		};
+
+		let result = serde_json
+			::to_vec(&entry)
+			.map_err(anyhow::Error::from)
+			.and_then(
+				|data| fs
+					::write(self.path(key), data)
+					.map_err(Into::into)
+			);
+
+		if let Err(error) = result {
+			log::warn!("failed to write websearch cache entry: {}", error);
+		}
+	}
+
+
+	/// Delete the entries older than the ttl, so that the cache doesn't grow forever.
+	fn sweep(&self) {
+		let dir = match fs::read_dir(&self.dir) {
+			Ok(dir) => dir,
+			Err(error) => {
+				log::warn!("failed to read websearch cache dir: {}", error);
+				return;
+			}
+		};
+
+		for entry in dir.filter_map(Result::ok) {
+			let modified = entry
+				.metadata()
+				.and_then(|metadata| metadata.modified())
+				.ok()
+				.and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+
+			if let Some(modified) = modified {
+				if self.expired(modified.as_secs()) {
+					let _ = fs::remove_file(entry.path());
+				}
+			}
+		}
+	}
+
+
+	/// Whether an entry written at the given time, in seconds since the unix epoch, is stale.
+	fn expired(&self, time: u64) -> bool {
+		now().saturating_sub(time) >= self.ttl.as_secs()
+	}
+
+
+	fn path(&self, key: u64) -> PathBuf {
+		self.dir.join(format!("{:016x}.json", key))
+	}
+}
+
+
+/// Wraps a web search backend, caching its results on disk. Cached and live searches look
+/// the same to the modules.
+#[derive(Debug, Clone)]
+pub struct Cached<WS> {
+	backend: WS,
+	/// The hash of the backend's config, so that changing it, like switching backends or
+	/// fetching more results, won't serve stale results.
+	backend_key: u64,
+	cache: Option<Cache>,
+}
+
+
+impl<WS> Cached<WS> {
+	/// Always search live, as with --no-cache.
+	pub fn disable_cache(&mut self) {
+		self.cache = None;
+	}
+}
+
+
+impl<WS> super::super::Module for Cached<WS>
+where
+	WS: super::super::Module,
+	WS::Config: Hash,
+{
+	type Config = Config<WS::Config>;
+
+	fn new(config: Self::Config) -> Self {
+		let cache = if config.ttl == Duration::from_secs(0) {
+			None
+		}
+		else {
+			Cache
+				::open(config.ttl)
+				.map_err(|error| log::warn!("websearch cache unavailable: {}", error))
+				.ok()
+		};
+
+		let mut hasher = Fnv1a::default();
+		config.backend.hash(&mut hasher);
+
+		Self {
+			backend_key: hasher.finish(),
+			backend: WS::new(config.backend),
+			cache,
+		}
+	}
+}
+
+
+#[async_trait]
+impl<WS> super::Module for Cached<WS>
+where
+	WS: super::Module + Send + Sync,
+	WS::Config: Hash,
+	WS::SearchConfig: Hash + Sync,
+{
+	type SearchConfig = WS::SearchConfig;
+	type Error = WS::Error;
+
+	async fn search(
+		&self,
+		query: &str,
+		config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error> {
+		let cache = match &self.cache {
+			Some(cache) => cache,
+			None => return self.backend.search(query, config).await,
+		};
+
+		let key = key(self.backend_key, query, config);
+
+		if let Some(urls) = cache.get(key, query) {
+			log::debug!("websearch cache hit: {}", query);
+			return Ok(urls);
+		}
+
+		let urls = self.backend
+			.search(query, config)
+			.await?;
+
+		// Empty results may be transient, so they are retried rather than cached.
+		if !urls.is_empty() {
+			cache.put(key, query, &urls);
+		}
+
+		Ok(urls)
+	}
+
+
+	fn can_search(&self, config: &Self::SearchConfig) -> bool {
+		self.backend.can_search(config)
+	}
+}
+
+
+/// A stable hash, as it names the cache files.
+fn key<C: Hash>(backend_key: u64, query: &str, config: &C) -> u64 {
+	let mut hasher = Fnv1a::default();
+	backend_key.hash(&mut hasher);
+	query.hash(&mut hasher);
+	config.hash(&mut hasher);
+	hasher.finish()
+}
+
+
+fn now() -> u64 {
+	SystemTime
+		::now()
+		.duration_since(UNIX_EPOCH)
+		.expect("system time before unix epoch")
+		.as_secs()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_cache() {
+		let dir = std::env::temp_dir().join(
+			format!("slizzy-websearch-cache-test-{}", std::process::id())
+		);
+		fs::create_dir_all(&dir).expect("failed to create test dir");
+
+		let urls: Box<[Url]> = Box::new(
+			["https://www.beatport.com/track/kinetic-rampage/15161234".parse().expect("invalid url")]
+		);
+
+		let cache = Cache::at(&dir, Duration::from_secs(60));
+		let key = key(0, "Test - track", &"config");
+
+		assert_eq!(cache.get(key, "Test - track"), None);
+
+		cache.put(key, "Test - track", &urls);
+
+		assert_eq!(cache.get(key, "Test - track"), Some(urls.clone()));
+		assert_ne!(key, super::key(0, "Test - track", &"other config"));
+		assert_ne!(key, super::key(1, "Test - track", &"config"));
+
+		// A different query with the same key is deleted.
+		assert_eq!(cache.get(key, "Other - track"), None);
+		assert!(!cache.path(key).exists());
+
+		cache.put(key, "Test - track", &urls);
+
+		// Expired.
+		let expired = Cache::at(&dir, Duration::from_secs(0));
+
+		assert_eq!(expired.get(key, "Test - track"), None);
+		assert!(!cache.path(key).exists());
+
+		cache.put(key, "Test - track", &urls);
+
+		cache.sweep();
+		assert!(cache.path(key).exists());
+
+		expired.sweep();
+		assert!(!cache.path(key).exists());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
@@ -0,0 +1,73 @@
+mod backend;
+mod cache;
+
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::net::url::Url;
+pub use backend::{Backend, Error};
+pub use cache::Cached;
+
+
+#[async_trait]
+pub trait Module: super::Module {
+	type SearchConfig: DeserializeOwned;
+	type Error: std::error::Error;
+
+	async fn search(
+		&self,
+		query: &str,
+		config: &Self::SearchConfig
+	) -> Result<Box<[Url]>, Self::Error>;
+
+	/// Whether the search config is complete, so that optional searches may be skipped.
+	fn can_search(&self, _config: &Self::SearchConfig) -> bool {
+		true
+	}
+}
+
+
+/// Restricts the results to a site, so that a single engine may serve all modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub struct SiteConfig {
+	#[serde(default)]
+	pub site: Option<Box<str>>,
+}
+
+
+impl SiteConfig {
+	/// The query, restricted to the site if any.
+	pub fn query<'a>(&self, query: &'a str) -> Cow<'a, str> {
+		match &self.site {
+			Some(site) => format!("site:{} {}", site, query).into(),
+			None => query.into(),
+		}
+	}
+}
+
+
+/// Parse the result urls, skipping malformed ones so that a single bad result won't
+/// fail the whole search.
+pub fn parse_urls<I>(backend: &str, items: I) -> Box<[Url]>
+where
+	I: IntoIterator<Item = Box<str>>,
+{
+	items
+		.into_iter()
+		.filter_map(
+			|item| {
+				let result = item.parse();
+
+				if result.is_err() {
+					log::debug!("{} url parse failed: {}", backend, item);
+				}
+
+				result.ok()
+			}
+		)
+		.collect()
+}
@@ -16,6 +16,8 @@ pub struct Config<SearchConfig> {
 	pub duration_tolerance: u16,
 	pub size_factor: f32,
 	pub size_tolerance: f32,
+	pub min_bitrate: u16,
+	pub delete_invalid: bool,
 	pub id_cleaner: IdCleaner,
 	pub blacklist: RegexSet,
 }
@@ -36,12 +38,17 @@ impl<SearchConfig> Config<SearchConfig> {
 	}
 
 
-	pub fn size_range(&self, duration: Duration) -> std::ops::Range<usize> {
+	pub fn expected_size(&self, duration: Duration) -> bytes::Mb {
 		let seconds = duration.as_seconds();
 
 		let minutes = seconds as f32 / 60.0;
 
-		let expected_size = minutes * self.size_factor;
+		bytes::Mb(minutes * self.size_factor)
+	}
+
+
+	pub fn size_range(&self, duration: Duration) -> std::ops::Range<usize> {
+		let bytes::Mb(expected_size) = self.expected_size(duration);
 
 		std::ops::Range {
 			start: bytes::Mb(expected_size - self.size_tolerance).into(),
@@ -69,6 +76,8 @@ where
 				duration_tolerance : config.slizzy.duration_tolerance,
 				size_factor        : config.slizzy.size_factor,
 				size_tolerance     : config.slizzy.size_tolerance,
+				min_bitrate        : config.slizzy.min_bitrate,
+				delete_invalid     : config.slizzy.delete_invalid,
 				id_cleaner         : config.slizzy.id_clean,
 				search             : config.zippyshare.search,
 				sim_threshold      : config.zippyshare.sim_threshold,
@@ -93,6 +102,9 @@ struct Slizzy {
 	duration_tolerance: u16,
 	size_factor: f32,
 	size_tolerance: f32,
+	min_bitrate: u16,
+	#[serde(default)]
+	delete_invalid: bool,
 	id_clean: IdCleaner,
 }
 
@@ -7,17 +7,21 @@ use std::{
 
 use thiserror::Error;
 
+use serde::Serialize;
+
 use futures::stream::StreamExt;
 
 use crate::{
 	report_wrapped,
 	track::{Track, Duration},
+	output::Output,
 	net::{http, url::Url},
 	sim::{self, Sim},
+	rank::{self, Score},
 	web::scraping,
-	util,
+	util::{self, bytes, mp3},
 };
-use super::super::{item, websearch};
+use super::super::{item, tracksource, websearch};
 
 
 #[derive(Debug, Error)]
@@ -58,10 +62,14 @@ pub enum ItemError {
 
 	#[error("download error: {0}")]
 	Download(http::downloader::Error),
+
+	#[error("invalid download: {0}")]
+	Invalid(tracksource::Mismatch),
 }
 
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Filter {
 	Id(Sim),
 	Blacklist(Box<[Box<str>]>), // array of matched patterns
@@ -70,25 +78,94 @@ pub enum Filter {
 }
 
 
-#[derive(Debug)]
+impl std::fmt::Display for Filter {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Filter::Id(sim) => write!(
+				f,
+				"id mismatch: {}% similarity below threshold",
+				sim.value()
+			),
+			Filter::Blacklist(items) => write!(f, "id blacklisted by regexes: {:?}", items),
+			Filter::Duration(duration) => write!(f, "duration mismatch: {} out of range", duration),
+			Filter::Size(size) => write!(f, "file size mismatch: {} out of range", size),
+		}
+	}
+}
+
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ItemStatus {
-	Error(ItemError),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		ItemError
+	),
 	Expired,
 	Filtered(Filter),
+	Candidate(Score),
+	Outranked(Score),
 	Downloading(util::io::Progress),
-	Done
+	/// The file was downloaded but doesn't match what was expected.
+	Flagged(tracksource::Mismatch),
+	/// The inspected file metadata, if available.
+	Done(Option<mp3::Metadata>),
+}
+
+
+impl std::fmt::Display for ItemStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ItemStatus::Error(error) => write!(f, "error: {}", error),
+			ItemStatus::Expired => f.write_str("file expired"),
+			ItemStatus::Filtered(filter) => write!(f, "{}", filter),
+			ItemStatus::Candidate(score) => write!(f, "candidate: {} score", score),
+			ItemStatus::Outranked(score) => write!(f, "outranked: {} score", score),
+			ItemStatus::Downloading(progress) => match progress.percentage() {
+				Some(percentage) => write!(f, "downloading: {:.0}%", percentage * 100.0),
+				None => write!(f, "downloading: {}", bytes::Mb::from(progress.completed)),
+			},
+			ItemStatus::Flagged(mismatch) => write!(f, "done, but {}", mismatch),
+			ItemStatus::Done(Some(metadata)) => write!(
+				f,
+				"done! {} at {} kbps",
+				metadata.duration,
+				metadata.bitrate
+			),
+			ItemStatus::Done(None) => f.write_str("done!"),
+		}
+	}
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Status<WSError: std::error::Error> {
 	Fetching,
 	NoEntries,
-	Error(Error<WSError>),
+	Error(
+		#[serde(serialize_with = "crate::util::serialize::display")]
+		Error<WSError>
+	),
 	Done,
 }
 
 
+impl<WSError> std::fmt::Display for Status<WSError>
+where
+	WSError: std::error::Error,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Status::Fetching => f.write_str("fetching"),
+			Status::NoEntries => f.write_str("no entries found"),
+			Status::Error(error) => write!(f, "error: {}", error),
+			Status::Done => f.write_str("done"),
+		}
+	}
+}
+
+
 pub struct Params<WS: websearch::Module> {
 	pub progress: Box<
 		dyn item::progress::Progress<
@@ -99,6 +176,9 @@ pub struct Params<WS: websearch::Module> {
 		> + Send
 	>,
 	pub websearch: WS,
+	pub ranking: rank::Participant,
+	pub output: Output,
+	pub downloads: tracksource::Downloads,
 }
 
 
@@ -109,6 +189,9 @@ where
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		f.debug_struct("Params")
 			.field("websearch", &self.websearch)
+			.field("ranking", &self.ranking)
+			.field("output", &self.output)
+			.field("downloads", &self.downloads)
 			.finish()
 	}
 }
@@ -180,11 +263,20 @@ async fn filter_entry<WSConfig>(
 	config: &super::Config<WSConfig>,
 	track: &Track,
 	metadata: scraper::Metadata,
-) -> Result<Option<Filter>, ItemError> {
+) -> Result<Result<Score, Filter>, ItemError> {
 	let id = metadata.id
 		.map_err(ItemError::Scraping)?;
 
+	let mut metrics = rank::Metrics {
+		sim: None,
+		duration: None,
+		quality: None,
+		hints: 0,
+	};
+
 	if let Some(id) = id {
+		metrics.hints = track.metadata.hints(&id);
+
 		let id = config.id_cleaner.clean(&id);
 
 		let similarity = sim::str(
@@ -194,7 +286,7 @@ async fn filter_entry<WSConfig>(
 
 		if similarity < config.sim_threshold {
 			return Ok(
-				Some(
+				Err(
 					Filter::Id(similarity)
 				)
 			);
@@ -209,11 +301,13 @@ async fn filter_entry<WSConfig>(
 				.collect();
 
 			return Ok(
-				Some(
+				Err(
 					Filter::Blacklist(patterns)
 				)
 			)
 		}
+
+		metrics.sim = Some(similarity);
 	}
 
 	if let Some(duration) = track.duration {
@@ -224,7 +318,7 @@ async fn filter_entry<WSConfig>(
 
 		if !size_range.contains(&size) {
 			return Ok(
-				Some(
+				Err(
 					Filter::Size(size)
 				)
 			);
@@ -239,23 +333,41 @@ async fn filter_entry<WSConfig>(
 
 		if !duration_range.contains(&preview_duration) {
 			return Ok(
-				Some(
+				Err(
 					Filter::Duration(preview_duration)
 				)
 			);
 		}
+
+		metrics.duration = Some(
+			rank::closeness(
+				preview_duration.as_seconds() as f32,
+				duration.as_seconds() as f32,
+				config.duration_tolerance as f32,
+			)
+		);
+
+		metrics.quality = Some(
+			rank::closeness(
+				bytes::Mb::from(size).0,
+				config.expected_size(duration).0,
+				config.size_tolerance,
+			)
+		);
 	}
 
-	Ok(None)
+	Ok(
+		Ok(metrics.into())
+	)
 }
 
 
-async fn handle_item<WSConfig>(
+async fn rank_item<WSConfig>(
 	config: &super::Config<WSConfig>,
 	track: &Track,
 	url: Url,
 	status: impl Fn(&ItemStatus),
-) -> Result<(), ItemError> {
+) -> Result<Option<(Url, Score)>, ItemError> {
 	let info = fetch_info(&url)
 		.await
 		.map_err(
@@ -271,7 +383,7 @@ async fn handle_item<WSConfig>(
 	let (download_url, metadata) = match info {
 		scraper::Data::Expired => {
 			status(&ItemStatus::Expired);
-			return Ok(())
+			return Ok(None)
 		},
 		scraper::Data::Available { download, metadata } => (
 			download
@@ -298,28 +410,53 @@ async fn handle_item<WSConfig>(
 			)
 		)?;
 
-	if let Some(filter) = filter {
-		log::debug!("zippy filtered entry: {:?}", filter);
+	match filter {
+		Err(filter) => {
+			log::debug!("zippy filtered entry: {:?}", filter);
 
-		status(
-			&ItemStatus::Filtered(filter)
-		);
+			status(
+				&ItemStatus::Filtered(filter)
+			);
 
-		return Ok(())
+			Ok(None)
+		},
+
+		Ok(score) => {
+			status(
+				&ItemStatus::Candidate(score)
+			);
+
+			Ok(
+				Some((download_url, score))
+			)
+		},
 	}
+}
+
 
+async fn download_item<WSConfig>(
+	config: &super::Config<WSConfig>,
+	track: &Track,
+	download_url: Url,
+	output: &Output,
+	status: impl Fn(&ItemStatus),
+) -> Result<tracksource::Download, ItemError> {
 	let default_path = Path::new(
 		track.id().as_ref()
 	);
 
-	http::Downloader
+	let path = http::Downloader
 		::new()
 		.reporter(
 			|&progress| status(
 				&ItemStatus::Downloading(progress)
 			)
 		)
-		.download_file(&download_url, default_path)
+		.download_file(
+			&download_url,
+			default_path,
+			|stem, extension| output.path(track.id(), stem, extension)
+		)
 		.await
 		.map_err(
 			|error| report_wrapped!(
@@ -331,9 +468,61 @@ async fn handle_item<WSConfig>(
 			)
 		)?;
 
-	status(&ItemStatus::Done);
+	let inspection = tracksource::inspect(
+		&path,
+		track.duration.map(
+			|duration| config.duration_range(duration)
+		),
+		config.min_bitrate,
+	);
+
+	match inspection {
+		Err(mismatch) if config.delete_invalid => {
+			if let Err(error) = std::fs::remove_file(&path) {
+				log::warn!("failed to delete {:#?}: {}", path, error);
+			}
+
+			Err(
+				report_wrapped!(
+					ItemStatus::Error(
+						ItemError::Invalid(mismatch)
+					),
+					status,
+					ItemStatus::Error(error) => error
+				)
+			)
+		},
+
+		Err(mismatch) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Flagged(mismatch));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Zippy,
+					url: download_url.to_string().into(),
+					path,
+					metadata: None,
+					flagged: true,
+				}
+			)
+		},
 
-	Ok(())
+		Ok(metadata) => {
+			tracksource::tag(&path, track);
+			status(&ItemStatus::Done(metadata));
+
+			Ok(
+				tracksource::Download {
+					source: tracksource::TrackSources::Zippy,
+					url: download_url.to_string().into(),
+					path,
+					metadata,
+					flagged: false,
+				}
+			)
+		},
+	}
 }
 
 
@@ -346,6 +535,7 @@ where
 	WS: websearch::Module,
 {
 	let progress = params.progress.as_ref();
+	let output = &params.output;
 
 	progress.status(&Status::Fetching);
 
@@ -386,26 +576,68 @@ where
 
 				progress.item(id, url.as_ref());
 
-				let progress = &progress;
+				async move {
+					let result = rank_item(
+						&module.config,
+						track,
+						url,
+						move |status| progress.item_status(id, status)
+					);
 
-				handle_item(
-					&module.config,
-					track,
-					url,
-					move |status| progress.item_status(id, status)
-				)
+					(id, result.await)
+				}
 			}
 		)
 		.collect();
 
-	let errors: Vec<ItemError> = items
+	let mut errors: Vec<ItemError> = Vec::new();
+	let mut candidates = Vec::new();
+
+	for (id, result) in items.collect::<Vec<_>>().await {
+		match result {
+			Ok(Some((url, score))) => candidates.push((id, url, score)),
+			Ok(None) => (),
+			Err(error) => errors.push(error),
+		}
+	}
+
+	let verdicts = params.ranking
+		.rank(
+			candidates
+				.iter()
+				.map(|(_, _, score)| *score)
+		)
+		.await;
+
+	let downloads: futures::stream::FuturesUnordered<_> = candidates
+		.into_iter()
+		.zip(verdicts.iter())
 		.filter_map(
-			|result| async move {
-				result.err()
+			|((id, url, score), verdict)| match verdict {
+				rank::Verdict::Selected => Some(
+					download_item(
+						&module.config,
+						track,
+						url,
+						output,
+						move |status| progress.item_status(id, status)
+					)
+				),
+
+				rank::Verdict::Outranked => {
+					progress.item_status(id, &ItemStatus::Outranked(score));
+					None
+				},
 			}
 		)
-		.collect()
-		.await;
+		.collect();
+
+	for result in downloads.collect::<Vec<_>>().await {
+		match result {
+			Ok(download) => params.downloads.push(download),
+			Err(error) => errors.push(error),
+		}
+	}
 
 	if errors.is_empty() {
 		progress.finish(&Status::Done);
@@ -1,6 +1,10 @@
 use std::error::Error;
 
-use crate::util;
+use crate::{
+	modules::tracksource,
+	rank::Score,
+	util::{self, mp3},
+};
 use super::{BackendError, BackendStatus, BackendItemStatus};
 
 
@@ -49,8 +53,11 @@ pub enum ItemStatus {
 	Error(Box<str>),
 	Expired,
 	Filtered(super::Filter),
+	Candidate(Score),
+	Outranked(Score),
 	Downloading(util::io::Progress),
-	Done
+	Flagged(tracksource::Mismatch),
+	Done(Option<mp3::Metadata>),
 }
 
 
@@ -71,8 +78,11 @@ impl From<&BackendItemStatus> for ItemStatus {
 			BackendItemStatus::Filtered(filter) => ItemStatus::Filtered(
 				filter.clone() // unfortunately, we have to clone here.
 			),
+			BackendItemStatus::Candidate(score) => ItemStatus::Candidate(*score),
+			BackendItemStatus::Outranked(score) => ItemStatus::Outranked(*score),
 			BackendItemStatus::Downloading(progress) => ItemStatus::Downloading(*progress),
-			BackendItemStatus::Done => ItemStatus::Done,
+			BackendItemStatus::Flagged(mismatch) => ItemStatus::Flagged(*mismatch),
+			BackendItemStatus::Done(metadata) => ItemStatus::Done(*metadata),
 		}
 	}
 }
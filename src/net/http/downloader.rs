@@ -1,10 +1,11 @@
 use std::io;
-use std::path::Path;
-use std::fs::File;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::fs::{self, File, OpenOptions};
 
 use thiserror::Error;
 
-use futures::AsyncReadExt;
+use futures::{AsyncReadExt, lock::Mutex};
 
 use crate::{
 	net::{http, url::Url},
@@ -15,6 +16,7 @@ use crate::{
 #[derive(Debug, Clone, Copy)]
 pub struct Downloader<R> where R: FnMut(&util::io::Progress) {
 	buffer_size: usize,
+	attempts: usize,
 	reporter: Option<R>,
 }
 
@@ -33,6 +35,7 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 	pub fn new() -> Self {
 		Self {
 			buffer_size: 8 * 1024,
+			attempts: 5,
 			reporter: None,
 		}
 	}
@@ -52,6 +55,15 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 	}
 
 
+	/// How many times to attempt a file download. Interrupted downloads are resumed.
+	pub fn attempts(mut self, attempts: usize) -> Self {
+		log::debug!("download attempts: {}", attempts);
+
+		self.attempts = attempts;
+		self
+	}
+
+
 	fn report(&mut self, progress: &util::io::Progress) {
 		self.reporter
 			.as_mut()
@@ -61,7 +73,14 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 	}
 
 
-	fn get_target_file(response: &http::Response, default: &Path) -> io::Result<File> {
+	fn get_target_file<T>(
+		response: &http::Response,
+		default: &Path,
+		target: T
+	) -> io::Result<(File, PathBuf)>
+	where
+		T: FnOnce(&Path, Option<&OsStr>) -> PathBuf
+	{
 		log::debug!("default filename: {:#?}", default);
 
 		let filename = response.filename();
@@ -93,30 +112,34 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 				}
 			);
 
-		util::io::fs::file::create_unique(stem, extension)
+		let path = target(Path::new(stem), extension);
+
+		util::io::fs::file::create_unique_at(&path, extension)
 	}
 
 
 	async fn download_body<W>(
 		&mut self,
 		response: &mut http::Response,
-		mut out: W
+		mut out: W,
+		progress: &mut util::io::Progress,
 	) -> Result<(), Error>
 	where
 		W: io::Write
 	{
-		let mut progress = util::io::Progress {
-			total: response.content_length(),
-			completed: 0,
-		};
-
 		let mut buffer: Box<[u8]> = vec![0; self.buffer_size].into_boxed_slice();
 
 		loop {
 			let bytes_read = response
 				.read(&mut buffer)
 				.await
-				.map_err(Error::Io)?;
+				.map_err( // Failing to read the body is a connection error, and may be retried.
+					|error| Error::Http(
+						http::Error::Response(
+							anyhow::Error::new(error).into()
+						)
+					)
+				)?;
 
 			if bytes_read == 0 {
 				return match progress.total {
@@ -138,7 +161,7 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 
 			progress.completed += bytes_read;
 
-			self.report(&progress);
+			self.report(progress);
 		}
 	}
 
@@ -164,17 +187,30 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 
 		log::trace!("download response: {:#?}", response);
 
+		let mut progress = util::io::Progress {
+			total: response.content_length(),
+			completed: 0,
+		};
+
 		self
-			.download_body(&mut response, out)
+			.download_body(&mut response, out, &mut progress)
 			.await
 	}
 
 
-	pub async fn download_file(
+	/// Download to a file. The target closure maps the file stem, which is the filename
+	/// suggested by the server or the default path, and the extension, if any, to the final
+	/// path. The data is written to a `.part` file, and interrupted downloads are resumed
+	/// with range requests. Returns the path of the downloaded file.
+	pub async fn download_file<T>(
 		&mut self,
 		url: &Url,
-		default_path: &Path
-	) -> Result<(), Error> {
+		default_path: &Path,
+		target: T,
+	) -> Result<PathBuf, Error>
+	where
+		T: FnOnce(&Path, Option<&OsStr>) -> PathBuf
+	{
 		let log_failed = || log::warn!("download failed: {}", url);
 
 		// Report 0 before dispatching the request:
@@ -182,7 +218,7 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 			&util::io::Progress::default()
 		);
 
-		let mut response = http::Request
+		let response = http::Request
 			::new(url)
 			.send()
 			.await
@@ -198,8 +234,9 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 
 		log::trace!("download response: {:#?}", response);
 
-		let file = Downloader::<R>
-			::get_target_file(&response, default_path)
+		// Reserve the target path, so that concurrent downloads won't pick the same name.
+		let (_, path) = Downloader::<R>
+			::get_target_file(&response, default_path, target)
 			.map_err(
 				|error| {
 					log_failed();
@@ -207,16 +244,195 @@ impl<R> Downloader<R> where R: FnMut(&util::io::Progress) {
 				}
 			)?;
 
-		log::trace!("download file: {:#?}", file);
+		let mut part_path = path
+			.as_os_str()
+			.to_owned();
+		part_path.push(".part");
+		let part_path = PathBuf::from(part_path);
 
 		let result = self
-			.download_body(&mut response, file)
-			.await;
+			.download_part(url, response, &part_path)
+			.await
+			.and_then(
+				|()| fs::rename(&part_path, &path).map_err(Error::Io)
+			);
+
+		match result {
+			Ok(()) => Ok(path),
+			Err(error) => {
+				log_failed();
+
+				// Don't leave garbage behind.
+				let _ = fs::remove_file(&part_path);
+				let _ = fs::remove_file(&path);
+
+				Err(error)
+			}
+		}
+	}
+
+
+	async fn download_part(
+		&mut self,
+		url: &Url,
+		response: http::Response,
+		part_path: &Path,
+	) -> Result<(), Error> {
+		let file = OpenOptions
+			::new()
+			.create(true)
+			.append(true)
+			.open(part_path)
+			.map_err(Error::Io)?;
+
+		// A stale part file may be left from a previous run.
+		file
+			.set_len(0)
+			.map_err(Error::Io)?;
+
+		log::trace!("download part file: {:#?}", file);
+
+		let attempts = self.attempts;
+
+		let progress = util::io::Progress {
+			total: response.content_length(),
+			completed: 0,
+		};
+
+		let state = Mutex::new(
+			Resume {
+				downloader: self,
+				file,
+				progress,
+				response: Some(response),
+			}
+		);
+
+		let state = &state;
+
+		util::future
+			::retry(
+				|| async move {
+					state
+						.lock()
+						.await
+						.attempt(url)
+						.await
+				},
+				|error| matches!(error, Error::Http(_)),
+				|attempt, error| {
+					log::warn!("download attempt {} failed: {}", attempt, error);
+					error
+				},
+				Some(attempts),
+			)
+			.await?;
+
+		let state = state.lock().await;
+
+		let size = state.file
+			.metadata()
+			.map_err(Error::Io)?
+			.len();
+
+		match state.progress.total {
+			Some(total) if size != total as u64 => Err(
+				Error::Http(
+					http::Error::Response(
+						anyhow::anyhow!("downloaded size {} doesn't match {}", size, total).into()
+					)
+				)
+			),
+			_ => Ok(()),
+		}
+	}
+}
+
+
+/// The state of a resumable file download.
+struct Resume<'a, R> where R: FnMut(&util::io::Progress) {
+	downloader: &'a mut Downloader<R>,
+	file: File,
+	progress: util::io::Progress,
+	/// The response to continue from, if any.
+	response: Option<http::Response>,
+}
+
+
+impl<'a, R> Resume<'a, R> where R: FnMut(&util::io::Progress) {
+	async fn attempt(&mut self, url: &Url) -> Result<(), Error> {
+		let mut response = match self.response.take() {
+			Some(response) => response,
+			None => self.resume(url).await?,
+		};
+
+		self.downloader
+			.download_body(&mut response, &mut self.file, &mut self.progress)
+			.await
+	}
+
+
+	async fn resume(&mut self, url: &Url) -> Result<http::Response, Error> {
+		log::debug!("resuming download from byte {}: {}", self.progress.completed, url);
+
+		let range = http::headers::Value
+			::from_str(
+				&format!("bytes={}-", self.progress.completed)
+			)
+			.expect("invalid range header");
+
+		let response = http::Request
+			::new(url)
+			.append_header(http::headers::RANGE, range)
+			.send()
+			.await
+			.map_err(
+				|error| Error::Http(
+					error.into()
+				)
+			)?;
+
+		log::trace!("resumed download response: {:#?}", response);
+
+		if response.status() == http::response::StatusCode::PARTIAL_CONTENT {
+			let start = response.range_start();
+
+			if start != Some(self.progress.completed as u64) {
+				log::debug!("server returned range starting at {:?}, restarting download", start);
+
+				self.file
+					.set_len(0)
+					.map_err(Error::Io)?;
+
+				self.progress.completed = 0;
+
+				self.downloader.report(&self.progress);
+
+				// The body is not the requested range, so retry from the start.
+				return Err(
+					Error::Http(
+						http::Error::Response(
+							anyhow::anyhow!("unexpected content range {:?}", start).into()
+						)
+					)
+				);
+			}
+		}
+		else {
+			log::debug!("server doesn't support range requests, restarting download");
+
+			self.file
+				.set_len(0)
+				.map_err(Error::Io)?;
+
+			self.progress = util::io::Progress {
+				total: response.content_length(),
+				completed: 0,
+			};
 
-		if result.is_err() {
-			log_failed()
+			self.downloader.report(&self.progress);
 		}
 
-		result
+		Ok(response)
 	}
 }
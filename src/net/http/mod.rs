@@ -13,9 +13,12 @@ pub use downloader::Downloader;
 
 pub mod headers {
 	pub use isahc::http::header::{
+		HeaderName as Name,
 		HeaderValue as Value,
+		AUTHORIZATION,
 		CONTENT_DISPOSITION,
 		CONTENT_LENGTH,
+		CONTENT_RANGE,
 		CONTENT_TYPE,
 		RANGE,
 		USER_AGENT
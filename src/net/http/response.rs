@@ -67,6 +67,20 @@ impl Response {
 	}
 
 
+	/// The first byte of a partial content response, from the Content-Range header.
+	pub fn range_start(&self) -> Option<u64> {
+		let content_range = self
+			.headers()
+			.get(headers::CONTENT_RANGE)?;
+
+		log::debug!("Content-Range header: {:#?}", content_range);
+
+		parse_range_start(
+			content_range.to_str().ok()?
+		)
+	}
+
+
 	pub fn filename(&self) -> Option<Cow<Path>> {
 		lazy_static! {
 			static ref FILENAME_REGEX: Regex = Regex
@@ -190,3 +204,31 @@ impl futures::AsyncRead for Response {
 			.poll_read(cx, buf)
 	}
 }
+
+
+/// Parse the first byte of a `bytes <start>-<end>/<total>` range.
+fn parse_range_start(content_range: &str) -> Option<u64> {
+	content_range
+		.trim()
+		.strip_prefix("bytes ")?
+		.split('-')
+		.next()?
+		.trim()
+		.parse()
+		.ok()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_range_start() {
+		assert_eq!(parse_range_start("bytes 1024-2047/2048"), Some(1024));
+		assert_eq!(parse_range_start("bytes 0-99/*"), Some(0));
+		assert_eq!(parse_range_start("bytes */2048"), None);
+		assert_eq!(parse_range_start("items 1-2/3"), None);
+	}
+}
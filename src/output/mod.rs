@@ -0,0 +1,106 @@
+pub mod sidecar;
+mod template;
+
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::track::Id;
+pub use template::Template;
+
+
+/// Where and how downloaded files are written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Output {
+	pub dir: PathBuf,
+	pub template: Option<Template>,
+	/// Write the track details to a JSON file along each download.
+	pub sidecar: bool,
+}
+
+
+impl Output {
+	/// Build the path for a downloaded file. When there is no filename template, the given
+	/// file stem is used, which usually is the name suggested by the server.
+	pub fn path(&self, id: &Id, stem: &Path, extension: Option<&OsStr>) -> PathBuf {
+		let path = match &self.template {
+			Some(template) => self.dir.join(
+				template.render(
+					id,
+					extension.and_then(OsStr::to_str)
+				)
+			),
+
+			None => {
+				let mut path = self.dir.join(stem);
+
+				if let Some(extension) = extension {
+					let mut filename = path
+						.file_name()
+						.expect("missing filename from stem")
+						.to_owned();
+
+					filename.push(".");
+					filename.push(extension);
+
+					path.set_file_name(filename);
+				}
+
+				path
+			}
+		};
+
+		log::debug!("output path: {:#?}", path);
+
+		path
+	}
+}
+
+
+impl Default for Output {
+	fn default() -> Self {
+		Self {
+			dir: PathBuf::from("."),
+			template: None,
+			sidecar: false,
+		}
+	}
+}
+
+
+impl<'de> Deserialize<'de> for Output {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let config = ConfigFile::deserialize(deserializer)?;
+
+		Ok(
+			Output {
+				dir: config.slizzy.output_dir.unwrap_or_else(|| PathBuf::from(".")),
+				template: config.slizzy.filename_template,
+				sidecar: config.slizzy.sidecar,
+			}
+		)
+	}
+}
+
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+	slizzy: Slizzy,
+}
+
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+struct Slizzy {
+	#[serde(default)]
+	output_dir: Option<PathBuf>,
+
+	#[serde(default)]
+	filename_template: Option<Template>,
+
+	#[serde(default)]
+	sidecar: bool,
+}
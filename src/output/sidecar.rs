@@ -0,0 +1,107 @@
+use std::{
+	fs,
+	io,
+	path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::track::{Duration, Track};
+
+
+/// The track details written along a downloaded file.
+#[derive(Debug, Serialize)]
+struct Sidecar<'a> {
+	id: &'a str,
+	duration: Option<Duration>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	label: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	release_date: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	bpm: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	key: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	genre: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	catalog_number: Option<&'a str>,
+}
+
+
+impl<'a> From<&'a Track> for Sidecar<'a> {
+	fn from(track: &'a Track) -> Self {
+		Self {
+			id: track.id().as_ref(),
+			duration: track.duration,
+			label: track.metadata.label.as_deref(),
+			release_date: track.metadata.release_date.as_deref(),
+			bpm: track.metadata.bpm,
+			key: track.metadata.key.as_deref(),
+			genre: track.metadata.genre.as_deref(),
+			catalog_number: track.metadata.catalog_number.as_deref(),
+		}
+	}
+}
+
+
+/// The sidecar path of a downloaded file: the same path, with a json extension.
+pub fn path(file: &Path) -> PathBuf {
+	file.with_extension("json")
+}
+
+
+/// Write the track details as JSON along the downloaded file, returning the sidecar path.
+pub fn write(file: &Path, track: &Track) -> io::Result<PathBuf> {
+	let path = path(file);
+
+	let data = serde_json::to_vec_pretty(&Sidecar::from(track))?;
+
+	fs::write(&path, data)?;
+
+	Ok(path)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_write() {
+		let dir = std::env::temp_dir().join(
+			format!("slizzy-sidecar-test-{}", std::process::id())
+		);
+		fs::create_dir_all(&dir).expect("failed to create test dir");
+
+		let mut track = Track
+			::new("Somne - Kinetic Rampage (Extended Mix)")
+			.expect("invalid track");
+
+		track.duration = Some(Duration::from_seconds(401));
+		track.metadata.bpm = Some(132);
+		track.metadata.catalog_number = Some("SKR026".into());
+
+		let path = write(&dir.join("Somne - Kinetic Rampage.mp3"), &track)
+			.expect("failed to write sidecar");
+
+		assert_eq!(path, dir.join("Somne - Kinetic Rampage.json"));
+
+		let sidecar: serde_json::Value = serde_json
+			::from_slice(&fs::read(&path).expect("failed to read sidecar"))
+			.expect("invalid sidecar");
+
+		assert_eq!(
+			sidecar,
+			serde_json::json!({
+				"id": "Somne - Kinetic Rampage (Extended Mix)",
+				"duration": 401,
+				"bpm": 132,
+				"catalog_number": "SKR026",
+			})
+		);
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
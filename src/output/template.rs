@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+
+use thiserror::Error;
+
+use serde::{Deserialize, Deserializer};
+
+use regex::Regex;
+
+use crate::track::Id;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+	Id,
+	Artists,
+	Name,
+	Mix,
+	Title,
+	Ext,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Segment {
+	Text(Box<str>),
+	Field(Field),
+}
+
+
+/// A filename template, like `{artists} - {name} ({mix}).{ext}`.
+/// The available fields are `id`, `artists`, `name`, `mix`, `title` and `ext`. Fields that
+/// are not available for a track render empty, and so do any parentheses or brackets left
+/// empty by them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Template(Box<[Segment]>);
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ParseError {
+	#[error("unknown field: {0}")]
+	UnknownField(Box<str>),
+
+	#[error("unclosed field")]
+	UnclosedField,
+}
+
+
+impl Template {
+	pub fn render(&self, id: &Id, extension: Option<&str>) -> String {
+		lazy_static! {
+			static ref EMPTY_GROUP: Regex = Regex
+				::new(r"\s*(\(\s*\)|\[\s*\])")
+				.expect("invalid regex");
+		}
+
+		let mut filename = String::with_capacity(id.len() + 8);
+
+		for segment in self.0.iter() {
+			let text = match segment {
+				Segment::Text(text) => text,
+				Segment::Field(Field::Id) => id.as_ref(),
+				Segment::Field(Field::Artists) => id.artists(),
+				Segment::Field(Field::Name) => id.name(),
+				Segment::Field(Field::Mix) => id.mix().unwrap_or(""),
+				Segment::Field(Field::Title) => id.title(),
+				Segment::Field(Field::Ext) => extension.unwrap_or(""),
+			};
+
+			filename.push_str(text);
+		}
+
+		let filename = EMPTY_GROUP.replace_all(&filename, "");
+
+		filename
+			.trim()
+			.trim_end_matches('.') // When there is no extension.
+			.replace('/', "-") // Ids may contain slashes, like in "AC/DC".
+	}
+}
+
+
+impl FromStr for Template {
+	type Err = ParseError;
+
+	fn from_str(template: &str) -> Result<Self, Self::Err> {
+		let mut segments = Vec::new();
+
+		let mut rest = template;
+
+		while let Some(start) = rest.find('{') {
+			if start > 0 {
+				segments.push(
+					Segment::Text(rest[..start].into())
+				);
+			}
+
+			rest = &rest[start + 1 ..];
+
+			let end = rest
+				.find('}')
+				.ok_or(ParseError::UnclosedField)?;
+
+			let field = match &rest[..end] {
+				"id" => Field::Id,
+				"artists" => Field::Artists,
+				"name" => Field::Name,
+				"mix" => Field::Mix,
+				"title" => Field::Title,
+				"ext" => Field::Ext,
+				other => return Err(
+					ParseError::UnknownField(other.into())
+				),
+			};
+
+			segments.push(
+				Segment::Field(field)
+			);
+
+			rest = &rest[end + 1 ..];
+		}
+
+		if !rest.is_empty() {
+			segments.push(
+				Segment::Text(rest.into())
+			);
+		}
+
+		Ok(
+			Template(
+				segments.into_boxed_slice()
+			)
+		)
+	}
+}
+
+
+impl<'de> Deserialize<'de> for Template {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let template = Box::<str>::deserialize(deserializer)?;
+
+		template
+			.parse()
+			.map_err(serde::de::Error::custom)
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn render(template: &str, id: &str, extension: Option<&str>) -> String {
+		let template: Template = template
+			.parse()
+			.expect("invalid template");
+
+		let id: Id = id
+			.parse()
+			.expect("invalid id");
+
+		template.render(&id, extension)
+	}
+
+
+	#[test]
+	fn test_render() {
+		let template = "{artists} - {name} ({mix}).{ext}";
+
+		assert_eq!(
+			render(template, "Mind Against & Somne - Vertere (Extended Mix)", Some("mp3")),
+			"Mind Against & Somne - Vertere (Extended Mix).mp3"
+		);
+
+		assert_eq!(
+			render(template, "Mind Against & Somne - Vertere", Some("mp3")),
+			"Mind Against & Somne - Vertere.mp3"
+		);
+
+		assert_eq!(
+			render(template, "AC/DC - Thunderstruck", None),
+			"AC-DC - Thunderstruck"
+		);
+
+		assert_eq!(
+			render("[{mix}] {title}.{ext}", "Somne - Vertere", Some("mp3")),
+			"Vertere.mp3"
+		);
+	}
+
+
+	#[test]
+	fn test_invalid_template() {
+		assert_eq!(
+			"{artists} - {album}".parse::<Template>(),
+			Err(ParseError::UnknownField("album".into()))
+		);
+
+		assert_eq!(
+			"{artists} - {name".parse::<Template>(),
+			Err(ParseError::UnclosedField)
+		);
+	}
+}
@@ -0,0 +1,142 @@
+//! Tracks given as the url of a metasource page, which skips the web search.
+
+use thiserror::Error;
+
+use crate::{
+	net::{http, url::Url},
+	track::{Track, ParseIdError},
+	modules::{beatport, bandcamp},
+	web::scraping,
+};
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("unsupported page: {0}, only beatport and bandcamp urls are accepted")]
+	UnsupportedHost(Url),
+
+	#[error("http error: {0}")]
+	Http(http::Error),
+
+	#[error("title not found: {0}")]
+	TitleNotFound(scraping::Error),
+
+	#[error("duration not found: {0}")]
+	DurationNotFound(scraping::Error),
+
+	#[error("album pages are not supported, use the url of a track")]
+	Album,
+
+	#[error("invalid track id: {0}")]
+	Id(ParseIdError),
+}
+
+
+/// Fetch the track id, duration and metadata from a beatport or bandcamp track page.
+pub async fn fetch(url: &Url) -> Result<Track, Error> {
+	let host = url
+		.host()
+		.unwrap_or_default();
+
+	let is_host = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+	if is_host("beatport.com") {
+		let data = beatport
+			::scrap(url)
+			.await
+			.map_err(Error::Http)?;
+
+		from_beatport(data)
+	}
+	else if is_host("bandcamp.com") {
+		let data = bandcamp
+			::scrap(url)
+			.await
+			.map_err(Error::Http)?;
+
+		from_bandcamp(data)
+	}
+	else {
+		Err(
+			Error::UnsupportedHost(url.clone())
+		)
+	}
+}
+
+
+fn from_beatport(data: beatport::scraper::Data) -> Result<Track, Error> {
+	let id = data.track_id.map_err(Error::TitleNotFound)?;
+	let duration = data.duration.map_err(Error::DurationNotFound)?;
+
+	let mut track = Track
+		::new(id.as_ref())
+		.map_err(Error::Id)?;
+
+	track.duration = Some(duration);
+	track.metadata = data.metadata.into();
+
+	Ok(track)
+}
+
+
+fn from_bandcamp(data: bandcamp::scraper::Data) -> Result<Track, Error> {
+	if data.album.is_some() {
+		return Err(Error::Album);
+	}
+
+	let title = data.track.map_err(Error::TitleNotFound)?;
+	let duration = data.duration.map_err(Error::DurationNotFound)?;
+
+	// The title can be the track id or just the name, depending on the label.
+	let id = match data.artist {
+		Some(artist) if !title.contains(" - ") => format!("{} - {}", artist, title),
+		_ => title.into(),
+	};
+
+	let mut track = Track
+		::new(id.as_str())
+		.map_err(Error::Id)?;
+
+	track.duration = Some(duration);
+	track.metadata = data.metadata;
+
+	Ok(track)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::track::{Duration, Metadata};
+
+	#[test]
+	fn test_from_bandcamp() {
+		let data = |track: &str, album| bandcamp::scraper::Data {
+			track: Ok(track.into()),
+			artist: Some("SRAMAANA".into()),
+			duration: Ok(Duration::new(5, 20)),
+			metadata: Metadata::default(),
+			album,
+		};
+
+		let track = from_bandcamp(data("Kinetic Rampage", None))
+			.expect("failed to build track");
+
+		assert_eq!(track.id().as_ref(), "SRAMAANA - Kinetic Rampage");
+		assert_eq!(track.duration, Some(Duration::new(5, 20)));
+
+		let track = from_bandcamp(data("Somne - Kinetic Rampage", None))
+			.expect("failed to build track");
+
+		assert_eq!(track.id().as_ref(), "Somne - Kinetic Rampage");
+
+		assert!(
+			matches!(
+				from_bandcamp(data("Dystopian Odyssey EP", Some(Box::new([])))),
+				Err(Error::Album)
+			)
+		);
+	}
+}
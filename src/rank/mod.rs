@@ -0,0 +1,250 @@
+mod config;
+
+use std::{
+	cell::RefCell,
+	rc::Rc,
+};
+
+use futures::{
+	channel::oneshot,
+	future,
+};
+
+use serde::Serialize;
+
+use crate::sim::Sim;
+pub use config::Config;
+
+#[cfg(test)]
+mod tests;
+
+
+// How much each metric contributes to the final score.
+const SIM_WEIGHT: f32 = 0.5;
+const DURATION_WEIGHT: f32 = 0.3;
+const QUALITY_WEIGHT: f32 = 0.2;
+
+// Added for each release detail hinting at a match, on top of the weighted metrics.
+const HINT_BONUS: f32 = 0.05;
+
+// Metrics that a source couldn't measure are considered to be halfway.
+const UNKNOWN_CLOSENESS: f32 = 0.5;
+
+
+/// The closeness between a value and its target, from 0 (out of tolerance) to 1 (exact).
+pub fn closeness(value: f32, target: f32, tolerance: f32) -> f32 {
+	if tolerance <= 0.0 {
+		return if value == target { 1.0 } else { 0.0 };
+	}
+
+	(1.0 - (value - target).abs() / tolerance).max(0.0)
+}
+
+
+/// What is known about a candidate. The duration and quality closeness should be in the
+/// [0, 1] range, where 1 is the best.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Metrics {
+	pub sim: Option<Sim>,
+	pub duration: Option<f32>,
+	pub quality: Option<f32>,
+	/// How many release details of the track the candidate mentions, see `Metadata::hints`.
+	pub hints: u8,
+}
+
+
+/// A candidate score, from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Score(u8);
+
+
+impl Score {
+	pub fn value(&self) -> u8 {
+		self.0
+	}
+}
+
+
+impl From<Metrics> for Score {
+	fn from(metrics: Metrics) -> Self {
+		let clamp = |closeness: Option<f32>| closeness
+			.unwrap_or(UNKNOWN_CLOSENESS)
+			.clamp(0.0, 1.0);
+
+		let sim = clamp(
+			metrics.sim.map(
+				|sim| sim.value() as f32 / 100.0
+			)
+		);
+
+		let score = sim * SIM_WEIGHT
+			+ clamp(metrics.duration) * DURATION_WEIGHT
+			+ clamp(metrics.quality) * QUALITY_WEIGHT
+			+ metrics.hints as f32 * HINT_BONUS;
+
+		let score = score.min(1.0);
+
+		log::debug!("score for {:?}: {}", metrics, score);
+
+		Score(
+			(score * 100.0).round() as u8
+		)
+	}
+}
+
+
+impl std::fmt::Display for Score {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}%", self.0)
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+	Selected,
+	Outranked,
+}
+
+
+#[derive(Debug)]
+struct State {
+	top: usize,
+	participants: usize,
+	candidates: Vec<(Score, oneshot::Sender<Verdict>)>,
+}
+
+
+impl State {
+	fn submitted(&mut self) {
+		self.participants -= 1;
+
+		if self.participants > 0 {
+			return;
+		}
+
+		let mut candidates = std::mem::take(&mut self.candidates);
+
+		log::debug!("ranking {} candidates", candidates.len());
+
+		// Stable sort, so that ties are resolved first-come-first-served.
+		candidates.sort_by(
+			|(score1, _), (score2, _)| score2.cmp(score1)
+		);
+
+		for (ix, (_, sender)) in candidates.into_iter().enumerate() {
+			let verdict =
+				if ix < self.top {
+					Verdict::Selected
+				}
+				else {
+					Verdict::Outranked
+				};
+
+			// The participant may have given up waiting, which is fine.
+			let _ = sender.send(verdict);
+		}
+	}
+}
+
+
+/// Ranks the candidates of many sources, and selects only the best ones for download.
+/// The verdicts are only given after all participants have submitted their candidates.
+#[derive(Debug, Clone)]
+pub struct Ranker {
+	state: Rc<RefCell<State>>,
+}
+
+
+impl Ranker {
+	pub fn new(config: Config) -> Self {
+		Self {
+			state: Rc::new(
+				RefCell::new(
+					State {
+						top: config.download_count,
+						participants: 0,
+						candidates: Vec::new(),
+					}
+				)
+			)
+		}
+	}
+
+
+	/// Register a new participant. All participants must be registered before any of
+	/// them submits.
+	pub fn participant(&self) -> Participant {
+		self.state
+			.borrow_mut()
+			.participants += 1;
+
+		Participant {
+			state: Some(
+				self.state.clone()
+			),
+		}
+	}
+}
+
+
+/// A handle to submit candidates to a ranker. Dropping a participant without submitting
+/// is equivalent to submitting no candidates.
+#[derive(Debug)]
+pub struct Participant {
+	state: Option<Rc<RefCell<State>>>,
+}
+
+
+impl Participant {
+	/// Submit the candidates' scores, and wait for the verdicts, in the same order.
+	pub async fn rank<I>(mut self, scores: I) -> Box<[Verdict]>
+	where
+		I: IntoIterator<Item = Score>
+	{
+		let state = self.state
+			.take()
+			.expect("participant already submitted");
+
+		let receivers: Vec<_> = {
+			let mut state = state.borrow_mut();
+
+			let receivers = scores
+				.into_iter()
+				.map(
+					|score| {
+						let (sender, receiver) = oneshot::channel();
+
+						state.candidates.push((score, sender));
+
+						receiver
+					}
+				)
+				.collect();
+
+			state.submitted();
+
+			receivers
+		};
+
+		future
+			::join_all(receivers)
+			.await
+			.into_iter()
+			.map(
+				|verdict| verdict.unwrap_or(Verdict::Outranked)
+			)
+			.collect()
+	}
+}
+
+
+impl Drop for Participant {
+	fn drop(&mut self) {
+		if let Some(state) = self.state.take() {
+			state
+				.borrow_mut()
+				.submitted();
+		}
+	}
+}
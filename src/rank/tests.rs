@@ -0,0 +1,88 @@
+use std::convert::TryFrom;
+
+use futures::future;
+
+use super::*;
+
+
+fn score(sim: u8, duration: f32, quality: f32) -> Score {
+	Score::from(
+		Metrics {
+			sim: Some(
+				Sim::try_from(sim).expect("invalid sim")
+			),
+			duration: Some(duration),
+			quality: Some(quality),
+			hints: 0,
+		}
+	)
+}
+
+
+#[test]
+fn test_score() {
+	assert_eq!(score(98, 1.0, 1.0).value(), 99);
+	assert_eq!(score(0, 0.0, 0.0).value(), 0);
+	assert_eq!(score(80, 0.5, 1.0).value(), 75);
+
+	assert_eq!(
+		Score::from(
+			Metrics { sim: None, duration: None, quality: None, hints: 0 }
+		)
+		.value(),
+		50
+	);
+
+	let hinted = |hints| Score::from(
+		Metrics {
+			sim: Sim::try_from(80_u8).ok(),
+			duration: Some(0.5),
+			quality: Some(1.0),
+			hints,
+		}
+	);
+
+	assert_eq!(hinted(2).value(), 85);
+	assert_eq!(hinted(20).value(), 100);
+
+	assert!(score(90, 1.0, 0.5) > score(90, 0.5, 1.0));
+}
+
+
+#[test]
+fn test_closeness() {
+	assert_eq!(closeness(100.0, 100.0, 5.0), 1.0);
+	assert_eq!(closeness(98.0, 100.0, 4.0), 0.5);
+	assert_eq!(closeness(110.0, 100.0, 5.0), 0.0);
+	assert_eq!(closeness(1.0, 1.0, 0.0), 1.0);
+}
+
+
+#[test]
+fn test_ranking() {
+	let ranker = Ranker::new(Config { download_count: 2 });
+
+	let slider = ranker.participant();
+	let zippy = ranker.participant();
+	let music2k = ranker.participant();
+
+	drop(ranker);
+	drop(music2k); // Failed before submitting.
+
+	let (slider, zippy) = futures::executor::block_on(
+		future::join(
+			slider.rank(vec![score(70, 1.0, 1.0), score(90, 1.0, 1.0)]),
+			zippy.rank(vec![score(80, 1.0, 1.0), score(60, 1.0, 1.0)]),
+		)
+	);
+
+	assert_eq!(
+		slider.as_ref(),
+		&[Verdict::Outranked, Verdict::Selected]
+	);
+
+	assert_eq!(
+		zippy.as_ref(),
+		&[Verdict::Selected, Verdict::Outranked]
+	);
+}
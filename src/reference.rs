@@ -0,0 +1,140 @@
+//! A local audio file used as the duration source, instead of the metasources.
+
+use std::{
+	fs::File,
+	io::{self, BufReader},
+	path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{
+	track::{Duration, Track, ParseIdError},
+	util::{id3, mp3, mp4},
+};
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("failed to read reference file: {0}")]
+	Io(#[from] io::Error),
+
+	#[error("unsupported reference file format, expected mp3 or m4a")]
+	UnsupportedFormat,
+
+	#[error("no track id given, and the reference file has no artist and title tags")]
+	MissingTags,
+
+	#[error("invalid track id from the reference tags: {0}")]
+	Id(ParseIdError),
+}
+
+
+/// Fill the track with the reference duration. When no track is given, the id is read
+/// from the artist and title tags.
+pub fn read(path: &Path, track: Option<Track>) -> Result<Track, Error> {
+	let extension = path
+		.extension()
+		.and_then(|extension| extension.to_str())
+		.map(str::to_lowercase);
+
+	let (duration, text) = match extension.as_deref() {
+		Some("mp3") => {
+			let data = std::fs::read(path)?;
+
+			let metadata = mp3::extract_metadata(data.as_slice())?;
+
+			(metadata.duration, id3::read(&data))
+		},
+
+		Some("m4a") | Some("mp4") => {
+			let file = BufReader::new(File::open(path)?);
+
+			let metadata = mp4::extract_metadata(file)?;
+
+			(metadata.duration, None)
+		},
+
+		_ => return Err(Error::UnsupportedFormat),
+	};
+
+	let mut track = match track {
+		Some(track) => track,
+		None => from_tags(text.unwrap_or_default())?,
+	};
+
+	track.duration = Some(duration);
+
+	Ok(track)
+}
+
+
+/// Read the duration and tags without reading the whole file, for indexing many files. The
+/// mp3 duration is estimated from the head of the file.
+pub fn probe(path: &Path) -> Result<(Duration, id3::Text), Error> {
+	let extension = path
+		.extension()
+		.and_then(|extension| extension.to_str())
+		.map(str::to_lowercase);
+
+	let file = File::open(path)?;
+
+	match extension.as_deref() {
+		Some("mp3") => {
+			let length = file.metadata()?.len();
+
+			let head = mp3::read_head(file)?;
+
+			let metadata = mp3::estimate_metadata(&head, length)?;
+
+			Ok((metadata.duration, id3::read(&head).unwrap_or_default()))
+		},
+
+		Some("m4a") | Some("mp4") => {
+			let metadata = mp4::extract_metadata(BufReader::new(file))?;
+
+			Ok((metadata.duration, id3::Text::default()))
+		},
+
+		_ => Err(Error::UnsupportedFormat),
+	}
+}
+
+
+/// The track id from the artist and title tags.
+pub fn from_tags(text: id3::Text) -> Result<Track, Error> {
+	match (text.artist, text.title) {
+		(Some(artist), Some(title)) => Track
+			::new(format!("{} - {}", artist, title).as_str())
+			.map_err(Error::Id),
+
+		_ => Err(Error::MissingTags),
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_tags() {
+		let track = from_tags(
+			id3::Text {
+				artist: Some("Somne".into()),
+				title: Some("Kinetic Rampage (Extended Mix)".into()),
+			}
+		)
+			.expect("failed to build track");
+
+		assert_eq!(track.id().as_ref(), "Somne - Kinetic Rampage (Extended Mix)");
+
+		assert!(
+			matches!(
+				from_tags(id3::Text { artist: None, title: Some("Kinetic Rampage".into()) }),
+				Err(Error::MissingTags)
+			)
+		);
+	}
+}
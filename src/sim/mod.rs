@@ -6,7 +6,7 @@ use thiserror::Error;
 
 use derive_more::Into;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub use self::str::str;
 
@@ -19,6 +19,7 @@ pub use self::str::str;
 	Hash,
 	Into,
 	Deserialize,
+	Serialize,
 )]
 pub struct Sim(u8);
 
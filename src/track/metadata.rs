@@ -0,0 +1,94 @@
+/// Additional track metadata, filled by the metasources when available.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+	pub label: Option<Box<str>>,
+	/// In `YYYY-MM-DD` format.
+	pub release_date: Option<Box<str>>,
+	pub bpm: Option<u16>,
+	pub key: Option<Box<str>>,
+	pub genre: Option<Box<str>>,
+	pub catalog_number: Option<Box<str>>,
+}
+
+
+impl Metadata {
+	/// How many release details, the label and the catalog number, a candidate's name
+	/// mentions. Uploads are often named after the release, so these hint at a match.
+	pub fn hints(&self, name: &str) -> u8 {
+		let name = normalize(name);
+
+		[self.label.as_deref(), self.catalog_number.as_deref()]
+			.iter()
+			.flatten()
+			.map(|detail| normalize(detail))
+			// Too short details would match by chance.
+			.filter(|detail| detail.len() >= MIN_HINT_LEN && name.contains(detail.as_str()))
+			.count() as u8
+	}
+}
+
+
+const MIN_HINT_LEN: usize = 3;
+
+
+/// Lowercase alphanumerics only, so that `SKR-026` matches `skr026`.
+fn normalize(text: &str) -> String {
+	text
+		.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+
+impl std::fmt::Display for Metadata {
+	/// A summary of the available fields, separated by bars.
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let bpm = self.bpm.map(|bpm| format!("{} bpm", bpm));
+
+		let fields = [
+			bpm.as_deref(),
+			self.key.as_deref(),
+			self.genre.as_deref(),
+			self.label.as_deref(),
+			self.catalog_number.as_deref(),
+			self.release_date.as_deref(),
+		];
+
+		let mut fields = fields
+			.iter()
+			.flatten();
+
+		if let Some(first) = fields.next() {
+			f.write_str(first)?;
+		}
+
+		for field in fields {
+			write!(f, " | {}", field)?;
+		}
+
+		Ok(())
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hints() {
+		let metadata = Metadata {
+			label: Some("Skryptöm Records".into()),
+			catalog_number: Some("SKR-026".into()),
+			genre: Some("Techno".into()),
+			..Default::default()
+		};
+
+		assert_eq!(metadata.hints("Somne - Kinetic Rampage (Extended Mix) [SKR026]"), 1);
+		assert_eq!(metadata.hints("Somne - Kinetic Rampage [Skryptöm Records SKR 026]"), 2);
+		assert_eq!(metadata.hints("Somne - Kinetic Rampage (Techno)"), 0);
+		assert_eq!(Metadata::default().hints("Somne - Kinetic Rampage"), 0);
+	}
+}
@@ -1,15 +1,18 @@
 mod duration;
 mod id;
+mod metadata;
 
 use std::convert::TryInto;
 
 pub use duration::{Duration, ParseError as ParseDurationError};
 pub use id::{Id, Cleaner as IdCleaner, ParseError as ParseIdError};
+pub use metadata::Metadata;
 
 
 #[derive(Debug, Clone, Eq)]
 pub struct Track {
 	pub duration: Option<Duration>,
+	pub metadata: Metadata,
 	id: Id,
 	query_string: Box<str>,
 }
@@ -28,7 +31,8 @@ impl Track {
 			Track {
 				id,
 				query_string,
-				duration: None
+				duration: None,
+				metadata: Metadata::default(),
 			}
 		)
 	}
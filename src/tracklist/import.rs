@@ -0,0 +1,579 @@
+use lazy_static::lazy_static;
+
+use thiserror::Error;
+
+use regex::Regex;
+
+use serde_json::Value;
+
+use crate::track::{Track, Duration, ParseDurationError};
+use super::{Entry, Error as EntryError, LineError};
+
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("invalid json: {0}")]
+	Json(serde_json::Error),
+
+	#[error("no track list found in json")]
+	MissingTracks,
+}
+
+
+/// A track from a playlist export, before being mapped to a track id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Row {
+	pub artists: Vec<String>,
+	pub title: String,
+	pub mix: Option<String>,
+	pub duration: Option<Duration>,
+}
+
+
+impl Row {
+	/// Map to the `Artists - Name (Mix)` format. Artists are joined by commas, and mix
+	/// suffixes like `- Extended Mix` are moved to the mix.
+	pub fn into_track(self) -> Result<Track, EntryError> {
+		lazy_static! {
+			static ref FEATURE_PATTERN: Regex = Regex
+				::new(r"(?i)\s*[(\[](feat|ft|with)\.?\s[^)\]]*[)\]]")
+				.expect("invalid regex");
+
+			static ref MIX_PATTERN: Regex = Regex
+				::new(
+					r"(?i)^(?P<name>.+?)\s+-\s+(?P<mix>[^-]*\b(mix|remix|edit|version|dub|rework|remaster(ed)?|bootleg|vip|instrumental)\b[^-]*)$"
+				)
+				.expect("invalid regex");
+		}
+
+		let artists = self.artists
+			.iter()
+			.map(
+				|artist| artist
+					.replace(&['(', ')'][..], "")
+					.replace(" - ", " ")
+					.trim()
+					.to_owned()
+			)
+			.filter(
+				|artist| !artist.is_empty()
+			)
+			.collect::<Vec<_>>()
+			.join(", ");
+
+		let title = FEATURE_PATTERN.replace_all(&self.title, "");
+		let title = title.trim();
+
+		let (name, mix) = match self.mix.as_deref().map(str::trim) {
+			Some(mix) if !mix.is_empty() => (title, Some(mix)),
+			_ => match MIX_PATTERN.captures(title) {
+				Some(captures) => (
+					captures
+						.name("name")
+						.expect("name group should always match")
+						.as_str(),
+					captures
+						.name("mix")
+						.map(|mix| mix.as_str().trim()),
+				),
+				None => (title, None),
+			}
+		};
+
+		let id = match mix {
+			// The name may not contain parenthesis when there's a mix.
+			Some(mix) => format!(
+				"{} - {} ({})",
+				artists,
+				name.replace('(', "[").replace(')', "]"),
+				mix.replace(&['(', ')'][..], "")
+			),
+			None => format!("{} - {}", artists, name),
+		};
+
+		let mut track = Track
+			::new(id.as_str())
+			.map_err(EntryError::Id)?;
+
+		track.duration = self.duration;
+
+		Ok(track)
+	}
+}
+
+
+/// Whether the tracklist is a playlist export, as opposed to a plain tracklist.
+pub fn detect(tracklist: &str) -> Option<Format> {
+	let text = tracklist.trim_start();
+
+	if text.starts_with('[') || text.starts_with('{') {
+		return Some(Format::Json);
+	}
+
+	let header = text
+		.lines()
+		.next()
+		.map(split_csv_line)?;
+
+	let columns = Columns::new(&header);
+
+	if columns.artists.is_some() && columns.title.is_some() {
+		Some(Format::Csv)
+	}
+	else {
+		None
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+	/// A CSV file with a header, like the ones from Exportify.
+	Csv,
+	/// A JSON array of track objects, like the ones from the Spotify, Deezer or Beatport APIs.
+	Json,
+}
+
+
+pub fn parse(tracklist: &str, format: Format) -> Result<Vec<Result<Entry, LineError>>, Error> {
+	match format {
+		Format::Csv => Ok(parse_csv(tracklist)),
+		Format::Json => parse_json(tracklist),
+	}
+}
+
+
+/// Rows are numbered by their line in the file.
+pub fn parse_csv(tracklist: &str) -> Vec<Result<Entry, LineError>> {
+	let mut lines = tracklist
+		.lines()
+		.enumerate()
+		.map(
+			|(ix, line)| (ix + 1, line)
+		)
+		.filter(
+			|(_, line)| !line.trim().is_empty()
+		);
+
+	let columns = match lines.next() {
+		Some((_, header)) => Columns::new(&split_csv_line(header)),
+		None => return Vec::new(),
+	};
+
+	lines
+		.map(
+			|(line, text)| {
+				let fields = split_csv_line(text);
+
+				let field = |column: Option<usize>| column
+					.and_then(|column| fields.get(column))
+					.map(|field| field.trim())
+					.filter(|field| !field.is_empty());
+
+				let duration = match (field(columns.duration_ms), field(columns.duration)) {
+					(Some(ms), _) => parse_milliseconds(ms).map(Some),
+					(None, Some(duration)) => parse_duration(duration).map(Some),
+					(None, None) => Ok(None),
+				};
+
+				let row = duration.map(
+					|duration| Row {
+						artists: field(columns.artists)
+							.map(split_artists)
+							.unwrap_or_default(),
+						title: field(columns.title)
+							.unwrap_or_default()
+							.to_owned(),
+						mix: field(columns.mix).map(Into::into),
+						duration,
+					}
+				);
+
+				row
+					.and_then(Row::into_track)
+					.map(
+						|track| Entry { line, track }
+					)
+					.map_err(
+						|error| LineError { line, error }
+					)
+			}
+		)
+		.collect()
+}
+
+
+/// Tracks are numbered by their position in the list, from 1.
+pub fn parse_json(tracklist: &str) -> Result<Vec<Result<Entry, LineError>>, Error> {
+	let value: Value = serde_json
+		::from_str(tracklist)
+		.map_err(Error::Json)?;
+
+	let tracks = match &value {
+		Value::Array(tracks) => tracks,
+		Value::Object(object) => ["tracks", "items", "data", "results"]
+			.iter()
+			.find_map(
+				|key| match object.get(*key) {
+					// Spotify nests the tracks as `{ "tracks": { "items": [...] } }`.
+					Some(Value::Object(inner)) => inner.get("items")?.as_array(),
+					Some(value) => value.as_array(),
+					None => None,
+				}
+			)
+			.ok_or(Error::MissingTracks)?,
+		_ => return Err(Error::MissingTracks),
+	};
+
+	let entries = tracks
+		.iter()
+		.enumerate()
+		.map(
+			|(ix, track)| {
+				let line = ix + 1;
+
+				// Spotify playlist items wrap the track object.
+				let track = track
+					.get("track")
+					.filter(|track| track.is_object())
+					.unwrap_or(track);
+
+				json_row(track)
+					.and_then(Row::into_track)
+					.map(
+						|track| Entry { line, track }
+					)
+					.map_err(
+						|error| LineError { line, error }
+					)
+			}
+		)
+		.collect();
+
+	Ok(entries)
+}
+
+
+fn json_row(track: &Value) -> Result<Row, EntryError> {
+	let string = |keys: &[&str]| keys
+		.iter()
+		.find_map(
+			|key| track.get(*key)?.as_str()
+		)
+		.map(str::to_owned);
+
+	let name = |value: &Value| match value {
+		Value::String(name) => Some(name.clone()),
+		Value::Object(object) => object
+			.get("name")?
+			.as_str()
+			.map(str::to_owned),
+		_ => None,
+	};
+
+	let artists = match track.get("artists").or_else(|| track.get("artist")) {
+		Some(Value::Array(artists)) => artists
+			.iter()
+			.filter_map(name)
+			.collect(),
+		Some(Value::String(artists)) => split_artists(artists),
+		Some(artist) => name(artist)
+			.into_iter()
+			.collect(),
+		None => Vec::new(),
+	};
+
+	let duration_ms = ["duration_ms", "length_ms"]
+		.iter()
+		.find_map(
+			|key| track.get(*key)
+		);
+
+	let duration = match (duration_ms, track.get("duration")) {
+		(Some(ms), _) => json_number(ms, parse_milliseconds).map(Some),
+		(None, Some(Value::String(duration))) => parse_duration(duration).map(Some),
+		(None, Some(seconds)) => json_number(seconds, parse_seconds).map(Some),
+		(None, None) => Ok(None),
+	};
+
+	Ok(
+		Row {
+			artists,
+			title: string(&["name", "title", "track_name"]).unwrap_or_default(),
+			mix: string(&["mix_name", "mix"]),
+			duration: duration?,
+		}
+	)
+}
+
+
+fn json_number<F>(value: &Value, parse: F) -> Result<Duration, EntryError>
+where
+	F: FnOnce(&str) -> Result<Duration, EntryError>
+{
+	match value {
+		Value::Number(number) => parse(&number.to_string()),
+		Value::String(number) => parse(number),
+		_ => Err(EntryError::Duration(ParseDurationError::InvalidNumber)),
+	}
+}
+
+
+/// The indices of the known columns in a CSV header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct Columns {
+	artists: Option<usize>,
+	title: Option<usize>,
+	mix: Option<usize>,
+	duration_ms: Option<usize>,
+	duration: Option<usize>,
+}
+
+
+impl Columns {
+	fn new(header: &[String]) -> Self {
+		let find = |names: &[&str]| header
+			.iter()
+			.position(
+				|column| names.contains(
+					&column
+						.trim()
+						.to_lowercase()
+						.as_str()
+				)
+			);
+
+		Self {
+			artists: find(&["artist name(s)", "artist names", "artists", "artist", "artist name"]),
+			title: find(&["track name", "track title", "title", "name", "track"]),
+			mix: find(&["mix name", "mix"]),
+			duration_ms: find(&["duration (ms)", "duration_ms", "duration ms", "length (ms)"]),
+			duration: find(&["duration", "length", "time"]),
+		}
+	}
+}
+
+
+/// Split a CSV line, handling quoted fields. Quoted line breaks are not supported.
+fn split_csv_line(line: &str) -> Vec<String> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut quoted = false;
+
+	let mut chars = line.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted && chars.peek() == Some(&'"') => {
+				chars.next();
+				field.push('"');
+			},
+
+			'"' => quoted = !quoted,
+
+			',' if !quoted => fields.push(std::mem::take(&mut field)),
+
+			c => field.push(c),
+		}
+	}
+
+	fields.push(field);
+
+	fields
+}
+
+
+fn split_artists(artists: &str) -> Vec<String> {
+	artists
+		.split(&[',', ';'][..])
+		.map(str::trim)
+		.filter(|artist| !artist.is_empty())
+		.map(str::to_owned)
+		.collect()
+}
+
+
+fn parse_milliseconds(ms: &str) -> Result<Duration, EntryError> {
+	let ms: f64 = ms
+		.parse()
+		.map_err(
+			|_| EntryError::Duration(ParseDurationError::InvalidNumber)
+		)?;
+
+	parse_seconds(&(ms / 1000.0).to_string())
+}
+
+
+/// Either `m:ss` or a number of seconds.
+fn parse_duration(duration: &str) -> Result<Duration, EntryError> {
+	if duration.contains(':') {
+		duration
+			.parse()
+			.map_err(EntryError::Duration)
+	}
+	else {
+		parse_seconds(duration)
+	}
+}
+
+
+fn parse_seconds(seconds: &str) -> Result<Duration, EntryError> {
+	let seconds: f64 = seconds
+		.parse()
+		.map_err(
+			|_| EntryError::Duration(ParseDurationError::InvalidNumber)
+		)?;
+
+	if !(0.0 ..= u16::MAX as f64).contains(&seconds) {
+		return Err(EntryError::Duration(ParseDurationError::InvalidNumber));
+	}
+
+	Ok(
+		Duration::from_seconds(seconds.round() as u16)
+	)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+
+	fn track(id: &str, duration: Option<Duration>) -> Track {
+		let mut track = Track
+			::new(id)
+			.expect("invalid track");
+
+		track.duration = duration;
+
+		track
+	}
+
+
+	#[test]
+	fn test_row() {
+		let test = |artists: &[&str], title: &str, mix: Option<&str>, id: &str| {
+			let row = Row {
+				artists: artists
+					.iter()
+					.map(|&artist| artist.to_owned())
+					.collect(),
+				title: title.into(),
+				mix: mix.map(Into::into),
+				duration: None,
+			};
+
+			assert_eq!(
+				row.into_track().expect("failed to map row"),
+				track(id, None)
+			);
+		};
+
+		test(&["Somne", "Mind Against"], "Vertere - Extended Mix", None, "Somne, Mind Against - Vertere (Extended Mix)");
+		test(&["Somne"], "Vertere", Some("Original Mix"), "Somne - Vertere (Original Mix)");
+		test(&["Somne"], "Vertere (Extended Mix)", None, "Somne - Vertere (Extended Mix)");
+		test(&["Somne"], "Vertere (feat. Someone) - Radio Edit", None, "Somne - Vertere (Radio Edit)");
+		test(&["Somne"], "Vertere - Part 2", None, "Somne - Vertere - Part 2");
+		test(&["Artist (UK)"], "Vertere", None, "Artist UK - Vertere");
+	}
+
+
+	#[test]
+	fn test_csv() {
+		let tracklist = concat!(
+			"\"Track URI\",\"Track Name\",\"Artist Name(s)\",\"Album Name\",\"Duration (ms)\"\n",
+			"\"spotify:track:1\",\"Vertere - Extended Mix\",\"Somne,Mind Against\",\"Vertere\",\"554000\"\n",
+			"\"spotify:track:2\",\"Divine Moments Of Truth\",\"Astrix, Shpongle\",\"\"\"Quoted\"\"\",\"449600\"\n",
+			"\"spotify:track:3\",\"\",\"Nobody\",\"\",\"1000\"\n",
+		);
+
+		assert_eq!(detect(tracklist), Some(Format::Csv));
+
+		assert_eq!(
+			parse_csv(tracklist),
+			vec![
+				Ok(
+					Entry {
+						line: 2,
+						track: track(
+							"Somne, Mind Against - Vertere (Extended Mix)",
+							Some(Duration::new(9, 14))
+						),
+					}
+				),
+				Ok(
+					Entry {
+						line: 3,
+						track: track(
+							"Astrix, Shpongle - Divine Moments Of Truth",
+							Some(Duration::new(7, 30))
+						),
+					}
+				),
+				Err(
+					LineError {
+						line: 4,
+						error: EntryError::Id(crate::track::ParseIdError::MissingName),
+					}
+				),
+			]
+		);
+	}
+
+
+	#[test]
+	fn test_json() {
+		let tracklist = r#"{
+			"tracks": {
+				"items": [
+					{
+						"track": {
+							"name": "Vertere - Extended Mix",
+							"artists": [{ "name": "Somne" }, { "name": "Mind Against" }],
+							"duration_ms": 554000
+						}
+					},
+					{ "title": "Vertere", "artist": { "name": "Georgi Z" }, "duration": 384 },
+					{ "name": "Vertere", "mix_name": "Original Mix", "artists": ["Somne"], "length_ms": "x" }
+				]
+			}
+		}"#;
+
+		assert_eq!(detect(tracklist), Some(Format::Json));
+
+		assert_eq!(
+			parse_json(tracklist).expect("failed to parse json"),
+			vec![
+				Ok(
+					Entry {
+						line: 1,
+						track: track(
+							"Somne, Mind Against - Vertere (Extended Mix)",
+							Some(Duration::new(9, 14))
+						),
+					}
+				),
+				Ok(
+					Entry {
+						line: 2,
+						track: track("Georgi Z - Vertere", Some(Duration::new(6, 24))),
+					}
+				),
+				Err(
+					LineError {
+						line: 3,
+						error: EntryError::Duration(ParseDurationError::InvalidNumber),
+					}
+				),
+			]
+		);
+	}
+
+
+	#[test]
+	fn test_detect_plain() {
+		assert_eq!(detect("Somne - Vertere\nGeorgi Z - Vertere 6:24"), None);
+		assert_eq!(detect("\"Georgi Z - Vertere\",6:24"), None);
+	}
+}
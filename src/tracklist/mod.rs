@@ -0,0 +1,121 @@
+use lazy_static::lazy_static;
+
+use thiserror::Error;
+
+use regex::Regex;
+
+use crate::track::{Track, ParseIdError, ParseDurationError};
+
+pub mod import;
+
+#[cfg(test)]
+mod tests;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum Error {
+	#[error("invalid track id: {0}")]
+	Id(ParseIdError),
+
+	#[error("invalid track duration: {0}")]
+	Duration(ParseDurationError),
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("line {line}: {error}")]
+pub struct LineError {
+	pub line: usize,
+	pub error: Error,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+	pub line: usize,
+	pub track: Track,
+}
+
+
+/// Parse a tracklist, one track per line, in the `Artists - Name (Mix)` format. Each id
+/// may be followed by the track duration (`m:ss`), separated by spaces, a comma, a
+/// semicolon or a tab, so that simple CSV files are accepted as well. Empty lines and
+/// lines starting with `#` are ignored.
+/// Lines are numbered from 1.
+pub fn parse(tracklist: &str) -> impl Iterator<Item = Result<Entry, LineError>> + '_ {
+	tracklist
+		.lines()
+		.enumerate()
+		.map(
+			|(ix, line)| (ix + 1, line.trim())
+		)
+		.filter(
+			|(_, line)| !line.is_empty() && !line.starts_with('#')
+		)
+		.map(
+			|(line, text)| parse_line(text)
+				.map(
+					|track| Entry { line, track }
+				)
+				.map_err(
+					|error| LineError { line, error }
+				)
+		)
+}
+
+
+/// Parse either a plain tracklist or a playlist export, detecting the format.
+pub fn parse_any(tracklist: &str) -> Result<Vec<Result<Entry, LineError>>, import::Error> {
+	match import::detect(tracklist) {
+		Some(format) => {
+			log::debug!("importing tracklist as {:?}", format);
+			import::parse(tracklist, format)
+		},
+		None => Ok(
+			parse(tracklist).collect()
+		),
+	}
+}
+
+
+fn parse_line(line: &str) -> Result<Track, Error> {
+	lazy_static! {
+		static ref DURATION_PATTERN: Regex = Regex
+			::new(r"^(?P<id>.*?)[\s,;]+(?P<duration>[0-9]+:[0-9]+)$")
+			.expect("invalid regex");
+	}
+
+	let (id, duration) = match DURATION_PATTERN.captures(line) {
+		Some(captures) => (
+			captures
+				.name("id")
+				.expect("id group should always match")
+				.as_str(),
+			Some(
+				captures
+					.name("duration")
+					.expect("duration group should always match")
+					.as_str()
+			),
+		),
+		None => (line, None),
+	};
+
+	let id = id
+		.trim()
+		.trim_matches('"'); // Quoted CSV field.
+
+	let mut track = Track
+		::new(id)
+		.map_err(Error::Id)?;
+
+	if let Some(duration) = duration {
+		track.duration = Some(
+			duration
+				.parse()
+				.map_err(Error::Duration)?
+		);
+	}
+
+	Ok(track)
+}
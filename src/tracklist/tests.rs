@@ -0,0 +1,97 @@
+use super::*;
+
+use crate::track::Duration;
+
+
+fn track(id: &str, duration: Option<Duration>) -> Track {
+	let mut track = Track
+		::new(id)
+		.expect("invalid track");
+
+	track.duration = duration;
+
+	track
+}
+
+
+#[test]
+fn test_parse() {
+	let tracklist = r#"
+# Warm up
+Mind Against & Somne - Vertere
+Somne, Mind Against - Vertere (Extended Mix) 9:14
+
+"Georgi Z - Vertere",6:24
+Astrix & Shpongle - Divine Moments Of Truth	7:30
+	"#;
+
+	let entries: Vec<_> = parse(tracklist).collect();
+
+	assert_eq!(
+		entries,
+		vec![
+			Ok(
+				Entry {
+					line: 3,
+					track: track("Mind Against & Somne - Vertere", None),
+				}
+			),
+			Ok(
+				Entry {
+					line: 4,
+					track: track(
+						"Somne, Mind Against - Vertere (Extended Mix)",
+						Some(Duration::new(9, 14))
+					),
+				}
+			),
+			Ok(
+				Entry {
+					line: 6,
+					track: track("Georgi Z - Vertere", Some(Duration::new(6, 24))),
+				}
+			),
+			Ok(
+				Entry {
+					line: 7,
+					track: track(
+						"Astrix & Shpongle - Divine Moments Of Truth",
+						Some(Duration::new(7, 30))
+					),
+				}
+			),
+		]
+	);
+}
+
+
+#[test]
+fn test_invalid_lines() {
+	let tracklist = "invalid-track\nTest - track 1:75\nTest - track(";
+
+	let entries: Vec<_> = parse(tracklist).collect();
+
+	assert_eq!(
+		entries,
+		vec![
+			Err(
+				LineError {
+					line: 1,
+					error: Error::Id(ParseIdError::MissingSeparator),
+				}
+			),
+			Err(
+				LineError {
+					line: 2,
+					error: Error::Duration(ParseDurationError::InvalidSeconds),
+				}
+			),
+			Err(
+				LineError {
+					line: 3,
+					error: Error::Id(ParseIdError::TrailingChars),
+				}
+			),
+		]
+	);
+}
@@ -0,0 +1,40 @@
+use tui::{
+	buffer::Buffer,
+	layout::Rect,
+	widgets::{Block, Borders, Paragraph, Text, Widget},
+};
+
+use crate::track::Metadata;
+
+
+/// The track being fetched, along with its release metadata.
+pub struct Header<'a> {
+	pub track: &'a str,
+	pub metadata: &'a Metadata,
+}
+
+
+impl<'a> Widget for Header<'a> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let title = format!(" {} ", self.track);
+
+		let metadata = self.metadata.to_string();
+
+		let text = [
+			Text::raw(
+				if metadata.is_empty() { "no release metadata" } else { &metadata }
+			)
+		];
+
+		let widget = Paragraph
+			::new(text.iter())
+			.block(
+				Block
+					::default()
+					.borders(Borders::ALL)
+					.title(&title)
+			);
+
+		widget.render(area, buf);
+	}
+}
@@ -2,6 +2,7 @@ use tui::layout::{Layout, Direction, Constraint, Rect};
 
 
 pub struct Root {
+	pub header: Rect,
 	pub metasources: Rect,
 	pub tracksources: Rect,
 }
@@ -14,6 +15,7 @@ impl Root {
 			.direction(Direction::Vertical)
 			.constraints(
 				vec![
+					Constraint::Length(3), // 1 line + 2 borders
 					Constraint::Length(12), // 10 entries + 2 borders
 					Constraint::Min(5)
 				]
@@ -21,7 +23,8 @@ impl Root {
 			.split(base);
 
 		match chunks.as_slice() {
-			[top, bottom] => Self {
+			[header, top, bottom] => Self {
+				header: *header,
 				metasources: *top,
 				tracksources: *bottom,
 			},
@@ -34,6 +37,9 @@ impl Root {
 pub struct MetaSources {
 	pub beatport: Rect,
 	pub bandcamp: Rect,
+	pub musicbrainz: Rect,
+	pub discogs: Rect,
+	pub reconcile: Rect,
 }
 
 
@@ -44,20 +50,24 @@ impl MetaSources {
 			.direction(Direction::Horizontal)
 			.constraints(
 				vec![
-					Constraint::Percentage(50),
-					Constraint::Percentage(50),
+					Constraint::Percentage(20),
+					Constraint::Percentage(20),
+					Constraint::Percentage(20),
+					Constraint::Percentage(20),
+					Constraint::Percentage(20),
 				]
 			)
 			.split(base);
 
-		let (left, right) = match chunks.as_slice() {
-			[left, right] => (*left, *right),
+		match chunks.as_slice() {
+			[first, second, third, fourth, fifth] => Self {
+				beatport: *first,
+				bandcamp: *second,
+				musicbrainz: *third,
+				discogs: *fourth,
+				reconcile: *fifth,
+			},
 			_ => panic!("chunks pattern not matched"),
-		};
-
-		Self {
-			beatport: left,
-			bandcamp: right,
 		}
 	}
 }
@@ -67,6 +77,8 @@ pub struct TrackSources {
 	pub slider: Rect,
 	pub zippy: Rect,
 	pub music2k: Rect,
+	pub plugin: Rect,
+	pub library: Rect,
 }
 
 
@@ -93,21 +105,40 @@ impl TrackSources {
 			.direction(Direction::Vertical)
 			.constraints(
 				vec![
-					Constraint::Percentage(50),
-					Constraint::Percentage(50),
+					Constraint::Percentage(67),
+					Constraint::Percentage(33),
+				]
+			)
+			.split(left);
+
+		let (top_left, bottom_left) = match chunks.as_slice() {
+			[top_left, bottom_left] => (*top_left, *bottom_left),
+			_ => panic!("chunks pattern not matched"),
+		};
+
+		let chunks = Layout
+			::default()
+			.direction(Direction::Vertical)
+			.constraints(
+				vec![
+					Constraint::Percentage(34),
+					Constraint::Percentage(33),
+					Constraint::Percentage(33),
 				]
 			)
 			.split(right);
 
-		let (top_right, bottom_right) = match chunks.as_slice() {
-			[top_right, bottom_right] => (*top_right, *bottom_right),
+		let (top_right, middle_right, bottom_right) = match chunks.as_slice() {
+			[top_right, middle_right, bottom_right] => (*top_right, *middle_right, *bottom_right),
 			_ => panic!("chunks pattern not matched"),
 		};
 
 		Self {
-			slider: left,
+			slider: top_left,
 			zippy: top_right,
-			music2k: bottom_right,
+			music2k: middle_right,
+			plugin: bottom_right,
+			library: bottom_left,
 		}
 	}
 }
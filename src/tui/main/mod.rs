@@ -1,3 +1,4 @@
+mod header;
 mod layout;
 
 use std::io;
@@ -5,13 +6,22 @@ use std::time::Duration;
 pub use std::thread::JoinHandle;
 
 use super::{Backend, Key, StdinReader, Terminal};
-use crate::util::future::abortable::AbortHandle;
+use crate::{
+	track::Metadata,
+	util::future::abortable::AbortHandle,
+};
+use header::Header;
 pub use crate::modules::{
 	beatport::tui::Widget as BeatportWidget,
 	bandcamp::tui::Widget as BandcampWidget,
+	musicbrainz::tui::Widget as MusicBrainzWidget,
+	discogs::tui::Widget as DiscogsWidget,
+	metasource::reconcile::tui::Widget as ReconcileWidget,
 	slider::tui::Widget as SliderWidget,
 	zippy::tui::Widget as ZippyWidget,
 	music2k::tui::Widget as Music2kWidget,
+	plugin::tui::Widget as PluginWidget,
+	library::tui::Widget as LibraryWidget,
 };
 
 
@@ -21,12 +31,21 @@ pub struct Window {
 	pub terminal: Terminal<Backend>,
 	pub input: StdinReader,
 
+	pub track: Box<str>,
+	/// The metadata known before the metasources run, if any.
+	pub metadata: Metadata,
+
 	// Widgets:
 	pub beatport_widget: BeatportWidget,
 	pub bandcamp_widget: BandcampWidget,
+	pub musicbrainz_widget: MusicBrainzWidget,
+	pub discogs_widget: DiscogsWidget,
+	pub reconcile_widget: ReconcileWidget,
 	pub slider_widget: SliderWidget,
 	pub zippy_widget: ZippyWidget,
 	pub music2k_widget: Music2kWidget,
+	pub plugin_widget: PluginWidget,
+	pub library_widget: LibraryWidget,
 }
 
 
@@ -47,18 +66,35 @@ impl Window {
 	fn update_widgets(&mut self) {
 		self.beatport_widget.update();
 		self.bandcamp_widget.update();
+		self.musicbrainz_widget.update();
+		self.discogs_widget.update();
+		self.reconcile_widget.update();
 		self.slider_widget.update();
 		self.zippy_widget.update();
 		self.music2k_widget.update();
+		self.plugin_widget.update();
+		self.library_widget.update();
 	}
 
 
 	fn draw(&mut self) -> io::Result<()> {
 		let beatport_widget = self.beatport_widget.renderer();
 		let bandcamp_widget = self.bandcamp_widget.renderer();
+		let musicbrainz_widget = self.musicbrainz_widget.renderer();
+		let discogs_widget = self.discogs_widget.renderer();
+		let reconcile_widget = self.reconcile_widget.renderer();
 		let slider_widget = self.slider_widget.renderer();
 		let zippy_widget = self.zippy_widget.renderer();
 		let music2k_widget = self.music2k_widget.renderer();
+		let plugin_widget = self.plugin_widget.renderer();
+		let library_widget = self.library_widget.renderer();
+
+		let header = Header {
+			track: &self.track,
+			metadata: self.reconcile_widget
+				.metadata()
+				.unwrap_or(&self.metadata),
+		};
 
 		self.terminal.draw(
 			|mut frame| {
@@ -68,15 +104,22 @@ impl Window {
 
 				let tracksources_layout = layout::TrackSources::new(root_layout.tracksources);
 
+				frame.render_widget(header, root_layout.header);
+
 				{
 					frame.render_widget(beatport_widget, metasources_layout.beatport);
 					frame.render_widget(bandcamp_widget, metasources_layout.bandcamp);
+					frame.render_widget(musicbrainz_widget, metasources_layout.musicbrainz);
+					frame.render_widget(discogs_widget, metasources_layout.discogs);
+					frame.render_widget(reconcile_widget, metasources_layout.reconcile);
 				}
 
 				{
 					frame.render_widget(slider_widget, tracksources_layout.slider);
 					frame.render_widget(zippy_widget, tracksources_layout.zippy);
 					frame.render_widget(music2k_widget, tracksources_layout.music2k);
+					frame.render_widget(plugin_widget, tracksources_layout.plugin);
+					frame.render_widget(library_widget, tracksources_layout.library);
 				}
 			}
 		)
@@ -0,0 +1,55 @@
+use std::hash::Hasher;
+
+
+// FNV-1a 64 bit parameters: http://www.isthe.com/chongo/tech/comp/fnv/
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+
+/// The FNV-1a hash. Unlike the std `DefaultHasher`, its algorithm is fixed, so the hashes
+/// may be persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fnv1a(u64);
+
+
+impl Default for Fnv1a {
+	fn default() -> Self {
+		Self(OFFSET_BASIS)
+	}
+}
+
+
+impl Hasher for Fnv1a {
+	fn finish(&self) -> u64 {
+		self.0
+	}
+
+
+	fn write(&mut self, bytes: &[u8]) {
+		for &byte in bytes {
+			self.0 ^= byte as u64;
+			self.0 = self.0.wrapping_mul(PRIME);
+		}
+	}
+}
+
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+	let mut hasher = Fnv1a::default();
+	hasher.write(bytes);
+	hasher.finish()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_fnv1a() {
+		assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+		assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+		assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+	}
+}
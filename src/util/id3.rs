@@ -0,0 +1,382 @@
+use std::{
+	convert::TryInto,
+	fs,
+	io,
+	path::Path,
+};
+
+use crate::track::{Duration, Track};
+
+
+// ID3v2.4 reference:
+// https://id3.org/id3v2.4.0-structure
+// https://id3.org/id3v2.4.0-frames
+
+
+pub const HEADER_SIZE: usize = 10;
+const FOOTER_FLAG: u8 = 0x10;
+const V1_SIZE: usize = 128;
+const UTF8_ENCODING: u8 = 3;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Tag<'a> {
+	pub artist: Option<&'a str>,
+	pub title: Option<&'a str>,
+	pub mix: Option<&'a str>,
+	pub duration: Option<Duration>,
+	pub label: Option<&'a str>,
+	pub release_date: Option<&'a str>,
+	pub bpm: Option<u16>,
+	pub key: Option<&'a str>,
+	pub genre: Option<&'a str>,
+	pub catalog_number: Option<&'a str>,
+}
+
+
+impl<'a> From<&'a Track> for Tag<'a> {
+	fn from(track: &'a Track) -> Self {
+		let id = track.id();
+
+		Self {
+			artist: Some(id.artists()),
+			title: Some(id.title()),
+			mix: id.mix(),
+			duration: track.duration,
+			label: track.metadata.label.as_deref(),
+			release_date: track.metadata.release_date.as_deref(),
+			bpm: track.metadata.bpm,
+			key: track.metadata.key.as_deref(),
+			genre: track.metadata.genre.as_deref(),
+			catalog_number: track.metadata.catalog_number.as_deref(),
+		}
+	}
+}
+
+
+impl<'a> Tag<'a> {
+	/// Encode as an ID3v2.4 tag.
+	pub fn encode(&self) -> Vec<u8> {
+		let duration = self.duration.map(
+			|duration| (duration.as_seconds() as u32 * 1000).to_string()
+		);
+
+		let bpm = self.bpm.map(|bpm| bpm.to_string());
+
+		// User defined text frames hold a description and the value, null separated.
+		let catalog_number = self.catalog_number.map(
+			|catalog_number| format!("CATALOGNUMBER\0{}", catalog_number)
+		);
+
+		let frames = [
+			(b"TPE1", self.artist),
+			(b"TIT2", self.title),
+			(b"TIT3", self.mix),
+			(b"TLEN", duration.as_deref()),
+			(b"TPUB", self.label),
+			(b"TDRC", self.release_date),
+			(b"TBPM", bpm.as_deref()),
+			(b"TKEY", self.key),
+			(b"TCON", self.genre),
+			(b"TXXX", catalog_number.as_deref()),
+		];
+
+		let mut body = Vec::new();
+
+		for (id, text) in frames.iter() {
+			if let Some(text) = text {
+				body.extend_from_slice(*id);
+				body.extend_from_slice(&syncsafe(text.len() as u32 + 1));
+				body.extend_from_slice(&[0, 0]); // Flags.
+				body.push(UTF8_ENCODING);
+				body.extend_from_slice(text.as_bytes());
+			}
+		}
+
+		let mut tag = Vec::with_capacity(HEADER_SIZE + body.len());
+
+		tag.extend_from_slice(b"ID3");
+		tag.extend_from_slice(&[4, 0]); // Version 2.4.0.
+		tag.push(0); // Flags.
+		tag.extend_from_slice(&syncsafe(body.len() as u32));
+		tag.extend_from_slice(&body);
+
+		tag
+	}
+}
+
+
+/// Replace all ID3 tags of a file with the given tag.
+pub fn write<P>(path: P, tag: &Tag) -> io::Result<()>
+where
+	P: AsRef<Path>
+{
+	let path = path.as_ref();
+
+	let data = fs::read(path)?;
+
+	let mut tagged = tag.encode();
+	tagged.extend_from_slice(
+		strip(&data)
+	);
+
+	let mut tmp_path = path
+		.as_os_str()
+		.to_owned();
+	tmp_path.push(".tagging");
+
+	fs::write(&tmp_path, &tagged)?;
+	fs::rename(&tmp_path, path)?;
+
+	log::debug!("wrote id3 tag to {:#?}", path);
+
+	Ok(())
+}
+
+
+/// The text frames read from an existing tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Text {
+	pub artist: Option<Box<str>>,
+	pub title: Option<Box<str>>,
+}
+
+
+/// Read the artist and title of a leading ID3v2.3 or ID3v2.4 tag, if any.
+pub fn read(data: &[u8]) -> Option<Text> {
+	const FRAME_HEADER_SIZE: usize = 10;
+
+	if data.len() < HEADER_SIZE || !data.starts_with(b"ID3") {
+		return None;
+	}
+
+	let version = data[3];
+
+	if version != 3 && version != 4 {
+		return None;
+	}
+
+	let size = read_syncsafe(data[6..10].try_into().ok()?) as usize;
+
+	let mut frames = data.get(HEADER_SIZE .. HEADER_SIZE + size)?;
+
+	let mut text = Text::default();
+
+	while frames.len() >= FRAME_HEADER_SIZE && frames[0] != 0 {
+		let id = &frames[0..4];
+		let size_bytes = frames[4..8].try_into().ok()?;
+
+		// Only version 2.4 uses syncsafe frame sizes.
+		let size = if version == 4 {
+			read_syncsafe(size_bytes)
+		}
+		else {
+			u32::from_be_bytes(size_bytes)
+		} as usize;
+
+		let body = frames.get(FRAME_HEADER_SIZE .. FRAME_HEADER_SIZE + size)?;
+
+		match id {
+			b"TPE1" => text.artist = decode_text(body),
+			b"TIT2" => text.title = decode_text(body),
+			_ => { },
+		}
+
+		frames = &frames[FRAME_HEADER_SIZE + size ..];
+	}
+
+	Some(text)
+}
+
+
+/// Decode a text frame body, which starts with the encoding byte.
+fn decode_text(body: &[u8]) -> Option<Box<str>> {
+	let (&encoding, body) = body.split_first()?;
+
+	let utf16 = |body: &[u8], big_endian: bool| -> String {
+		let units: Vec<u16> = body
+			.chunks_exact(2)
+			.map(
+				|pair| if big_endian {
+					u16::from_be_bytes([pair[0], pair[1]])
+				}
+				else {
+					u16::from_le_bytes([pair[0], pair[1]])
+				}
+			)
+			.collect();
+
+		String::from_utf16_lossy(&units)
+	};
+
+	let text = match encoding {
+		0 => body
+			.iter()
+			.map(|&byte| byte as char)
+			.collect(),
+		1 => match body {
+			[0xff, 0xfe, rest @ ..] => utf16(rest, false),
+			[0xfe, 0xff, rest @ ..] => utf16(rest, true),
+			_ => return None,
+		},
+		2 => utf16(body, true),
+		UTF8_ENCODING => String::from_utf8_lossy(body).into_owned(),
+		_ => return None,
+	};
+
+	// Multiple values are null separated, take the first.
+	let text = text
+		.split('\0')
+		.next()?
+		.trim();
+
+	if text.is_empty() {
+		None
+	}
+	else {
+		Some(text.into())
+	}
+}
+
+
+/// The size of the leading ID3v2 tag, including its header and footer, or zero if none.
+/// Only the tag header is needed.
+pub fn size(data: &[u8]) -> usize {
+	if data.len() < HEADER_SIZE || !data.starts_with(b"ID3") {
+		return 0;
+	}
+
+	let size = read_syncsafe(
+		data[6..10]
+			.try_into()
+			.expect("invalid slice size")
+	) as usize;
+
+	let footer = if data[5] & FOOTER_FLAG != 0 { HEADER_SIZE } else { 0 };
+
+	HEADER_SIZE + size + footer
+}
+
+
+/// Strip the leading ID3v2 and trailing ID3v1 tags, if any.
+pub fn strip(mut data: &[u8]) -> &[u8] {
+	data = data.get(size(data)..).unwrap_or(&[]);
+
+	if data.len() >= V1_SIZE && data[data.len() - V1_SIZE ..].starts_with(b"TAG") {
+		data = &data[.. data.len() - V1_SIZE];
+	}
+
+	data
+}
+
+
+fn syncsafe(value: u32) -> [u8; 4] {
+	[
+		(value >> 21 & 0x7f) as u8,
+		(value >> 14 & 0x7f) as u8,
+		(value >> 7 & 0x7f) as u8,
+		(value & 0x7f) as u8,
+	]
+}
+
+
+fn read_syncsafe(bytes: [u8; 4]) -> u32 {
+	bytes
+		.iter()
+		.fold(0, |value, &byte| value << 7 | (byte & 0x7f) as u32)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_syncsafe() {
+		assert_eq!(syncsafe(0x7f), [0, 0, 0, 0x7f]);
+		assert_eq!(syncsafe(0x80), [0, 0, 1, 0]);
+
+		for &value in [0, 1, 255, 4096, 0x0fff_ffff].iter() {
+			assert_eq!(read_syncsafe(syncsafe(value)), value);
+		}
+	}
+
+
+	#[test]
+	fn test_read() {
+		let tag = Tag {
+			artist: Some("Somne"),
+			title: Some("Kinetic Rampage"),
+			bpm: Some(140),
+			..Tag::default()
+		};
+
+		assert_eq!(
+			read(&tag.encode()),
+			Some(
+				Text {
+					artist: Some("Somne".into()),
+					title: Some("Kinetic Rampage".into()),
+				}
+			)
+		);
+
+		// Version 2.3, with plain frame sizes and a latin1 frame.
+		let tag = [
+			b"ID3\x03\x00\x00\x00\x00\x00\x11".as_ref(),
+			b"TIT2\x00\x00\x00\x07\x00\x00\x00Caf\xe9s\x00",
+		].concat();
+
+		assert_eq!(
+			read(&tag),
+			Some(
+				Text {
+					artist: None,
+					title: Some("Caf\u{e9}s".into()),
+				}
+			)
+		);
+
+		assert_eq!(read(b"not a tag"), None);
+	}
+
+
+	#[test]
+	fn test_encode() {
+		let tag = Tag {
+			artist: Some("Somne"),
+			duration: Some(Duration::new(1, 0)),
+			..Tag::default()
+		};
+
+		assert_eq!(
+			tag.encode(),
+			[
+				b"ID3\x04\x00\x00\x00\x00\x00\x20".as_ref(),
+				b"TPE1\x00\x00\x00\x06\x00\x00\x03Somne",
+				b"TLEN\x00\x00\x00\x06\x00\x00\x0360000",
+			].concat()
+		);
+	}
+
+
+	#[test]
+	fn test_strip() {
+		let audio = [0xff, 0xfb, 0x90, 0x64];
+
+		let mut v1 = vec![0; V1_SIZE];
+		v1[..3].copy_from_slice(b"TAG");
+
+		let data = [
+			Tag { artist: Some("[slider.kz]"), ..Tag::default() }
+				.encode()
+				.as_slice(),
+			&audio,
+			&v1,
+		].concat();
+
+		assert_eq!(strip(&data), audio);
+		assert_eq!(strip(&audio), audio);
+	}
+}
@@ -1,11 +1,13 @@
 use std::io::{self, Write};
 use std::fs::{File, OpenOptions};
 use std::ffi::OsStr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::os::unix::ffi::{OsStrExt, OsStringExt};
 
 
-pub fn create_unique<P1, P2>(path: &P1, extension: Option<&P2>) -> io::Result<File>
+/// Create a new file, appending a counter to the path if it already exists. Returns the
+/// created file and its path.
+pub fn create_unique<P1, P2>(path: &P1, extension: Option<&P2>) -> io::Result<(File, PathBuf)>
 where
 	P1: AsRef<Path> + ?Sized,
 	P2: AsRef<Path> + ?Sized,
@@ -20,7 +22,7 @@ where
 
 	let stem_size = path_buf.len();
 
-	let mut try_create = |count: usize| -> io::Result<File> {
+	let mut try_create = |count: usize| -> io::Result<(File, PathBuf)> {
 		if count > 0 {
 			write!(path_buf, " ({})", count)
 				.expect("write to vec failed");
@@ -42,7 +44,10 @@ where
 			::new()
 			.write(true)
 			.create_new(true)
-			.open(filename);
+			.open(filename)
+			.map(
+				|file| (file, PathBuf::from(filename))
+			);
 
 		if result.is_ok() {
 			log::debug!("created unique file: {:#?}", Path::new(filename));
@@ -69,3 +74,24 @@ where
 		};
 	}
 }
+
+
+/// Create a new file at the path, appending a counter before the extension if it already
+/// exists. Only the given extension is stripped, as the stem may contain dots.
+pub fn create_unique_at(path: &Path, extension: Option<&OsStr>) -> io::Result<(File, PathBuf)> {
+	let stem = extension
+		.and_then(
+			|extension| {
+				let path = path.as_os_str().as_bytes();
+				let stem = path.strip_suffix(extension.as_bytes())?;
+				let stem = stem.strip_suffix(b".")?;
+
+				Some(OsStr::from_bytes(stem))
+			}
+		);
+
+	match stem {
+		Some(stem) => create_unique(stem, extension),
+		None => create_unique(path, None::<&OsStr>),
+	}
+}
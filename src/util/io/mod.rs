@@ -1,7 +1,9 @@
 pub mod fs;
 
+use serde::Serialize;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct Progress {
 	pub total: Option<usize>,
 	pub completed: usize,
@@ -1,7 +1,11 @@
 pub mod bytes;
 pub mod error;
 pub mod future;
+pub mod hash;
+pub mod id3;
 pub mod io;
+pub mod mp3;
 pub mod mp4;
 pub mod mime;
 pub mod regex;
+pub mod serialize;
@@ -0,0 +1,446 @@
+use std::{
+	convert::TryInto,
+	io::{self, Read},
+};
+
+use serde::Serialize;
+
+use crate::track::Duration;
+use super::id3;
+
+
+// MP3 references:
+// http://www.mp3-tech.org/programmer/frame_header.html
+// https://www.codeproject.com/Articles/8295/MPEG-Audio-Frame-Header
+// http://gabriel.mp3-tech.org/mp3infotag.html
+
+
+const BITRATES_V1: [u16; 16] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0];
+const BITRATES_V2: [u16; 16] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0];
+
+const SAMPLE_RATES_V1: [u32; 3] = [44100, 48000, 32000];
+const SAMPLE_RATES_V2: [u32; 3] = [22050, 24000, 16000];
+const SAMPLE_RATES_V25: [u32; 3] = [11025, 12000, 8000];
+
+// How far to look for the first frame, after the ID3 tag.
+const SYNC_LIMIT: usize = 64 * 1024;
+
+// How much to read after the sync limit, to include the Xing header and the next frame.
+const FRAME_LIMIT: usize = 4 * 1024;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Metadata {
+	pub duration: Duration,
+	/// Average bitrate, in kbps.
+	pub bitrate: u16,
+}
+
+
+impl Metadata {
+	fn new(samples: u64, sample_rate: u32, bytes: u64) -> Option<Self> {
+		let seconds = samples as f64 / sample_rate as f64;
+
+		if seconds <= 0.0 {
+			return None;
+		}
+
+		Some(
+			Self {
+				duration: Duration::from_seconds(seconds.round() as u16),
+				bitrate: (bytes as f64 * 8.0 / seconds / 1000.0).round() as u16,
+			}
+		)
+	}
+}
+
+
+/// Extract the duration and bitrate of a Layer III stream, from the Xing or VBRI header if
+/// present, or else by scanning all frames.
+pub fn extract_metadata<R>(mut reader: R) -> io::Result<Metadata>
+where
+	R: Read
+{
+	let mut data = Vec::new();
+	reader.read_to_end(&mut data)?;
+
+	let data = id3::strip(&data);
+
+	let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+	let (offset, header) = find_first_frame(data)
+		.ok_or_else(|| invalid("no mp3 frame found"))?;
+
+	let stream = &data[offset..];
+
+	let metadata = xing(&header, stream, stream.len() as u64)
+		.or_else(|| vbri(&header, stream))
+		.or_else(|| scan(stream))
+		.ok_or_else(|| invalid("empty mp3 stream"))?;
+
+	log::debug!("mp3 metadata: {:#?}", metadata);
+
+	Ok(metadata)
+}
+
+
+/// Read the leading ID3 tag and enough of the stream to find the first frame, without
+/// reading the whole file.
+pub fn read_head<R>(mut reader: R) -> io::Result<Vec<u8>>
+where
+	R: Read
+{
+	let mut data = Vec::with_capacity(id3::HEADER_SIZE);
+
+	(&mut reader)
+		.take(id3::HEADER_SIZE as u64)
+		.read_to_end(&mut data)?;
+
+	let limit = id3::size(&data) + SYNC_LIMIT + FRAME_LIMIT;
+
+	reader
+		.take((limit - data.len()) as u64)
+		.read_to_end(&mut data)?;
+
+	Ok(data)
+}
+
+
+/// Estimate the duration and bitrate from the head of a file of the given length, as read
+/// by `read_head`. Streams without a Xing or VBRI header are assumed to be constant bitrate.
+pub fn estimate_metadata(head: &[u8], length: u64) -> io::Result<Metadata> {
+	let tag_size = id3::size(head);
+
+	let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+	let data = head
+		.get(tag_size..)
+		.ok_or_else(|| invalid("truncated id3 tag"))?;
+
+	let (offset, header) = find_first_frame(data)
+		.ok_or_else(|| invalid("no mp3 frame found"))?;
+
+	let stream = &data[offset..];
+	let stream_len = length.saturating_sub((tag_size + offset) as u64);
+
+	let metadata = xing(&header, stream, stream_len)
+		.or_else(|| vbri(&header, stream))
+		.or_else(
+			|| Metadata::new(
+				stream_len / header.size() as u64 * header.samples() as u64,
+				header.sample_rate,
+				stream_len,
+			)
+		)
+		.ok_or_else(|| invalid("empty mp3 stream"))?;
+
+	log::debug!("estimated mp3 metadata: {:#?}", metadata);
+
+	Ok(metadata)
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Version {
+	V1,
+	V2,
+	V25,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameHeader {
+	version: Version,
+	/// In kbps.
+	bitrate: u16,
+	sample_rate: u32,
+	padding: bool,
+	mono: bool,
+}
+
+
+impl FrameHeader {
+	fn parse(bytes: &[u8]) -> Option<Self> {
+		let bytes: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+
+		if bytes[0] != 0xff || bytes[1] & 0xe0 != 0xe0 {
+			return None;
+		}
+
+		let version = match (bytes[1] >> 3) & 0b11 {
+			0b00 => Version::V25,
+			0b10 => Version::V2,
+			0b11 => Version::V1,
+			_ => return None,
+		};
+
+		let is_layer3 = (bytes[1] >> 1) & 0b11 == 0b01;
+		if !is_layer3 {
+			return None;
+		}
+
+		let bitrate_index = (bytes[2] >> 4) as usize;
+		let bitrate = match version {
+			Version::V1 => BITRATES_V1[bitrate_index],
+			Version::V2 | Version::V25 => BITRATES_V2[bitrate_index],
+		};
+
+		if bitrate == 0 { // Free or invalid bitrate.
+			return None;
+		}
+
+		let sample_rate_index = ((bytes[2] >> 2) & 0b11) as usize;
+		let sample_rate = match version {
+			Version::V1 => SAMPLE_RATES_V1.get(sample_rate_index),
+			Version::V2 => SAMPLE_RATES_V2.get(sample_rate_index),
+			Version::V25 => SAMPLE_RATES_V25.get(sample_rate_index),
+		};
+
+		Some(
+			Self {
+				version,
+				bitrate,
+				sample_rate: *sample_rate?,
+				padding: (bytes[2] >> 1) & 1 == 1,
+				mono: bytes[3] >> 6 == 0b11,
+			}
+		)
+	}
+
+
+	fn samples(&self) -> u32 {
+		match self.version {
+			Version::V1 => 1152,
+			Version::V2 | Version::V25 => 576,
+		}
+	}
+
+
+	fn size(&self) -> usize {
+		let size = self.samples() / 8 * self.bitrate as u32 * 1000 / self.sample_rate;
+
+		size as usize + self.padding as usize
+	}
+
+
+	/// Offset of the Xing header, after the frame header and side information.
+	fn xing_offset(&self) -> usize {
+		let side_info = match (self.version, self.mono) {
+			(Version::V1, false) => 32,
+			(Version::V1, true) => 17,
+			(_, false) => 17,
+			(_, true) => 9,
+		};
+
+		4 + side_info
+	}
+}
+
+
+/// Find the first frame that is followed by another valid frame, to avoid false syncs.
+fn find_first_frame(data: &[u8]) -> Option<(usize, FrameHeader)> {
+	let limit = data.len().min(SYNC_LIMIT);
+
+	(0 .. limit)
+		.filter_map(
+			|offset| Some((offset, FrameHeader::parse(&data[offset..])?))
+		)
+		.find(
+			|(offset, header)| {
+				let next = offset + header.size();
+
+				next >= data.len() || FrameHeader::parse(&data[next..]).is_some()
+			}
+		)
+}
+
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+	let bytes = data.get(offset .. offset + 4)?;
+
+	Some(
+		u32::from_be_bytes(bytes.try_into().ok()?)
+	)
+}
+
+
+/// The stream length is used when the header has no byte count.
+fn xing(header: &FrameHeader, stream: &[u8], stream_len: u64) -> Option<Metadata> {
+	const FRAMES_FLAG: u32 = 0x1;
+	const BYTES_FLAG: u32 = 0x2;
+	const TOC_FLAG: u32 = 0x4;
+	const QUALITY_FLAG: u32 = 0x8;
+
+	let offset = header.xing_offset();
+
+	let tag = stream.get(offset .. offset + 4)?;
+	if tag != b"Xing" && tag != b"Info" {
+		return None;
+	}
+
+	let flags = read_u32(stream, offset + 4)?;
+
+	if flags & FRAMES_FLAG == 0 {
+		return None;
+	}
+
+	let mut position = offset + 8;
+
+	let frames = read_u32(stream, position)?;
+	position += 4;
+
+	let bytes = if flags & BYTES_FLAG != 0 {
+		position += 4;
+		read_u32(stream, position - 4)? as u64
+	}
+	else {
+		stream_len
+	};
+
+	if flags & TOC_FLAG != 0 {
+		position += 100;
+	}
+
+	if flags & QUALITY_FLAG != 0 {
+		position += 4;
+	}
+
+	let mut samples = frames as u64 * header.samples() as u64;
+
+	// The LAME header tells the encoder delay and padding, in samples.
+	if stream.get(position .. position + 4) == Some(b"LAME") {
+		if let Some(delay) = stream.get(position + 21 .. position + 24) {
+			let start = ((delay[0] as u64) << 4) | (delay[1] as u64 >> 4);
+			let end = ((delay[1] as u64 & 0x0f) << 8) | delay[2] as u64;
+
+			samples = samples.saturating_sub(start + end);
+		}
+	}
+
+	Metadata::new(samples, header.sample_rate, bytes)
+}
+
+
+fn vbri(header: &FrameHeader, stream: &[u8]) -> Option<Metadata> {
+	const OFFSET: usize = 4 + 32;
+
+	if stream.get(OFFSET .. OFFSET + 4)? != b"VBRI" {
+		return None;
+	}
+
+	let bytes = read_u32(stream, OFFSET + 10)?;
+	let frames = read_u32(stream, OFFSET + 14)?;
+
+	Metadata::new(
+		frames as u64 * header.samples() as u64,
+		header.sample_rate,
+		bytes as u64,
+	)
+}
+
+
+fn scan(stream: &[u8]) -> Option<Metadata> {
+	let mut offset = 0;
+	let mut samples: u64 = 0;
+	let mut sample_rate = None;
+
+	while let Some(header) = FrameHeader::parse(&stream[offset..]) {
+		let size = header.size();
+
+		if offset + size > stream.len() {
+			break;
+		}
+
+		samples += header.samples() as u64;
+		sample_rate.get_or_insert(header.sample_rate);
+		offset += size;
+	}
+
+	Metadata::new(samples, sample_rate?, offset as u64)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// MPEG 1 Layer III, 128 kbps, 44100 Hz, stereo.
+	const HEADER: [u8; 4] = [0xff, 0xfb, 0x90, 0x00];
+	const FRAME_SIZE: usize = 417;
+
+
+	fn frames(count: usize) -> Vec<u8> {
+		let mut frame = vec![0; FRAME_SIZE];
+		frame[..4].copy_from_slice(&HEADER);
+
+		frame.repeat(count)
+	}
+
+
+	#[test]
+	fn test_scan() {
+		let data = frames(383);
+
+		assert_eq!(
+			extract_metadata(data.as_slice()).expect("failed to extract metadata"),
+			Metadata {
+				duration: Duration::from_seconds(10),
+				bitrate: 128,
+			}
+		);
+	}
+
+
+	#[test]
+	fn test_xing() {
+		let mut data = frames(4);
+
+		data[36..40].copy_from_slice(b"Xing");
+		data[40..44].copy_from_slice(&3u32.to_be_bytes());
+		data[44..48].copy_from_slice(&1000u32.to_be_bytes());
+		data[48..52].copy_from_slice(&(1000 * FRAME_SIZE as u32).to_be_bytes());
+
+		let data = [
+			crate::util::id3::Tag { artist: Some("Somne"), ..Default::default() }
+				.encode(),
+			data,
+		].concat();
+
+		assert_eq!(
+			extract_metadata(data.as_slice()).expect("failed to extract metadata"),
+			Metadata {
+				duration: Duration::from_seconds(26),
+				bitrate: 128,
+			}
+		);
+	}
+
+
+	#[test]
+	fn test_estimate() {
+		let data = [
+			crate::util::id3::Tag { artist: Some("Somne"), ..Default::default() }
+				.encode(),
+			frames(383),
+		].concat();
+
+		let head = read_head(data.as_slice()).expect("failed to read head");
+
+		assert_eq!(head.len(), data.len().min(id3::size(&data) + SYNC_LIMIT + FRAME_LIMIT));
+
+		assert_eq!(
+			estimate_metadata(&head[.. 4096], data.len() as u64).expect("failed to estimate metadata"),
+			Metadata {
+				duration: Duration::from_seconds(10),
+				bitrate: 128,
+			}
+		);
+	}
+
+
+	#[test]
+	fn test_invalid() {
+		assert!(extract_metadata([0u8; 1024].as_ref()).is_err());
+	}
+}
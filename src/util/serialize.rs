@@ -0,0 +1,14 @@
+use std::fmt::Display;
+
+use serde::Serializer;
+
+
+/// Serialize a value as its display string. Useful for errors, which usually don't
+/// implement `Serialize`.
+pub fn display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: Display,
+	S: Serializer,
+{
+	serializer.collect_str(value)
+}